@@ -0,0 +1,89 @@
+//! Micro-benchmark of `Trace::calculate_state` on a wide net.
+//!
+//! Run it with `cargo bench --bench calculate_state`. It prints the pin lookups made per tick,
+//! compared to a pass writing back to every pin of the trace, and the time taken per tick.
+
+use std::{
+    cell::Cell,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use virt_ic::{
+    board::{Resolution, Trace},
+    chip::{Chip, ChipRunner, Pin, PinId, PinType},
+    utilities::Storage,
+    State,
+};
+
+/// Amount of pins on the benchmarked trace, one of them driving the others
+const FANOUT: usize = 64;
+const TICKS: u32 = 100_000;
+/// Id of the only pin of a probe
+const PIN: PinId = 1;
+
+/// A single pin chip counting how many times its pin gets looked up
+#[derive(Debug, Clone)]
+struct Probe {
+    pin: Pin,
+    lookups: Cell<usize>,
+}
+
+impl Chip for Probe {
+    fn list_pins(&self) -> Vec<(PinId, &Pin)> {
+        vec![(PIN, &self.pin)]
+    }
+
+    fn get_pin(&self, _: PinId) -> Option<&Pin> {
+        self.lookups.set(self.lookups.get() + 1);
+        Some(&self.pin)
+    }
+
+    fn get_pin_mut(&mut self, _: PinId) -> Option<&mut Pin> {
+        self.lookups.set(self.lookups.get() + 1);
+        Some(&mut self.pin)
+    }
+}
+
+impl ChipRunner for Probe {
+    fn run(&mut self, _: Duration) {}
+}
+
+fn main() {
+    let mut chips = Storage::new();
+    let mut trace = Trace::new();
+    for index in 0..FANOUT {
+        let (pin_type, state) = match index {
+            0 => (PinType::Output, State::High),
+            // a few idle bus pins, like a memory that isn't selected
+            _ if index % 8 == 0 => (PinType::Floating, State::Undefined),
+            _ => (PinType::Input, State::Undefined),
+        };
+        let probe = chips.add(Probe {
+            pin: Pin { pin_type, state },
+            lookups: Cell::new(0),
+        });
+        trace.connect(probe, PIN);
+    }
+    let resolution = Resolution::default();
+
+    trace.calculate_state(&mut chips, &resolution);
+    let lookups: usize = chips
+        .as_vec()
+        .iter()
+        .map(|(_, probe)| probe.lookups.get())
+        .sum();
+    println!(
+        "pin lookups per tick: {lookups} (a pass writing back to every pin makes {})",
+        FANOUT * 2
+    );
+
+    let start = Instant::now();
+    for _ in 0..TICKS {
+        trace.calculate_state(black_box(&mut chips), &resolution);
+    }
+    println!(
+        "calculate_state on {FANOUT} pins: {:?} per tick",
+        start.elapsed() / TICKS
+    );
+}
@@ -0,0 +1,55 @@
+use std::time::Duration;
+
+use virt_ic::{
+    chip::{registers::DemuxAddressLatch, ChipBuilder, ChipRunner, Pin},
+    State,
+};
+
+fn main() {
+    let mut demux = DemuxAddressLatch::build();
+    demux.vcc.state = State::High;
+
+    // address phase
+    demux.ale.state = State::High;
+    Pin::write(
+        &mut [
+            &mut demux.ad0,
+            &mut demux.ad1,
+            &mut demux.ad2,
+            &mut demux.ad3,
+            &mut demux.ad4,
+            &mut demux.ad5,
+            &mut demux.ad6,
+            &mut demux.ad7,
+        ],
+        0x42,
+    );
+    demux.run(Duration::from_millis(1));
+
+    // data phase
+    demux.ale.state = State::Low;
+    Pin::write(
+        &mut [
+            &mut demux.ad0,
+            &mut demux.ad1,
+            &mut demux.ad2,
+            &mut demux.ad3,
+            &mut demux.ad4,
+            &mut demux.ad5,
+            &mut demux.ad6,
+            &mut demux.ad7,
+        ],
+        0xA5,
+    );
+    demux.run(Duration::from_millis(1));
+
+    println!(
+        "address: 0x{:02X}, data: 0x{:02X}",
+        Pin::read(&[
+            &demux.a0, &demux.a1, &demux.a2, &demux.a3, &demux.a4, &demux.a5, &demux.a6, &demux.a7,
+        ]),
+        Pin::read(&[
+            &demux.d0, &demux.d1, &demux.d2, &demux.d3, &demux.d4, &demux.d5, &demux.d6, &demux.d7,
+        ])
+    );
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use custom_chip::MyCustomChip;
+use virt_ic::{
+    board::Board,
+    chip::{gates::AndGate, generators::Generator, Chip, ChipBuilder, DynChip},
+};
+
+mod custom_chip {
+    use std::time::Duration;
+
+    use virt_ic::{
+        chip::{ChipBuilder, ChipRunner, Pin, PinId, PinType},
+        generate_chip, State,
+    };
+
+    #[derive(Debug, Clone)]
+    pub struct MyCustomChip {
+        pub vcc: Pin,
+        pub gnd: Pin,
+        pub a: Pin,
+        pub na: Pin,
+    }
+
+    impl MyCustomChip {
+        pub const VCC: PinId = 1;
+        pub const GND: PinId = 2;
+        pub const A: PinId = 3;
+        pub const NA: PinId = 4;
+    }
+
+    impl ChipBuilder<MyCustomChip> for MyCustomChip {
+        fn build() -> MyCustomChip {
+            MyCustomChip {
+                vcc: Pin::from(PinType::Input),
+                gnd: Pin::from(PinType::Output),
+                a: Pin::from(PinType::Input),
+                na: Pin::from(PinType::Output),
+            }
+        }
+    }
+
+    generate_chip!(
+        MyCustomChip,
+        vcc: MyCustomChip::VCC,
+        gnd: MyCustomChip::GND,
+        a: MyCustomChip::A,
+        na: MyCustomChip::NA
+    );
+
+    impl ChipRunner for MyCustomChip {
+        fn run(&mut self, _: Duration) {
+            if self.vcc.state.as_logic(3.3) == State::High {
+                self.gnd.state = State::Low;
+                self.na.state = State::from(!bool::from(self.a.state.as_logic(3.3)));
+            }
+        }
+    }
+}
+
+fn main() {
+    // a board of boxed chips accepts any chip, no wrapper enum needed
+    let mut board: Board<Box<dyn DynChip>> = Board::new();
+    let and_gate = board.register_chip(Box::new(AndGate::build()));
+    let vcc = board.register_chip(Box::new(Generator::build()));
+    let custom = board.register_chip(Box::new(MyCustomChip::build()));
+
+    board.connect_many(&[
+        (vcc, Generator::OUT),
+        (and_gate, AndGate::VCC),
+        (and_gate, AndGate::B),
+        (custom, MyCustomChip::VCC),
+    ]);
+    board.connect(custom, MyCustomChip::NA, and_gate, AndGate::A);
+
+    // simulate the board for 10ms
+    board.run_during(Duration::from_millis(10), Duration::from_millis(1));
+
+    // check the results
+    if let Some(chip) = board.get_chip(&and_gate) {
+        println!(
+            "A={:?}, \tB={:?}, \tA&B={:?}",
+            chip.get_pin_state(AndGate::A),
+            chip.get_pin_state(AndGate::B),
+            chip.get_pin_state(AndGate::AB)
+        );
+    }
+}
@@ -65,14 +65,14 @@ mod custom_chip {
 #[derive(Debug, Clone)]
 pub enum CustomChipSet {
     MyCustomChip(MyCustomChip),
-    Builtin(ChipSet),
+    Builtin(Box<ChipSet>),
 }
 
 impl_chip_type!(CustomChipSet: (MyCustomChip, Builtin));
 
 impl From<ChipSet> for CustomChipSet {
     fn from(value: ChipSet) -> Self {
-        CustomChipSet::Builtin(value)
+        CustomChipSet::Builtin(Box::new(value))
     }
 }
 
@@ -80,7 +80,7 @@ fn main() {
     // create a new board
     let mut board: Board<CustomChipSet> = Board::new();
     // place an AND gate to the board
-    let and_gate = board.register_chip(AndGate::build().into());
+    let and_gate = board.register_chip(ChipSet::from(AndGate::build()).into());
     // also place a generator
     let vcc = board.register_chip(ChipSet::from(Generator::build()).into());
     let gnd = board
@@ -113,9 +113,9 @@ fn main() {
     if let Some(chip) = board.get_chip(&and_gate) {
         println!(
             "A={:?}, \tB={:?}, \tA&B={:?}",
-            chip.get_pin(AndGate::A).map(|p| p.state),
-            chip.get_pin(AndGate::B).map(|p| p.state),
-            chip.get_pin(AndGate::AB).map(|p| p.state)
+            chip.get_pin_state(AndGate::A),
+            chip.get_pin_state(AndGate::B),
+            chip.get_pin_state(AndGate::AB)
         );
     }
 
@@ -134,9 +134,12 @@ fn main() {
     if let Some(chip) = board.get_chip(&and_gate) {
         println!(
             "A={:?}, \tB={:?}, \tA&B={:?}",
-            chip.get_pin(AndGate::A).map(|p| p.state),
-            chip.get_pin(AndGate::B).map(|p| p.state),
-            chip.get_pin(AndGate::AB).map(|p| p.state)
+            chip.get_pin_state(AndGate::A),
+            chip.get_pin_state(AndGate::B),
+            chip.get_pin_state(AndGate::AB)
         );
     }
+
+    // print a summary of the board
+    println!("{}", board.describe());
 }
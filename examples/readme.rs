@@ -9,7 +9,7 @@ fn main() {
     // create a new board
     let mut board: Board<ChipSet> = Board::new();
     // place an AND gate to the board
-    let and_gate = board.register_chip(AndGate::build());
+    let and_gate = board.register_chip(AndGate::build().into());
     // also place a generator
     let vcc = board.register_chip(Generator::build().into());
     let gnd = board.register_chip(Generator::build().with_state(virt_ic::State::Low).into());
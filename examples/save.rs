@@ -1,6 +1,5 @@
 use std::time::Duration;
 
-use ron::ser::PrettyConfig;
 use virt_ic::{
     board::{Board, Trace},
     chip::{gates::AndGate, generators::Generator, ChipBuilder, ChipSet},
@@ -9,7 +8,7 @@ use virt_ic::{
 fn main() {
     let mut board: Board<ChipSet> = Board::new();
 
-    let and_gate = board.register_chip(AndGate::build());
+    let and_gate = board.register_chip(AndGate::build().into());
 
     let vcc = board.register_chip(Generator::build().into());
 
@@ -25,11 +24,12 @@ fn main() {
 
     board.run(Duration::from_millis(10));
 
-    let saved = ron::ser::to_string_pretty(&board, PrettyConfig::default()).unwrap();
+    let mut saved = Vec::new();
+    board.save_to_writer(&mut saved).unwrap();
 
-    println!("{}", saved);
+    println!("{}", String::from_utf8_lossy(&saved));
 
-    let mut board2: Board<ChipSet> = ron::de::from_str(&saved).unwrap();
+    let mut board2: Board<ChipSet> = Board::load_from_reader(saved.as_slice()).unwrap();
 
     if let Some(t) = board2.get_trace_mut(&trace) {
         t.disconnect(and_gate, AndGate::A);
@@ -13,16 +13,16 @@ use virt_ic::{
 fn main() {
     let mut board: Board<ChipSet> = Board::new();
 
-    let seg_dec = board.register_chip(SevenSegmentsDecoder::build());
+    let seg_dec = board.register_chip(SevenSegmentsDecoder::build().into());
 
-    let display = board.register_chip(SegmentDisplay::build());
+    let display = board.register_chip(SegmentDisplay::build().into());
 
     let gen = board.register_chip(Generator::build().into());
 
-    let btn_a = board.register_chip(Button::build());
-    let btn_b = board.register_chip(Button::build());
-    let btn_c = board.register_chip(Button::build());
-    let btn_d = board.register_chip(Button::build());
+    let btn_a = board.register_chip(Button::build().into());
+    let btn_b = board.register_chip(Button::build().into());
+    let btn_c = board.register_chip(Button::build().into());
+    let btn_d = board.register_chip(Button::build().into());
 
     board.register_trace(Trace::from(vec![
         (gen, Generator::OUT),
@@ -86,7 +86,7 @@ fn main() {
     board.run_realtime(Duration::from_millis(100));
 
     if let Some(ChipSet::SegmentDisplay(display)) = board.get_chip(&display) {
-        println!("{}:\n{}", display.as_char(), display.to_string());
+        println!("{}:\n{}", display.as_char(), display);
     }
 
     if let Some(ChipSet::Button(a)) = board.get_chip_mut(&btn_a) {
@@ -96,7 +96,7 @@ fn main() {
     board.run_realtime(Duration::from_millis(100));
 
     if let Some(ChipSet::SegmentDisplay(display)) = board.get_chip(&display) {
-        println!("{}:\n{}", display.as_char(), display.to_string());
+        println!("{}:\n{}", display.as_char(), display);
     }
 
     if let Some(ChipSet::Button(a)) = board.get_chip_mut(&btn_a) {
@@ -109,7 +109,7 @@ fn main() {
     board.run_realtime(Duration::from_millis(100));
 
     if let Some(ChipSet::SegmentDisplay(display)) = board.get_chip(&display) {
-        println!("{}:\n{}", display.as_char(), display.to_string());
+        println!("{}:\n{}", display.as_char(), display);
     }
     if let Some(ChipSet::Button(b)) = board.get_chip_mut(&btn_b) {
         b.release();
@@ -121,7 +121,7 @@ fn main() {
     board.run_realtime(Duration::from_millis(100));
 
     if let Some(ChipSet::SegmentDisplay(display)) = board.get_chip(&display) {
-        println!("{}:\n{}", display.as_char(), display.to_string());
+        println!("{}:\n{}", display.as_char(), display);
     }
 
     if let Some(ChipSet::Button(c)) = board.get_chip_mut(&btn_c) {
@@ -134,6 +134,6 @@ fn main() {
     board.run_realtime(Duration::from_millis(100));
 
     if let Some(ChipSet::SegmentDisplay(display)) = board.get_chip(&display) {
-        println!("{}:\n{}", display.as_char(), display.to_string());
+        println!("{}:\n{}", display.as_char(), display);
     }
 }
@@ -23,12 +23,12 @@ use virt_ic::{
 fn main() {
     let mut board: Board<ChipSet> = Board::new();
 
-    let nand = board.register_chip(NandGate::build());
+    let nand = board.register_chip(NandGate::build().into());
 
     let gen = board.register_chip(Generator::build().into());
 
-    let set_btn = board.register_chip(Button::build());
-    let reset_btn = board.register_chip(Button::build());
+    let set_btn = board.register_chip(Button::build().into());
+    let reset_btn = board.register_chip(Button::build().into());
 
     board.register_trace(Trace::from(vec![
         (gen, Generator::OUT),
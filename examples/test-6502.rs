@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use virt_ic::{
-    board::{Board, Trace},
+    board::{Board, MemoryBus, Trace},
     chip::{
         clocks::Clock,
         cpu::{nes6502, Nes6502},
@@ -28,86 +28,134 @@ use virt_ic::{
 fn main() {
     let mut board: Board<ChipSet> = Board::new();
 
-    // assemble a 6502 program
-    let mut prg = nes6502::Assembler::assemble(&[
+    // assemble a 6502 program along with the reset vector
+    let prg = nes6502::Assembler::assemble_items(&[
         // first do some addition and substraction
-        nes6502::Opcode::CLC,
-        nes6502::Opcode::LDA(nes6502::AddressingMode::Immediate(0x5A)),
-        nes6502::Opcode::ADC(nes6502::AddressingMode::Immediate(0xFF)),
-        nes6502::Opcode::SEC,
-        nes6502::Opcode::SBC(nes6502::AddressingMode::Immediate(0xFF)),
+        nes6502::Opcode::CLC.into(),
+        nes6502::Opcode::LDA(nes6502::AddressingMode::Immediate(0x5A)).into(),
+        nes6502::Opcode::ADC(nes6502::AddressingMode::Immediate(0xFF)).into(),
+        nes6502::Opcode::SEC.into(),
+        nes6502::Opcode::SBC(nes6502::AddressingMode::Immediate(0xFF)).into(),
         // then setup a loop that'll fill the first 10 bytes of RAM with the content of the last byte in RAM
-        nes6502::Opcode::LDX(nes6502::AddressingMode::Immediate(0x0A)),
-        nes6502::Opcode::LDA(nes6502::AddressingMode::ZeroPage(0xFF)),
-        nes6502::Opcode::STA(nes6502::AddressingMode::ZeroPageIndexedX(0x00)),
-        nes6502::Opcode::DEX,
-        nes6502::Opcode::BPL(-5),
+        nes6502::Opcode::LDX(nes6502::AddressingMode::Immediate(0x0A)).into(),
+        nes6502::Opcode::LDA(nes6502::AddressingMode::ZeroPage(0xFF)).into(),
+        nes6502::Opcode::STA(nes6502::AddressingMode::ZeroPageIndexedX(0x00)).into(),
+        nes6502::Opcode::DEX.into(),
+        nes6502::Opcode::BPL(-5).into(),
         // infinite loop to halt the program
-        nes6502::Opcode::BMI(-2),
+        nes6502::Opcode::BMI(-2).into(),
+        // write 6502's reset vector
+        nes6502::Item::Org(0xFC),
+        nes6502::Item::Word(0x8000),
     ])
     .unwrap();
-    // resize assembled program and write 6502's reset vector
-    prg.resize(256, 0);
-    prg[0xFC] = 0x80;
-    prg[0xFD] = 0x00;
 
     let rom = board.register_chip(Rom256B::build().set_data(prg.as_slice()).into());
-    let ram = board.register_chip(Ram256B::build());
+    let ram = board.register_chip(Ram256B::build().into());
 
-    let not = board.register_chip(NotGate::build());
+    let not = board.register_chip(NotGate::build().into());
 
     let vcc = board.register_chip(Generator::build().into());
     let clock = board.register_chip(Clock::build().with_frequency(50.0).into());
 
-    let cpu = board.register_chip(Nes6502::build());
+    let cpu = board.register_chip(Nes6502::build().into());
 
-    board.register_trace(Trace::from(vec![
-        (vcc, Generator::OUT),
-        (clock, Clock::VCC),
-        (cpu, Nes6502::VCC),
-        (not, NotGate::VCC),
-        (rom, Rom256B::VCC),
-        (ram, Ram256B::VCC),
-    ]));
+    board.power_rail(
+        vcc,
+        &[
+            (clock, Clock::VCC),
+            (cpu, Nes6502::VCC),
+            (not, NotGate::VCC),
+            (rom, Rom256B::VCC),
+            (ram, Ram256B::VCC),
+        ],
+    );
 
     // connect address and data lines to ROM
-    board.connect(cpu, Nes6502::A0, rom, Rom256B::A0);
-    board.connect(cpu, Nes6502::A1, rom, Rom256B::A1);
-    board.connect(cpu, Nes6502::A2, rom, Rom256B::A2);
-    board.connect(cpu, Nes6502::A3, rom, Rom256B::A3);
-    board.connect(cpu, Nes6502::A4, rom, Rom256B::A4);
-    board.connect(cpu, Nes6502::A5, rom, Rom256B::A5);
-    board.connect(cpu, Nes6502::A6, rom, Rom256B::A6);
-    board.connect(cpu, Nes6502::A7, rom, Rom256B::A7);
-    board.connect(cpu, Nes6502::D0, rom, Rom256B::IO0);
-    board.connect(cpu, Nes6502::D1, rom, Rom256B::IO1);
-    board.connect(cpu, Nes6502::D2, rom, Rom256B::IO2);
-    board.connect(cpu, Nes6502::D3, rom, Rom256B::IO3);
-    board.connect(cpu, Nes6502::D4, rom, Rom256B::IO4);
-    board.connect(cpu, Nes6502::D5, rom, Rom256B::IO5);
-    board.connect(cpu, Nes6502::D6, rom, Rom256B::IO6);
-    board.connect(cpu, Nes6502::D7, rom, Rom256B::IO7);
+    let cpu_bus = MemoryBus {
+        address: &[
+            Nes6502::A0,
+            Nes6502::A1,
+            Nes6502::A2,
+            Nes6502::A3,
+            Nes6502::A4,
+            Nes6502::A5,
+            Nes6502::A6,
+            Nes6502::A7,
+        ],
+        data: &[
+            Nes6502::D0,
+            Nes6502::D1,
+            Nes6502::D2,
+            Nes6502::D3,
+            Nes6502::D4,
+            Nes6502::D5,
+            Nes6502::D6,
+            Nes6502::D7,
+        ],
+        ..Default::default()
+    };
+    board.connect_bus_to_memory(
+        cpu,
+        &cpu_bus,
+        rom,
+        &MemoryBus {
+            address: &[
+                Rom256B::A0,
+                Rom256B::A1,
+                Rom256B::A2,
+                Rom256B::A3,
+                Rom256B::A4,
+                Rom256B::A5,
+                Rom256B::A6,
+                Rom256B::A7,
+            ],
+            data: &[
+                Rom256B::IO0,
+                Rom256B::IO1,
+                Rom256B::IO2,
+                Rom256B::IO3,
+                Rom256B::IO4,
+                Rom256B::IO5,
+                Rom256B::IO6,
+                Rom256B::IO7,
+            ],
+            ..Default::default()
+        },
+    );
     // connect NOT A15 to ROM's CS
     board.connect(cpu, Nes6502::A15, not, NotGate::A);
     board.connect(not, NotGate::NOT_A, rom, Rom256B::CS);
 
     // connect address and data lines to RAM
-    board.connect(cpu, Nes6502::A0, ram, Ram256B::A0);
-    board.connect(cpu, Nes6502::A1, ram, Ram256B::A1);
-    board.connect(cpu, Nes6502::A2, ram, Ram256B::A2);
-    board.connect(cpu, Nes6502::A3, ram, Ram256B::A3);
-    board.connect(cpu, Nes6502::A4, ram, Ram256B::A4);
-    board.connect(cpu, Nes6502::A5, ram, Ram256B::A5);
-    board.connect(cpu, Nes6502::A6, ram, Ram256B::A6);
-    board.connect(cpu, Nes6502::A7, ram, Ram256B::A7);
-    board.connect(cpu, Nes6502::D0, ram, Ram256B::IO0);
-    board.connect(cpu, Nes6502::D1, ram, Ram256B::IO1);
-    board.connect(cpu, Nes6502::D2, ram, Ram256B::IO2);
-    board.connect(cpu, Nes6502::D3, ram, Ram256B::IO3);
-    board.connect(cpu, Nes6502::D4, ram, Ram256B::IO4);
-    board.connect(cpu, Nes6502::D5, ram, Ram256B::IO5);
-    board.connect(cpu, Nes6502::D6, ram, Ram256B::IO6);
-    board.connect(cpu, Nes6502::D7, ram, Ram256B::IO7);
+    board.connect_bus_to_memory(
+        cpu,
+        &cpu_bus,
+        ram,
+        &MemoryBus {
+            address: &[
+                Ram256B::A0,
+                Ram256B::A1,
+                Ram256B::A2,
+                Ram256B::A3,
+                Ram256B::A4,
+                Ram256B::A5,
+                Ram256B::A6,
+                Ram256B::A7,
+            ],
+            data: &[
+                Ram256B::IO0,
+                Ram256B::IO1,
+                Ram256B::IO2,
+                Ram256B::IO3,
+                Ram256B::IO4,
+                Ram256B::IO5,
+                Ram256B::IO6,
+                Ram256B::IO7,
+            ],
+            ..Default::default()
+        },
+    );
     // connect A15 to RAM's CS
     board.connect(cpu, Nes6502::A15, ram, Ram256B::CS);
 
@@ -129,11 +177,11 @@ fn main() {
 
     if let Some(ChipSet::Rom256B(rom)) = board.get_chip(&rom) {
         println!("ROM CONTENT");
-        println!("{}", rom.to_string());
+        println!("{}", rom);
     }
     if let Some(ChipSet::Ram256B(ram)) = board.get_chip(&ram) {
         println!("RAM CONTENT");
-        println!("{}", ram.to_string());
+        println!("{}", ram);
     }
 
     // run the simulation at 50Hz for 3.2 seconds
@@ -141,12 +189,12 @@ fn main() {
         board.run_realtime(Duration::from_millis(20));
 
         if let Some(ChipSet::Nes6502(cpu)) = board.get_chip(&cpu) {
-            println!("{}", cpu.to_string());
+            println!("{}", cpu);
         }
     }
 
     if let Some(ChipSet::Ram256B(ram)) = board.get_chip(&ram) {
         println!("RAM CONTENT");
-        println!("{}", ram.to_string());
+        println!("{}", ram);
     }
 }
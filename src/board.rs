@@ -1,16 +1,43 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    chip::{Chip, PinId, PinType},
+    chip::{generators::Generator, Chip, ChipSet, PinId, PinRole, PinType},
     utilities::{Id, Storage},
-    State,
+    State, ANALOG_EPSILON, DEFAULT_RAILS,
 };
 
+/// Version of the format written by `Board::save_to_writer`
+pub const SAVE_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board<C: Chip> {
     chips: Storage<C>,
     traces: Storage<Trace<C>>,
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    forced_pins: Vec<(Id<C>, PinId, State)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    rails: Option<RangeInclusive<f32>>,
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    pulls: Vec<(Id<Trace<C>>, State)>,
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    policies: Vec<(Id<Trace<C>>, ResolutionPolicy)>,
+    /// layout of the chips, for the user interfaces drawing the board
+    #[cfg_attr(feature = "serde", serde(default = "Vec::new"))]
+    positions: Vec<(Id<C>, (f32, f32))>,
+    /// number of output pins that changed state during the last run, when measured
+    #[cfg_attr(feature = "serde", serde(skip))]
+    switching: Option<usize>,
+    /// whether the constant sources have run since the chips were last modified
+    #[cfg_attr(feature = "serde", serde(skip))]
+    sources_settled: bool,
+    /// pins of each chip that are connected to a trace, rebuilt once the wiring changes
+    #[cfg_attr(feature = "serde", serde(skip, default = "Option::default"))]
+    wired_pins: Option<Vec<(Id<C>, Vec<PinId>)>>,
 }
 
 impl<C> Board<C>
@@ -21,11 +48,109 @@ where
         Board {
             chips: Storage::default(),
             traces: Storage::default(),
+            forced_pins: Vec::new(),
+            rails: None,
+            pulls: Vec::new(),
+            policies: Vec::new(),
+            positions: Vec::new(),
+            switching: None,
+            sources_settled: false,
+            wired_pins: None,
         }
     }
 
+    /// Run the circuit once: calculate the state of every trace, then run every chip.
+    ///
+    /// The board is borrowed mutably for the whole step, so neither the chips nor the caller can
+    /// reach it while it runs, which rules out nested runs at compile time. To look at the board
+    /// between steps, use `run_until` whose condition gets a shared reference to it.
     pub fn run(&mut self, tick_duration: Duration) {
+        self.step(tick_duration, None);
+    }
+
+    /// Run the circuit once like `run`, measuring the time spent calculating the traces and
+    /// running each type of chip, to find out what slows down a simulation
+    pub fn run_profiled(&mut self, step: Duration) -> ProfileReport {
+        let mut report = ProfileReport::default();
+        self.step(step, Some(&mut report));
+        report
+    }
+
+    fn step(&mut self, tick_duration: Duration, mut report: Option<&mut ProfileReport>) {
+        // only the wired inputs get written by the traces, so they're the only ones to reset
+        let wired_pins = match self.wired_pins.take() {
+            Some(wired_pins) => wired_pins,
+            None => self.collect_wired_pins(),
+        };
+        for (chip_id, pins) in wired_pins.iter() {
+            let Some(chip) = self.chips.get_mut(chip_id) else {
+                continue;
+            };
+            for pin_id in pins {
+                if let Some(pin) = chip.get_pin_mut(*pin_id) {
+                    if matches!(pin.pin_type, PinType::Input) {
+                        pin.state = State::Undefined;
+                    }
+                }
+            }
+        }
+        self.wired_pins = Some(wired_pins);
+
+        // forced pins are applied before the traces so that forced outputs drive their nets,
+        // and after them so that forced inputs ignore their nets
+        self.apply_forced_pins();
+        let rails = self.rails();
+        let start = report.is_some().then(Instant::now);
+        for (id, trace) in self.traces.as_mut_vec() {
+            let resolution = Resolution {
+                rails: rails.clone(),
+                pull: pull_state(&self.pulls, id),
+                policy: trace_policy(&self.policies, id),
+            };
+            trace.calculate_state(&mut self.chips, &resolution);
+        }
+        if let (Some(report), Some(start)) = (report.as_mut(), start) {
+            report.traces += start.elapsed();
+        }
+        self.apply_forced_pins();
+
+        let mut switching = self.switching.map(|_| 0);
         for (_id, chip) in self.chips.as_mut_vec() {
+            // the outputs of constant sources can't change until the chips get modified
+            if self.sources_settled && chip.is_constant_source() {
+                continue;
+            }
+            // the outputs are only recorded when the switching gets measured
+            let outputs: Option<Vec<_>> = switching.is_some().then(|| {
+                chip.list_pins()
+                    .into_iter()
+                    .filter(|(_, pin)| matches!(pin.pin_type, PinType::Output))
+                    .map(|(pin_id, pin)| (pin_id, pin.state))
+                    .collect()
+            });
+            let start = report.is_some().then(Instant::now);
+            chip.run(tick_duration);
+            if let (Some(report), Some(start)) = (report.as_mut(), start) {
+                *report.chips.entry(chip.chip_name()).or_default() += start.elapsed();
+            }
+            if let (Some(switching), Some(outputs)) = (switching.as_mut(), outputs) {
+                *switching += outputs
+                    .into_iter()
+                    .filter(|(pin_id, state)| {
+                        !chip.get_pin_state(*pin_id).approx_eq(state, ANALOG_EPSILON)
+                    })
+                    .count();
+            }
+        }
+        self.switching = switching;
+        self.sources_settled = true;
+    }
+
+    /// Reset every input pin and list the pins of each chip that are connected to a trace
+    fn collect_wired_pins(&mut self) -> Vec<(Id<C>, Vec<PinId>)> {
+        let traces = self.traces.as_vec();
+        let mut wired_pins = vec![];
+        for (chip_id, chip) in self.chips.as_mut_vec() {
             let mut pins_to_reset = vec![];
             for (pin_id, pin) in chip.list_pins() {
                 if matches!(pin.pin_type, PinType::Input) {
@@ -37,14 +162,129 @@ where
                     pin.state = State::Undefined
                 }
             }
+            let mut pins: Vec<_> = traces
+                .iter()
+                .flat_map(|(_, trace)| trace.pins.iter())
+                .filter(|(id, _)| *id == chip_id)
+                .map(|(_, pin_id)| *pin_id)
+                .collect();
+            pins.sort_unstable();
+            pins.dedup();
+            wired_pins.push((chip_id, pins));
         }
+        wired_pins
+    }
 
-        for (_id, trace) in self.traces.as_mut_vec() {
-            trace.calculate_state(&mut self.chips);
-        }
+    /// Get the number of output pins that changed state during the last run, once enabled with
+    /// `enable_switching_stats`.
+    /// Each switching output charges or discharges its trace, so this is a rough estimate of
+    /// the dynamic power drawn by the board.
+    pub fn estimate_switching(&self) -> Option<usize> {
+        self.switching
+    }
 
+    /// Measure the switching outputs at each run, to be read with `estimate_switching`.
+    /// Disabled by default, as the outputs of every chip need to be recorded before it runs.
+    pub fn enable_switching_stats(&mut self) {
+        self.switching.get_or_insert(0);
+    }
+
+    /// Stop measuring the switching outputs
+    pub fn disable_switching_stats(&mut self) {
+        self.switching = None;
+    }
+
+    /// Bring every chip of the board back to its power-on state
+    pub fn reset_all(&mut self) {
+        self.sources_settled = false;
         for (_id, chip) in self.chips.as_mut_vec() {
-            chip.run(tick_duration);
+            chip.reset();
+        }
+    }
+
+    fn apply_forced_pins(&mut self) {
+        for (chip_id, pin_id, state) in self.forced_pins.iter() {
+            if let Some(chip) = self.chips.get_mut(chip_id) {
+                chip.set_pin_state(*pin_id, *state);
+            }
+        }
+    }
+
+    /// Set the supply rails that analog states get clamped to on the traces,
+    /// defaults to `DEFAULT_RAILS`
+    ///
+    /// # Panics
+    /// Panics if the rails are inverted or not a number
+    pub fn set_rails(&mut self, rails: RangeInclusive<f32>) {
+        assert!(
+            rails.start() <= rails.end(),
+            "invalid supply rails {rails:?}, the start must be lower than the end"
+        );
+        self.rails = Some(rails);
+    }
+
+    fn rails(&self) -> RangeInclusive<f32> {
+        self.rails.clone().unwrap_or(DEFAULT_RAILS)
+    }
+
+    /// Force the state of a pin, regardless of what's connected to it, until it gets released.
+    /// Useful to inject signals into a chip without wiring a generator.
+    /// Note that a chip can still overwrite its own output pins while it runs.
+    pub fn force_pin(&mut self, chip: Id<C>, pin: PinId, state: State) {
+        self.sources_settled = false;
+        self.forced_pins
+            .retain(|(chip_id, pin_id, _)| !(*chip_id == chip && *pin_id == pin));
+        self.forced_pins.push((chip, pin, state));
+    }
+
+    /// Stop forcing the state of a pin
+    pub fn release_pin(&mut self, chip: Id<C>, pin: PinId) {
+        self.sources_settled = false;
+        // a released pin that isn't wired goes back to undefined once the pins get reset
+        self.wired_pins = None;
+        self.forced_pins
+            .retain(|(chip_id, pin_id, _)| !(*chip_id == chip && *pin_id == pin));
+    }
+
+    /// Pull a trace up: it reads High when none of its outputs drive it
+    pub fn add_pullup(&mut self, trace: Id<Trace<C>>) {
+        self.remove_pull(trace);
+        self.pulls.push((trace, State::High));
+    }
+
+    /// Pull a trace down: it reads Low when none of its outputs drive it
+    pub fn add_pulldown(&mut self, trace: Id<Trace<C>>) {
+        self.remove_pull(trace);
+        self.pulls.push((trace, State::Low));
+    }
+
+    /// Let a trace float again when none of its outputs drive it
+    pub fn remove_pull(&mut self, trace: Id<Trace<C>>) {
+        self.pulls.retain(|(trace_id, _)| *trace_id != trace);
+    }
+
+    /// Place a chip on the layout of the board. The board only stores the position, it's up to
+    /// the user interface drawing the board to give it a meaning.
+    pub fn set_position(&mut self, chip: Id<C>, x: f32, y: f32) {
+        match self.positions.iter_mut().find(|(id, _)| *id == chip) {
+            Some((_, position)) => *position = (x, y),
+            None => self.positions.push((chip, (x, y))),
+        }
+    }
+
+    /// Get the position of a chip on the layout, if it was given one
+    pub fn position(&self, chip: &Id<C>) -> Option<(f32, f32)> {
+        self.positions
+            .iter()
+            .find(|(id, _)| id == chip)
+            .map(|(_, position)| *position)
+    }
+
+    /// Choose how a trace combines the states of its outputs, defaults to `ResolutionPolicy::WiredOr`
+    pub fn set_trace_policy(&mut self, trace: Id<Trace<C>>, policy: ResolutionPolicy) {
+        self.policies.retain(|(trace_id, _)| *trace_id != trace);
+        if policy != ResolutionPolicy::default() {
+            self.policies.push((trace, policy));
         }
     }
 
@@ -58,6 +298,222 @@ where
         }
     }
 
+    /// Run the circuit step by step until the condition is met, for at most `max` of simulated time.
+    /// The condition is checked after every step, return whether it was met.
+    pub fn run_until<F: FnMut(&Board<C>) -> bool>(
+        &mut self,
+        step: Duration,
+        max: Duration,
+        mut cond: F,
+    ) -> bool {
+        let mut elapsed = Duration::default();
+        while elapsed < max {
+            self.run(step);
+            elapsed += step;
+            if cond(self) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Run the circuit until the given clock pin has produced a certain amount of rising edges,
+    /// segmented by a step, and return the amount of steps it took.
+    /// The step must be small enough to observe every edge of the clock.
+    /// Fails if the pin doesn't exist. Never returns if the clock stops before producing all the
+    /// edges, when it isn't powered for instance, see `run_cycles_bounded` to give up instead.
+    pub fn run_cycles(
+        &mut self,
+        clock: Id<C>,
+        clock_pin: PinId,
+        cycles: usize,
+        step: Duration,
+    ) -> Result<usize, CycleError<C>> {
+        self.run_cycles_bounded(clock, clock_pin, cycles, step, usize::MAX)
+    }
+
+    /// Same as `run_cycles`, but fails if the edges weren't all seen after `max_steps` steps
+    pub fn run_cycles_bounded(
+        &mut self,
+        clock: Id<C>,
+        clock_pin: PinId,
+        cycles: usize,
+        step: Duration,
+        max_steps: usize,
+    ) -> Result<usize, CycleError<C>> {
+        let Some(mut previous) = self.read_pin(&clock, clock_pin) else {
+            return Err(CycleError::UnknownPin(clock, clock_pin));
+        };
+        let mut edges = 0;
+        for steps in 0..max_steps {
+            if edges >= cycles {
+                return Ok(steps);
+            }
+            self.run(step);
+            let current = self.read_pin(&clock, clock_pin).unwrap_or_default();
+            if current && !previous {
+                edges += 1;
+            }
+            previous = current;
+        }
+        if edges >= cycles {
+            Ok(max_steps)
+        } else {
+            Err(CycleError::StepCapReached { edges })
+        }
+    }
+
+    /// Run the circuit step by step until no pin changes anymore, and return the amount of steps it took.
+    /// Fails if the pins keep cycling through the same states, or if the circuit
+    /// didn't settle after `max_iterations` steps.
+    pub fn run_until_stable(
+        &mut self,
+        step: Duration,
+        max_iterations: usize,
+    ) -> Result<usize, StabilityError<C>> {
+        let mut history = vec![self.pin_states()];
+        for iteration in 1..=max_iterations {
+            self.run(step);
+            let snapshot = self.pin_states();
+            if history.last() == Some(&snapshot) {
+                return Ok(iteration);
+            }
+            if let Some(start) = history.iter().position(|past| *past == snapshot) {
+                // only the chips whose pins changed during the cycle are involved
+                let cycle = &history[start..];
+                let chips = snapshot
+                    .into_iter()
+                    .filter(|(id, pins)| {
+                        cycle.iter().any(|past| {
+                            past.iter()
+                                .find(|(past_id, _)| past_id == id)
+                                .map_or(true, |(_, past_pins)| past_pins != pins)
+                        })
+                    })
+                    .map(|(id, _)| id)
+                    .collect();
+                return Err(StabilityError::OscillationDetected(chips));
+            }
+            history.push(snapshot);
+        }
+        Err(StabilityError::IterationCapReached)
+    }
+
+    /// Check the wiring of the board without running it, and report anything suspicious:
+    /// unpowered chips, traces that nothing reads from, and traces driven by conflicting sources.
+    pub fn validate(&self) -> Vec<ValidationWarning<C>> {
+        let mut warnings = vec![];
+        let traces = self.traces.as_vec();
+
+        for (chip_id, chip) in self.chips.as_vec() {
+            // a chip is reported once, however many of its power pins are left unconnected
+            let unpowered = chip
+                .pin_metadata()
+                .into_iter()
+                .filter(|meta| meta.role == PinRole::Power)
+                .any(|meta| {
+                    let pin_id = meta.id;
+                    !traces
+                        .iter()
+                        .any(|(_, trace)| trace.pins.contains(&(chip_id, pin_id)))
+                        && !self.forced_pins.iter().any(|(forced_chip, forced_pin, _)| {
+                            *forced_chip == chip_id && *forced_pin == pin_id
+                        })
+                });
+            if unpowered {
+                warnings.push(ValidationWarning::UnpoweredChip(chip_id));
+            }
+        }
+
+        for (trace_id, trace) in traces {
+            let pins: Vec<_> = trace
+                .pins
+                .iter()
+                .filter_map(|(chip_id, pin_id)| {
+                    self.chips
+                        .get(chip_id)
+                        .and_then(|chip| chip.get_pin(*pin_id))
+                })
+                .collect();
+            if !pins.is_empty()
+                && pins
+                    .iter()
+                    .all(|pin| matches!(pin.pin_type, PinType::Output))
+            {
+                warnings.push(ValidationWarning::NoConsumer(trace_id));
+            }
+            // constant sources like generators output their configured state without running
+            let mut sources = trace.pins.iter().filter_map(|(chip_id, pin_id)| {
+                let chip = self.chips.get(chip_id)?;
+                let pin = chip.get_pin(*pin_id)?;
+                (chip.is_constant_source()
+                    && matches!(pin.pin_type, PinType::Output)
+                    && pin.state != State::Undefined)
+                    .then_some(pin.state)
+            });
+            if let Some(first) = sources.next() {
+                if sources.any(|state| !state.approx_eq(&first, ANALOG_EPSILON)) {
+                    warnings.push(ValidationWarning::ConflictingSources(trace_id));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Capture the whole state of the board, chips and traces included, so that it can be
+    /// brought back later using `restore`
+    pub fn snapshot(&self) -> BoardSnapshot<C> {
+        BoardSnapshot {
+            chips: self.chips.clone(),
+            traces: self.traces.clone(),
+            forced_pins: self.forced_pins.clone(),
+            rails: self.rails.clone(),
+            pulls: self.pulls.clone(),
+            policies: self.policies.clone(),
+            positions: self.positions.clone(),
+            switching: self.switching,
+        }
+    }
+
+    /// Bring the board back to the state it was in when the snapshot was taken
+    pub fn restore(&mut self, snapshot: &BoardSnapshot<C>) {
+        self.sources_settled = false;
+        self.wired_pins = None;
+        self.chips.clone_from(&snapshot.chips);
+        self.traces.clone_from(&snapshot.traces);
+        self.forced_pins.clone_from(&snapshot.forced_pins);
+        self.rails.clone_from(&snapshot.rails);
+        self.pulls.clone_from(&snapshot.pulls);
+        self.policies.clone_from(&snapshot.policies);
+        self.positions.clone_from(&snapshot.positions);
+        self.switching = snapshot.switching;
+    }
+
+    fn pin_states(&self) -> Vec<(Id<C>, Vec<State>)> {
+        self.chips
+            .as_vec()
+            .into_iter()
+            .map(|(id, chip)| {
+                (
+                    id,
+                    chip.list_pins()
+                        .into_iter()
+                        .map(|(_, pin)| pin.state)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn read_pin(&self, chip: &Id<C>, pin: PinId) -> Option<bool> {
+        // read like a chip powered by the upper rail would
+        let threshold = State::Analog(*self.rails().end()).rail_threshold(3.3);
+        self.chips
+            .get(chip)
+            .and_then(|chip| chip.get_pin(pin))
+            .map(|pin| pin.state.as_logic(threshold).into())
+    }
+
     pub fn run_realtime(&mut self, duration: Duration) {
         let instant = Instant::now();
         let mut old = Instant::now();
@@ -70,23 +526,196 @@ where
     }
 
     pub fn register_chip(&mut self, chip: C) -> Id<C> {
+        self.sources_settled = false;
+        self.wired_pins = None;
         self.chips.add(chip)
     }
 
+    /// Swap the chip behind an existing id, and return the previous one.
+    /// Traces connected to the chip are left untouched, so the new chip must use the same
+    /// pin ids for the same purpose, otherwise they will end up wired to the wrong pins.
+    /// Nothing happens if the chip doesn't exist.
+    pub fn replace_chip(&mut self, id: Id<C>, new_chip: C) -> Option<C> {
+        self.sources_settled = false;
+        self.wired_pins = None;
+        self.chips
+            .get_mut(&id)
+            .map(|chip| std::mem::replace(chip, new_chip))
+    }
+
     pub fn register_trace(&mut self, trace: Trace<C>) -> Id<Trace<C>> {
+        self.wired_pins = None;
         self.traces.add(trace)
     }
 
+    /// Connect two pins with a new trace, or return the trace already joining them if any
+    ///
+    /// # Panics
+    /// Panics if one of the chips doesn't have the given pin, see `try_connect` to get an error instead
     pub fn connect(
         &mut self,
         chip_a: Id<C>,
-        pin_a: PinId,
+        pin_a: impl Into<PinId>,
         chip_b: Id<C>,
-        pin_b: PinId,
+        pin_b: impl Into<PinId>,
     ) -> Id<Trace<C>> {
-        self.traces.add(Trace {
-            pins: vec![(chip_a, pin_a), (chip_b, pin_b)],
-        })
+        let (pin_a, pin_b) = (pin_a.into(), pin_b.into());
+        self.assert_pin(chip_a, pin_a);
+        self.assert_pin(chip_b, pin_b);
+        let existing = self.traces.as_vec().into_iter().find(|(_, trace)| {
+            trace.pins.contains(&(chip_a, pin_a)) && trace.pins.contains(&(chip_b, pin_b))
+        });
+        if let Some((id, _)) = existing {
+            return id;
+        }
+        self.register_trace(Trace::from(vec![(chip_a, pin_a), (chip_b, pin_b)]))
+    }
+
+    /// Disconnect a pin from every trace it's connected to
+    pub fn disconnect(&mut self, chip: Id<C>, pin: impl Into<PinId>) {
+        let pin = pin.into();
+        self.wired_pins = None;
+        for (_, trace) in self.traces.as_mut_vec() {
+            trace.disconnect(chip, pin);
+        }
+    }
+
+    fn check_pin(&self, chip: Id<C>, pin: PinId) -> Result<(), ConnectError<C>> {
+        match self.chips.get(&chip) {
+            Some(c) if c.get_pin(pin).is_some() => Ok(()),
+            _ => Err(ConnectError::UnknownPin(chip, pin)),
+        }
+    }
+
+    fn assert_pin(&self, chip: Id<C>, pin: PinId) {
+        if self.check_pin(chip, pin).is_err() {
+            panic!("chip {chip} doesn't have a pin {pin}");
+        }
+    }
+
+    /// Connect two pins, making sure that a pin never ends up on several traces.
+    /// If one of the pins is already connected, either fail or, when `merge` is true, connect
+    /// the other pin to the existing trace (merging the traces if both pins are connected).
+    /// Also fails when one of the chips doesn't have the given pin.
+    pub fn try_connect(
+        &mut self,
+        chip_a: Id<C>,
+        pin_a: impl Into<PinId>,
+        chip_b: Id<C>,
+        pin_b: impl Into<PinId>,
+        merge: bool,
+    ) -> Result<Id<Trace<C>>, ConnectError<C>> {
+        let (pin_a, pin_b) = (pin_a.into(), pin_b.into());
+        self.check_pin(chip_a, pin_a)?;
+        self.check_pin(chip_b, pin_b)?;
+        let trace_a = self.find_trace(chip_a, pin_a);
+        let trace_b = self.find_trace(chip_b, pin_b);
+        if !merge {
+            if let Some(trace) = trace_a {
+                return Err(ConnectError::PinAlreadyConnected(chip_a, pin_a, trace));
+            }
+            if let Some(trace) = trace_b {
+                return Err(ConnectError::PinAlreadyConnected(chip_b, pin_b, trace));
+            }
+        }
+        match (trace_a, trace_b) {
+            (None, None) => Ok(self.connect(chip_a, pin_a, chip_b, pin_b)),
+            (Some(id), None) | (None, Some(id)) => {
+                self.wired_pins = None;
+                if let Some(trace) = self.traces.get_mut(&id) {
+                    trace.connect(chip_a, pin_a);
+                    trace.connect(chip_b, pin_b);
+                }
+                Ok(id)
+            }
+            (Some(id_a), Some(id_b)) => Ok(self.merge_traces(id_a, id_b)),
+        }
+    }
+
+    /// Move all the pins of trace `b` into trace `a`, remove `b` and return the id of `a`.
+    /// The pull and resolution policy of `b` are kept if `a` doesn't have any.
+    /// Nothing happens if one of the traces doesn't exist.
+    pub fn merge_traces(&mut self, a: Id<Trace<C>>, b: Id<Trace<C>>) -> Id<Trace<C>> {
+        if a != b && self.traces.get(&a).is_some() {
+            self.wired_pins = None;
+            if let Some(other) = self.traces.remove(b) {
+                if let Some(trace) = self.traces.get_mut(&a) {
+                    for (chip, pin) in other.pins {
+                        trace.connect(chip, pin);
+                    }
+                }
+                if pull_state(&self.pulls, a) == State::Undefined {
+                    for (trace_id, _) in self.pulls.iter_mut().filter(|(id, _)| *id == b) {
+                        *trace_id = a;
+                    }
+                }
+                self.remove_pull(b);
+                if !self.policies.iter().any(|(id, _)| *id == a) {
+                    for (trace_id, _) in self.policies.iter_mut().filter(|(id, _)| *id == b) {
+                        *trace_id = a;
+                    }
+                }
+                self.policies.retain(|(id, _)| *id != b);
+            }
+        }
+        a
+    }
+
+    /// Find the trace a pin is connected to
+    fn find_trace(&self, chip: Id<C>, pin: PinId) -> Option<Id<Trace<C>>> {
+        self.traces
+            .as_vec()
+            .into_iter()
+            .find(|(_, trace)| trace.pins.contains(&(chip, pin)))
+            .map(|(id, _)| id)
+    }
+
+    /// Connect all the given pins together with a single trace
+    ///
+    /// # Panics
+    /// Panics if one of the chips doesn't have the given pin
+    pub fn connect_many(&mut self, pins: &[(Id<C>, PinId)]) -> Id<Trace<C>> {
+        let mut trace = Trace::new();
+        for &(chip, pin) in pins {
+            self.assert_pin(chip, pin);
+            trace.connect(chip, pin);
+        }
+        self.register_trace(trace)
+    }
+
+    /// Wire the bus of a CPU to a memory chip: the address, data and control pins of both sides
+    /// are connected by position, pins left over on the wider side stay unconnected.
+    /// A pin that is already connected gets the other one added to its trace, so a CPU can be
+    /// wired to several memories with the same pins. Return the traces of the bus.
+    ///
+    /// # Panics
+    /// Panics if one of the chips doesn't have the given pin
+    pub fn connect_bus_to_memory(
+        &mut self,
+        cpu: Id<C>,
+        cpu_bus: &MemoryBus,
+        memory: Id<C>,
+        memory_bus: &MemoryBus,
+    ) -> Vec<Id<Trace<C>>> {
+        let pairs = [
+            (cpu_bus.address, memory_bus.address),
+            (cpu_bus.data, memory_bus.data),
+            (cpu_bus.control, memory_bus.control),
+        ];
+        pairs
+            .into_iter()
+            .flat_map(|(cpu_pins, memory_pins)| cpu_pins.iter().zip(memory_pins))
+            .map(|(&cpu_pin, &memory_pin)| {
+                match self.try_connect(cpu, cpu_pin, memory, memory_pin, true) {
+                    Ok(trace) => trace,
+                    Err(ConnectError::UnknownPin(chip, pin)) => {
+                        panic!("chip {chip} doesn't have a pin {pin}")
+                    }
+                    // connected pins are never refused when merging
+                    Err(ConnectError::PinAlreadyConnected(..)) => unreachable!(),
+                }
+            })
+            .collect()
     }
 
     pub fn get_chip(&self, id: &Id<C>) -> Option<&C> {
@@ -94,16 +723,438 @@ where
     }
 
     pub fn get_chip_mut(&mut self, id: &Id<C>) -> Option<&mut C> {
+        self.sources_settled = false;
         self.chips.get_mut(id)
     }
 
+    /// Get the ids of every chip matching the predicate, in the order they were registered
+    pub fn find<F: Fn(&C) -> bool>(&self, predicate: F) -> Vec<Id<C>> {
+        self.chips
+            .as_vec()
+            .into_iter()
+            .filter(|(_, chip)| predicate(chip))
+            .map(|(id, _)| id)
+            .collect()
+    }
+
     pub fn get_trace(&self, id: &Id<Trace<C>>) -> Option<&Trace<C>> {
         self.traces.get(id)
     }
 
+    /// Get the state currently driven onto a trace, without altering any pin
+    pub fn get_trace_state(&self, id: &Id<Trace<C>>) -> Option<State> {
+        self.traces
+            .get(id)
+            .map(|trace| trace.resolved_state(&self.chips, &self.get_trace_resolution(*id)))
+    }
+
+    /// Get the rails, pull and policy the board resolves a trace with
+    pub fn get_trace_resolution(&self, id: Id<Trace<C>>) -> Resolution {
+        Resolution {
+            rails: self.rails(),
+            pull: pull_state(&self.pulls, id),
+            policy: trace_policy(&self.policies, id),
+        }
+    }
+
+    /// Get the output pins driving a trace and their states, see `Trace::drivers`
+    pub fn get_trace_drivers(&self, id: &Id<Trace<C>>) -> Option<Vec<(Id<C>, PinId, State)>> {
+        self.traces.get(id).map(|trace| trace.drivers(&self.chips))
+    }
+
     pub fn get_trace_mut(&mut self, id: &Id<Trace<C>>) -> Option<&mut Trace<C>> {
+        self.wired_pins = None;
         self.traces.get_mut(id)
     }
+
+    /// Get a human-readable summary of the board: every chip with its name, then every trace
+    /// with the pins it connects and the state driven onto it
+    pub fn describe(&self) -> String {
+        let mut description = String::from("chips:\n");
+        for (id, chip) in self.chips.as_vec() {
+            description.push_str(&format!("  {id} {}\n", chip.chip_name()));
+        }
+        description.push_str("traces:\n");
+        for (id, trace) in self.traces.as_vec() {
+            let pins: Vec<_> = trace
+                .pins
+                .iter()
+                .map(
+                    |(chip, pin)| match self.chips.get(chip).and_then(|c| c.get_pin_name(*pin)) {
+                        Some(name) => format!("{chip}.{name}"),
+                        None => format!("{chip}.{pin}"),
+                    },
+                )
+                .collect();
+            let state = self.get_trace_state(&id).unwrap_or_default();
+            description.push_str(&format!("  {id} [{}] {state:?}\n", pins.join(", ")));
+        }
+        description
+    }
+}
+
+/// Get the state a trace is pulled to, `State::Undefined` if it isn't pulled
+fn pull_state<C: Chip>(pulls: &[(Id<Trace<C>>, State)], trace: Id<Trace<C>>) -> State {
+    pulls
+        .iter()
+        .find(|(trace_id, _)| *trace_id == trace)
+        .map(|(_, state)| *state)
+        .unwrap_or_default()
+}
+
+/// Get the resolution policy of a trace
+fn trace_policy<C: Chip>(
+    policies: &[(Id<Trace<C>>, ResolutionPolicy)],
+    trace: Id<Trace<C>>,
+) -> ResolutionPolicy {
+    policies
+        .iter()
+        .find(|(trace_id, _)| *trace_id == trace)
+        .map(|(_, policy)| *policy)
+        .unwrap_or_default()
+}
+
+impl Board<ChipSet> {
+    /// Connect the output of a generator to the VCC pins of the given chips with a single trace,
+    /// and return the rail
+    ///
+    /// # Panics
+    /// Panics if one of the chips doesn't have the given pin
+    pub fn power_rail(
+        &mut self,
+        generator: Id<ChipSet>,
+        chip_vcc_pins: &[(Id<ChipSet>, PinId)],
+    ) -> Id<Trace<ChipSet>> {
+        let mut pins = vec![(generator, Generator::OUT)];
+        pins.extend_from_slice(chip_vcc_pins);
+        self.connect_many(&pins)
+    }
+
+    /// Same as `power_rail` for the GND pins of the given chips, the generator is expected to
+    /// drive a Low state (see `Generator::with_state`)
+    ///
+    /// # Panics
+    /// Panics if one of the chips doesn't have the given pin
+    pub fn ground_rail(
+        &mut self,
+        generator: Id<ChipSet>,
+        chip_gnd_pins: &[(Id<ChipSet>, PinId)],
+    ) -> Id<Trace<ChipSet>> {
+        self.power_rail(generator, chip_gnd_pins)
+    }
+
+    /// Switch every generator of the board on or off, to simulate a power cycle
+    pub fn set_power(&mut self, on: bool) {
+        self.sources_settled = false;
+        for id in self.find(|chip| matches!(chip, ChipSet::Generator(_))) {
+            if let Some(ChipSet::Generator(generator)) = self.chips.get_mut(&id) {
+                generator.set_enabled(on);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C> Board<C>
+where
+    C: Chip + serde::Serialize + serde::de::DeserializeOwned,
+{
+    /// Save the board as RON, along with the version of the format so that it can still be
+    /// loaded once the chips have changed
+    pub fn save_to_writer<W: std::io::Write>(&self, writer: W) -> Result<(), BoardError> {
+        #[derive(serde::Serialize)]
+        #[serde(bound = "C: serde::Serialize")]
+        struct SavedBoard<'a, C: Chip> {
+            version: u32,
+            board: &'a Board<C>,
+        }
+
+        ron::ser::to_writer_pretty(
+            writer,
+            &SavedBoard {
+                version: SAVE_VERSION,
+                board: self,
+            },
+            ron::ser::PrettyConfig::default(),
+        )
+        .map_err(|err| BoardError::Io(err.to_string()))
+    }
+
+    /// Load a board saved with `save_to_writer`.
+    /// Boards serialized directly, before the format got versioned, are loaded as well.
+    pub fn load_from_reader<R: std::io::Read>(mut reader: R) -> Result<Self, BoardError> {
+        #[derive(serde::Deserialize)]
+        struct Header {
+            #[serde(default)]
+            version: u32,
+        }
+        #[derive(serde::Deserialize)]
+        #[serde(bound = "C: serde::de::DeserializeOwned")]
+        struct SavedBoard<C: Chip> {
+            board: Board<C>,
+        }
+
+        let mut saved = String::new();
+        reader
+            .read_to_string(&mut saved)
+            .map_err(|err| BoardError::Io(err.to_string()))?;
+        let header: Header =
+            ron::de::from_str(&saved).map_err(|err| BoardError::Parse(err.to_string()))?;
+        match header.version {
+            // unversioned saves are the board itself
+            0 => ron::de::from_str(&saved).map_err(|err| BoardError::Parse(err.to_string())),
+            SAVE_VERSION => ron::de::from_str::<SavedBoard<C>>(&saved)
+                .map(|saved| saved.board)
+                .map_err(|err| BoardError::Parse(err.to_string())),
+            version => Err(BoardError::UnsupportedVersion {
+                found: version,
+                supported: SAVE_VERSION,
+            }),
+        }
+    }
+}
+
+/// Pins of one side of a memory bus, see `Board::connect_bus_to_memory`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryBus<'a> {
+    /// Address pins, from the least significant bit
+    pub address: &'a [PinId],
+    /// Data pins, from the least significant bit
+    pub data: &'a [PinId],
+    /// Control pins such as R/!W or !OE, paired with the ones of the other side
+    pub control: &'a [PinId],
+}
+
+/// Time spent in each part of a board during `Board::run_profiled`
+#[derive(Debug, Clone, Default)]
+pub struct ProfileReport {
+    /// Time spent running the chips, by chip name
+    pub chips: BTreeMap<&'static str, Duration>,
+    /// Time spent calculating the state of the traces
+    pub traces: Duration,
+}
+
+/// State of a board captured by `Board::snapshot`
+#[derive(Debug, Clone)]
+pub struct BoardSnapshot<C: Chip> {
+    chips: Storage<C>,
+    traces: Storage<Trace<C>>,
+    forced_pins: Vec<(Id<C>, PinId, State)>,
+    rails: Option<RangeInclusive<f32>>,
+    pulls: Vec<(Id<Trace<C>>, State)>,
+    policies: Vec<(Id<Trace<C>>, ResolutionPolicy)>,
+    positions: Vec<(Id<C>, (f32, f32))>,
+    switching: Option<usize>,
+}
+
+/// Records snapshots of a board while running it, to be able to travel back in time.
+/// Only the latest snapshots are kept, the oldest ones are dropped once the history is full.
+#[derive(Debug, Clone)]
+pub struct TimelineRecorder<C: Chip> {
+    interval: usize,
+    max_history: usize,
+    tick: usize,
+    history: VecDeque<(usize, BoardSnapshot<C>)>,
+}
+
+impl<C> TimelineRecorder<C>
+where
+    C: Chip,
+{
+    /// Record a snapshot every `interval` ticks, keeping at most `max_history` snapshots
+    pub fn new(interval: usize, max_history: usize) -> Self {
+        TimelineRecorder {
+            interval: interval.max(1),
+            max_history: max_history.max(1),
+            tick: 0,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// Amount of ticks run through the recorder so far
+    pub fn tick(&self) -> usize {
+        self.tick
+    }
+
+    /// Run the board like `Board::run_during`, recording it along the way
+    pub fn run_during(&mut self, board: &mut Board<C>, duration: Duration, step: Duration) {
+        self.record(board);
+        let mut elapsed = Duration::default();
+        while elapsed < duration {
+            board.run(step);
+            elapsed += step;
+            self.tick += 1;
+            self.record(board);
+        }
+    }
+
+    /// Get the snapshot recorded at the given tick, if it's still in the history
+    pub fn snapshot_at(&self, tick: usize) -> Option<&BoardSnapshot<C>> {
+        self.history
+            .iter()
+            .find(|(recorded, _)| *recorded == tick)
+            .map(|(_, snapshot)| snapshot)
+    }
+
+    /// Bring the board back to the given tick, and return whether a snapshot was found.
+    /// Snapshots recorded after that tick are discarded, the recording continues from there.
+    pub fn restore_to(&mut self, board: &mut Board<C>, tick: usize) -> bool {
+        let Some(snapshot) = self.snapshot_at(tick) else {
+            return false;
+        };
+        board.restore(snapshot);
+        self.history.retain(|(recorded, _)| *recorded <= tick);
+        self.tick = tick;
+        true
+    }
+
+    fn record(&mut self, board: &Board<C>) {
+        let recorded = self
+            .history
+            .back()
+            .is_some_and(|(tick, _)| *tick == self.tick);
+        if !recorded && self.tick % self.interval == 0 {
+            if self.history.len() >= self.max_history {
+                self.history.pop_front();
+            }
+            self.history.push_back((self.tick, board.snapshot()));
+        }
+    }
+}
+
+/// Reasons for which a board couldn't be saved or loaded
+#[derive(Debug, Clone)]
+pub enum BoardError {
+    /// The reader or writer failed
+    Io(String),
+    /// The saved board is malformed, or doesn't match the chips it's loaded with
+    Parse(String),
+    /// The board was saved by a newer version of the format
+    UnsupportedVersion { found: u32, supported: u32 },
+}
+
+/// Reasons for which a circuit did not settle in `Board::run_until_stable`
+#[derive(Debug, Clone)]
+pub enum StabilityError<C: Chip> {
+    /// The pins kept cycling through the same states, the given chips are part of the oscillation
+    OscillationDetected(Vec<Id<C>>),
+    /// The circuit was still changing when the iteration cap was reached
+    IterationCapReached,
+}
+
+/// Reasons for which `Board::run_cycles_bounded` couldn't count the requested clock cycles
+#[derive(Debug, Clone)]
+pub enum CycleError<C: Chip> {
+    /// The chip doesn't have this pin, or isn't registered on the board
+    UnknownPin(Id<C>, PinId),
+    /// The step cap was reached after counting the given amount of rising edges
+    StepCapReached { edges: usize },
+}
+
+/// Reasons for which `Board::try_connect` refused to connect two pins
+#[derive(Debug, Clone)]
+pub enum ConnectError<C: Chip> {
+    /// The pin of the chip is already connected to the given trace
+    PinAlreadyConnected(Id<C>, PinId, Id<Trace<C>>),
+    /// The chip doesn't have this pin, or isn't registered on the board
+    UnknownPin(Id<C>, PinId),
+}
+
+/// Wiring issues reported by `Board::validate`
+#[derive(Debug, Clone)]
+pub enum ValidationWarning<C: Chip> {
+    /// A power pin of the chip (see `PinRole::Power`) isn't connected to anything
+    UnpoweredChip(Id<C>),
+    /// Only output pins are connected to the trace, so nothing reads it
+    NoConsumer(Id<Trace<C>>),
+    /// The trace is driven by constant sources that don't agree on its state
+    ConflictingSources(Id<Trace<C>>),
+}
+
+/// How a trace combines the states of the outputs driving it, see `Board::set_trace_policy`.
+/// Outputs in the Undefined state never take part in the resolution.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResolutionPolicy {
+    /// The highest state wins: High over analog, analog over Low
+    #[default]
+    WiredOr,
+    /// The lowest state wins: Low over analog, analog over High
+    WiredAnd,
+    /// Any Low output pulls the trace Low, otherwise same as `WiredOr`
+    DominantLow,
+    /// The trace takes the mean voltage of its outputs, Low and High being the rails
+    Analog,
+}
+
+impl ResolutionPolicy {
+    /// Combine the states of the outputs of a trace, Undefined if none of them drive it
+    pub fn resolve(
+        &self,
+        states: impl IntoIterator<Item = State>,
+        rails: &RangeInclusive<f32>,
+    ) -> State {
+        let states = states
+            .into_iter()
+            .filter(|state| *state != State::Undefined);
+        match self {
+            ResolutionPolicy::WiredOr => states.fold(State::Undefined, |mut acc, state| {
+                acc.feed_state_within(state, rails)
+            }),
+            ResolutionPolicy::WiredAnd => states
+                .map(|state| match state {
+                    State::Analog(v) => State::Analog(v.clamp(*rails.start(), *rails.end())),
+                    _ => state,
+                })
+                .fold(State::Undefined, |acc, state| match (acc, state) {
+                    (State::Undefined | State::High, _) => state,
+                    (_, State::Undefined | State::High) => acc,
+                    (State::Low, _) | (_, State::Low) => State::Low,
+                    (State::Analog(a), State::Analog(b)) => State::Analog(a.min(b)),
+                }),
+            ResolutionPolicy::DominantLow => {
+                states.fold(State::Undefined, |mut acc, state| match (acc, state) {
+                    (State::Low, _) | (_, State::Low) => State::Low,
+                    _ => acc.feed_state_within(state, rails),
+                })
+            }
+            ResolutionPolicy::Analog => {
+                let (sum, count) = states.fold((0.0, 0), |(sum, count), state| {
+                    let voltage = match state {
+                        State::High => *rails.end(),
+                        State::Analog(v) => v.clamp(*rails.start(), *rails.end()),
+                        _ => *rails.start(),
+                    };
+                    (sum + voltage, count + 1)
+                });
+                if count == 0 {
+                    State::Undefined
+                } else {
+                    State::Analog(sum / count as f32)
+                }
+            }
+        }
+    }
+}
+
+/// Settings a trace resolves its state with, as configured on the board holding it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolution {
+    /// Supply rails that analog states get clamped to
+    pub rails: RangeInclusive<f32>,
+    /// State taken by the trace when none of its outputs drive it
+    pub pull: State,
+    pub policy: ResolutionPolicy,
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        Resolution {
+            rails: DEFAULT_RAILS,
+            pull: State::Undefined,
+            policy: ResolutionPolicy::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -111,6 +1162,15 @@ where
 #[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Trace<C: Chip> {
     pins: Vec<(Id<C>, usize)>,
+    /// indexes in `pins` of the input pins found during the last read pass
+    #[cfg_attr(feature = "serde", serde(skip))]
+    inputs: Vec<usize>,
+    /// states of the output pins found during the last read pass
+    #[cfg_attr(feature = "serde", serde(skip))]
+    outputs: Vec<State>,
+    /// states resolved at each step, when enabled
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Option<Vec<State>>,
 }
 
 impl<C> Trace<C>
@@ -118,45 +1178,126 @@ where
     C: Chip,
 {
     pub fn new() -> Self {
-        Trace { pins: Vec::new() }
+        Trace {
+            pins: Vec::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            history: None,
+        }
     }
 
-    pub fn connect(&mut self, chip: Id<C>, pin: PinId) {
+    pub fn connect(&mut self, chip: Id<C>, pin: impl Into<PinId>) {
+        let pin = pin.into();
         if !self.pins.contains(&(chip, pin)) {
             self.pins.push((chip, pin))
         }
     }
 
-    pub fn disconnect(&mut self, chip: Id<C>, pin: PinId) {
+    pub fn disconnect(&mut self, chip: Id<C>, pin: impl Into<PinId>) {
+        let pin = pin.into();
         self.pins.retain(|&x| x != (chip, pin));
     }
 
+    /// Remove the given pins from the trace and return a new trace connecting them,
+    /// pins that aren't part of the trace are ignored.
+    /// The new trace needs to be registered on the board to take effect.
+    pub fn split(&mut self, pins_to_move: &[(Id<C>, PinId)]) -> Trace<C> {
+        let (moved, kept) = self.pins.iter().partition(|pin| pins_to_move.contains(pin));
+        self.pins = kept;
+        Trace::from(moved)
+    }
+
     pub fn get_connections(&self) -> &[(Id<C>, usize)] {
         &self.pins
     }
 
-    pub fn calculate_state(&mut self, chip_storage: &mut Storage<C>) {
-        let mut base_state = State::Undefined;
-        // read state
-        for (chip_id, pin_id) in self.pins.iter() {
+    /// Record the state resolved on the trace each time it gets calculated, to be read with
+    /// `history`. Disabled by default, as the history grows with every step.
+    pub fn enable_history(&mut self) {
+        self.history.get_or_insert_with(Vec::new);
+    }
+
+    /// Stop recording the states of the trace and drop the recorded ones
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Get the states resolved on the trace at each step since the history was enabled
+    pub fn history(&self) -> &[State] {
+        self.history.as_deref().unwrap_or_default()
+    }
+
+    /// Get every output pin connected to the trace along with the state it drives,
+    /// to find out which chips are fighting over a net
+    pub fn drivers(&self, chip_storage: &Storage<C>) -> Vec<(Id<C>, PinId, State)> {
+        self.pins
+            .iter()
+            .filter_map(|(chip_id, pin_id)| {
+                chip_storage
+                    .get(chip_id)
+                    .and_then(|chip| chip.get_pin(*pin_id))
+                    .filter(|pin| matches!(pin.pin_type, PinType::Output))
+                    .map(|pin| (*chip_id, *pin_id, pin.state))
+            })
+            .collect()
+    }
+
+    /// Get the state driven onto the trace by its output pins, without writing anything.
+    /// This is the state that `calculate_state` would feed to the input pins.
+    pub fn resolved_state(&self, chip_storage: &Storage<C>, resolution: &Resolution) -> State {
+        let state = resolution.policy.resolve(
+            self.pins
+                .iter()
+                .filter_map(|(chip_id, pin_id)| {
+                    chip_storage
+                        .get(chip_id)
+                        .and_then(|chip| chip.get_pin(*pin_id))
+                })
+                .filter(|pin| matches!(pin.pin_type, PinType::Output))
+                .map(|pin| pin.state),
+            &resolution.rails,
+        );
+        match state {
+            State::Undefined => resolution.pull,
+            _ => state,
+        }
+    }
+
+    /// Resolve the state driven by the output pins of the trace and feed it to its input pins
+    pub fn calculate_state(&mut self, chip_storage: &mut Storage<C>, resolution: &Resolution) {
+        // read state, remembering which pins will need to be written to
+        // pin types can only change while chips run, so they're stable for the write pass
+        self.inputs.clear();
+        self.outputs.clear();
+        for (index, (chip_id, pin_id)) in self.pins.iter().enumerate() {
             if let Some(pin) = chip_storage
                 .get(chip_id)
                 .and_then(|chip| chip.get_pin(*pin_id))
             {
-                if matches!(pin.pin_type, PinType::Output) {
-                    base_state = base_state.feed_state(pin.state);
+                match pin.pin_type {
+                    PinType::Output => self.outputs.push(pin.state),
+                    PinType::Input => self.inputs.push(index),
+                    PinType::Floating => {}
                 }
             }
         }
+        let mut base_state = resolution
+            .policy
+            .resolve(self.outputs.iter().copied(), &resolution.rails);
+        if base_state == State::Undefined {
+            base_state = resolution.pull;
+        }
+        if let Some(history) = &mut self.history {
+            history.push(base_state);
+        }
         // write state
-        for (chip_id, pin_id) in self.pins.iter() {
-            if let Some(mut pin) = chip_storage
+        for &index in self.inputs.iter() {
+            let (chip_id, pin_id) = &self.pins[index];
+            if let Some(pin) = chip_storage
                 .get_mut(chip_id)
                 .and_then(|chip| chip.get_pin_mut(*pin_id))
             {
-                if matches!(pin.pin_type, PinType::Input) {
-                    pin.state = pin.state.feed_state(base_state);
-                }
+                pin.state = pin.state.feed_state_within(base_state, &resolution.rails);
             }
         }
     }
@@ -167,6 +1308,1440 @@ where
     C: Chip,
 {
     fn from(pins: Vec<(Id<C>, usize)>) -> Self {
-        Trace { pins }
+        Trace {
+            pins,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            history: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, time::Duration};
+
+    use super::{
+        Board, ConnectError, CycleError, MemoryBus, Resolution, ResolutionPolicy, StabilityError,
+        TimelineRecorder, Trace, ValidationWarning,
+    };
+    use crate::{
+        chip::{
+            clocks::{Clock, PatternGenerator},
+            counters::UpDownCounter4Bit,
+            cpu::nes6502::{AddressingMode, Assembler, Item, Nes6502, Nes6502Pin, Opcode},
+            gates::{AndGate, NotGate, OrGate},
+            generators::Generator,
+            inputs::Button,
+            memories::{MemoryEvent, Ram256B, Ram256BPin, RamInit, Rom256B},
+            Chip, ChipBuilder, ChipRunner, ChipSet, DynChip, Pin, PinId, PinMeta, PinRole, PinType,
+        },
+        utilities::{Id, Storage},
+        State,
+    };
+
+    /// A 6502 with 256 bytes of RAM mirrored over $0000-$7FFF and 256 bytes of ROM mirrored over
+    /// $8000-$FFFF, laid out like the test-6502 example
+    struct Computer {
+        board: Board<ChipSet>,
+        cpu: Id<ChipSet>,
+        ram: Id<ChipSet>,
+    }
+
+    /// Period of the 1kHz clock of `computer`
+    const PERIOD: Duration = Duration::from_millis(1);
+    /// Step small enough for the buses to settle between two clock edges
+    const STEP: Duration = Duration::from_micros(50);
+
+    fn computer(program: &[Item]) -> Computer {
+        let mut items = program.to_vec();
+        items.extend([Item::Org(0xFC), Item::Word(0x8000)]);
+        let image = Assembler::assemble_items(&items).unwrap();
+
+        let mut board: Board<ChipSet> = Board::new();
+        let rom = board.register_chip(Rom256B::build().set_data(&image).into());
+        let ram = board.register_chip(Ram256B::build().into());
+        let not = board.register_chip(NotGate::build().into());
+        let vcc = board.register_chip(Generator::build().into());
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+        let cpu = board.register_chip(Nes6502::build().into());
+        board.power_rail(
+            vcc,
+            &[
+                (clock, Clock::VCC),
+                (cpu, Nes6502::VCC),
+                (not, NotGate::VCC),
+                (rom, Rom256B::VCC),
+                (ram, Ram256B::VCC),
+            ],
+        );
+        let cpu_bus = MemoryBus {
+            address: &[
+                Nes6502::A0,
+                Nes6502::A1,
+                Nes6502::A2,
+                Nes6502::A3,
+                Nes6502::A4,
+                Nes6502::A5,
+                Nes6502::A6,
+                Nes6502::A7,
+            ],
+            data: &[
+                Nes6502::D0,
+                Nes6502::D1,
+                Nes6502::D2,
+                Nes6502::D3,
+                Nes6502::D4,
+                Nes6502::D5,
+                Nes6502::D6,
+                Nes6502::D7,
+            ],
+            ..Default::default()
+        };
+        let memory_bus = MemoryBus {
+            address: &[
+                Ram256B::A0,
+                Ram256B::A1,
+                Ram256B::A2,
+                Ram256B::A3,
+                Ram256B::A4,
+                Ram256B::A5,
+                Ram256B::A6,
+                Ram256B::A7,
+            ],
+            data: &[
+                Ram256B::IO0,
+                Ram256B::IO1,
+                Ram256B::IO2,
+                Ram256B::IO3,
+                Ram256B::IO4,
+                Ram256B::IO5,
+                Ram256B::IO6,
+                Ram256B::IO7,
+            ],
+            ..Default::default()
+        };
+        // both memories share the same pinout
+        board.connect_bus_to_memory(cpu, &cpu_bus, rom, &memory_bus);
+        board.connect_bus_to_memory(cpu, &cpu_bus, ram, &memory_bus);
+        board.connect(cpu, Nes6502::A15, not, NotGate::A);
+        board.connect(not, NotGate::NOT_A, rom, Rom256B::CS);
+        board.connect(cpu, Nes6502::A15, ram, Ram256B::CS);
+        board.register_trace(Trace::from(vec![
+            (cpu, Nes6502::RW),
+            (ram, Ram256B::WE),
+            (not, NotGate::B),
+        ]));
+        board.register_trace(Trace::from(vec![
+            (not, NotGate::NOT_B),
+            (rom, Rom256B::OE),
+            (ram, Ram256B::OE),
+        ]));
+        board.connect(clock, Clock::CLK, cpu, Nes6502::CLK);
+        Computer { board, cpu, ram }
+    }
+
+    impl Computer {
+        fn run_cycles(&mut self, cycles: usize) {
+            self.board.run_during(PERIOD * cycles as u32, STEP);
+        }
+
+        fn ram(&self, addr: u8) -> u8 {
+            match self.board.get_chip(&self.ram) {
+                Some(ChipSet::Ram256B(ram)) => ram.data()[addr as usize],
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// A single pin chip counting how many times its pin gets looked up
+    #[derive(Debug, Clone)]
+    struct Probe {
+        pin: Pin,
+        lookups: Cell<usize>,
+    }
+
+    impl Probe {
+        const PIN: PinId = 1;
+
+        fn new(pin_type: PinType, state: State) -> Self {
+            Probe {
+                pin: Pin { pin_type, state },
+                lookups: Cell::new(0),
+            }
+        }
+    }
+
+    impl Chip for Probe {
+        fn list_pins(&self) -> Vec<(PinId, &Pin)> {
+            vec![(1, &self.pin)]
+        }
+
+        fn get_pin(&self, _: PinId) -> Option<&Pin> {
+            self.lookups.set(self.lookups.get() + 1);
+            Some(&self.pin)
+        }
+
+        fn get_pin_mut(&mut self, _: PinId) -> Option<&mut Pin> {
+            self.lookups.set(self.lookups.get() + 1);
+            Some(&mut self.pin)
+        }
+    }
+
+    impl ChipRunner for Probe {
+        fn run(&mut self, _: Duration) {}
+    }
+
+    #[test]
+    fn calculate_state_only_writes_to_inputs() {
+        let mut chips = Storage::new();
+        let probes = [
+            (PinType::Output, State::High),
+            (PinType::Output, State::Undefined),
+            (PinType::Input, State::Undefined),
+            (PinType::Input, State::Low),
+            (PinType::Floating, State::Low),
+            (PinType::Input, State::Undefined),
+        ]
+        .map(|(pin_type, state)| chips.add(Probe::new(pin_type, state)));
+        let mut trace = Trace::from(probes.iter().map(|id| (*id, 1)).collect::<Vec<_>>());
+
+        trace.calculate_state(&mut chips, &Resolution::default());
+
+        // same result as feeding the resolved state to every input of the trace
+        let states: Vec<_> = probes
+            .iter()
+            .map(|id| chips.get(id).unwrap().pin.state)
+            .collect();
+        assert_eq!(
+            states,
+            [
+                State::High,
+                State::Undefined,
+                State::High,
+                State::High,
+                State::Low,
+                State::High
+            ]
+        );
+        // one read per pin, and one write per input instead of one per pin
+        let lookups: usize = probes
+            .iter()
+            .map(|id| chips.get(id).unwrap().lookups.get())
+            .sum();
+        assert_eq!(lookups, 6 + 3);
+    }
+
+    #[test]
+    fn wiring_changes_after_a_run_are_seen() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().with_state(State::High).into());
+        let gate = board.register_chip(NotGate::build().into());
+        let input =
+            |board: &Board<ChipSet>| board.get_chip(&gate).unwrap().get_pin_state(NotGate::A);
+
+        board.run(Duration::from_millis(1));
+        assert_eq!(input(&board), State::Undefined);
+
+        board.connect(vcc, Generator::OUT, gate, NotGate::A);
+        board.run(Duration::from_millis(1));
+        assert_eq!(input(&board), State::High);
+        // the newly wired pin gets reset before each step, so it follows its trace
+        board.set_power(false);
+        board.run(Duration::from_millis(1));
+        assert_eq!(input(&board), State::Undefined);
+        board.set_power(true);
+        board.run(Duration::from_millis(1));
+        assert_eq!(input(&board), State::High);
+
+        board.disconnect(gate, NotGate::A);
+        board.run(Duration::from_millis(1));
+        assert_eq!(input(&board), State::Undefined);
+    }
+
+    #[test]
+    fn drivers_lists_the_outputs_fighting_over_a_trace() {
+        let mut chips = Storage::new();
+        let high = chips.add(Probe::new(PinType::Output, State::High));
+        let low = chips.add(Probe::new(PinType::Output, State::Low));
+        let input = chips.add(Probe::new(PinType::Input, State::Undefined));
+        let trace = Trace::from(vec![
+            (high, Probe::PIN),
+            (input, Probe::PIN),
+            (low, Probe::PIN),
+        ]);
+
+        assert_eq!(
+            trace.drivers(&chips),
+            [
+                (high, Probe::PIN, State::High),
+                (low, Probe::PIN, State::Low)
+            ]
+        );
+    }
+
+    #[test]
+    fn resolved_state_reads_without_writing() {
+        let mut chips = Storage::new();
+        let probes = [
+            (PinType::Output, State::Low),
+            (PinType::Output, State::High),
+            (PinType::Input, State::Undefined),
+        ]
+        .map(|(pin_type, state)| chips.add(Probe::new(pin_type, state)));
+        let mut trace = Trace::from(probes.iter().map(|id| (*id, 1)).collect::<Vec<_>>());
+        let resolution = Resolution::default();
+
+        assert_eq!(trace.resolved_state(&chips, &resolution), State::High);
+        let input = |chips: &Storage<Probe>| chips.get(&probes[2]).unwrap().pin.state;
+        assert_eq!(input(&chips), State::Undefined);
+
+        // it's the state that gets fed to the inputs
+        trace.calculate_state(&mut chips, &resolution);
+        assert_eq!(input(&chips), State::High);
+    }
+
+    #[test]
+    fn connect_many_joins_a_vcc_net() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gates = [(); 4].map(|_| board.register_chip(AndGate::build().into()));
+        let mut pins = vec![(vcc, Generator::OUT)];
+        pins.extend(gates.iter().map(|gate| (*gate, AndGate::VCC)));
+
+        let trace = board.connect_many(&pins);
+
+        assert_eq!(board.get_trace(&trace).unwrap().get_connections(), pins);
+        assert_eq!(board.traces.as_vec().len(), 1);
+    }
+
+    #[test]
+    fn forced_pins_drive_a_chip_without_generators() {
+        let mut board: Board<ChipSet> = Board::new();
+        let and = board.register_chip(AndGate::build().into());
+        for pin in [AndGate::VCC, AndGate::A, AndGate::B] {
+            board.force_pin(and, pin, State::High);
+        }
+        let output =
+            |board: &Board<ChipSet>| board.get_chip(&and).unwrap().get_pin_state(AndGate::AB);
+
+        // the forced states survive the inputs being cleared at every step
+        board.run(Duration::from_millis(1));
+        board.run(Duration::from_millis(1));
+        assert_eq!(output(&board), State::High);
+
+        board.force_pin(and, AndGate::B, State::Low);
+        board.run(Duration::from_millis(1));
+        assert_eq!(output(&board), State::Low);
+
+        // once released, the unconnected input reads as undefined again
+        board.release_pin(and, AndGate::B);
+        board.run(Duration::from_millis(1));
+        assert_eq!(
+            board.get_chip(&and).unwrap().get_pin_state(AndGate::B),
+            State::Undefined
+        );
+    }
+
+    #[test]
+    fn pin_enums_connect_like_the_consts() {
+        let mut board: Board<ChipSet> = Board::new();
+        let cpu = board.register_chip(Nes6502::build().into());
+        let ram = board.register_chip(Ram256B::build().into());
+        let with_enums = board.connect(cpu, Nes6502Pin::A0, ram, Ram256BPin::A0);
+        let with_consts = Trace::from(vec![(cpu, Nes6502::A0), (ram, Ram256B::A0)]);
+        assert_eq!(
+            board.get_trace(&with_enums).unwrap().get_connections(),
+            with_consts.get_connections()
+        );
+        // both forms name the same pins, so connecting them again finds the same trace
+        assert_eq!(
+            board.connect(cpu, Nes6502::A0, ram, Ram256B::A0),
+            with_enums
+        );
+    }
+
+    #[test]
+    fn boxed_custom_chip_next_to_a_built_in_one() {
+        let mut board: Board<Box<dyn DynChip>> = Board::new();
+        let and = board.register_chip(Box::new(AndGate::build()));
+        let vcc = board.register_chip(Box::new(Generator::build()));
+        let custom = board.register_chip(Box::new(Probe::new(PinType::Output, State::High)));
+        board.connect_many(&[
+            (vcc, Generator::OUT),
+            (and, AndGate::VCC),
+            (and, AndGate::B),
+        ]);
+        board.connect(custom, Probe::PIN, and, AndGate::A);
+
+        board.run_during(Duration::from_millis(3), Duration::from_millis(1));
+
+        let and = board.get_chip(&and).unwrap();
+        assert_eq!(and.chip_name(), "AndGate");
+        assert_eq!(and.get_pin_state(AndGate::AB), State::High);
+        assert_eq!(board.get_chip(&custom).unwrap().chip_name(), "Probe");
+    }
+
+    #[test]
+    fn describe_lists_the_chips_and_the_nets() {
+        // the board of the extend example, with a probe as the custom chip
+        #[derive(Debug, Clone)]
+        enum CustomChipSet {
+            Probe(Probe),
+            Builtin(Box<ChipSet>),
+        }
+        crate::impl_chip_type!(CustomChipSet: (Probe, Builtin));
+
+        let mut board: Board<CustomChipSet> = Board::new();
+        let builtin = |chip: ChipSet| CustomChipSet::Builtin(Box::new(chip));
+        let and = board.register_chip(builtin(AndGate::build().into()));
+        let vcc = board.register_chip(builtin(Generator::build().into()));
+        let custom = board.register_chip(CustomChipSet::Probe(Probe::new(
+            PinType::Input,
+            State::Undefined,
+        )));
+        board.connect_many(&[
+            (vcc, Generator::OUT),
+            (and, AndGate::VCC),
+            (custom, Probe::PIN),
+            (and, AndGate::B),
+        ]);
+        board.run(Duration::from_millis(1));
+
+        assert_eq!(
+            board.describe(),
+            "chips:\n  #0 Builtin\n  #1 Builtin\n  #2 Probe\n\
+             traces:\n  #0 [#1.1, #0.vcc, #2.1, #0.b] High\n"
+        );
+    }
+
+    #[test]
+    fn skipping_constant_sources_gives_the_same_results() {
+        /// A generator the board can't skip
+        #[derive(Debug, Clone)]
+        struct Unsettled(Generator);
+
+        impl Chip for Unsettled {
+            fn list_pins(&self) -> Vec<(PinId, &Pin)> {
+                self.0.list_pins()
+            }
+
+            fn get_pin(&self, pin: PinId) -> Option<&Pin> {
+                self.0.get_pin(pin)
+            }
+
+            fn get_pin_mut(&mut self, pin: PinId) -> Option<&mut Pin> {
+                self.0.get_pin_mut(pin)
+            }
+        }
+
+        impl ChipRunner for Unsettled {
+            fn run(&mut self, tick_duration: Duration) {
+                self.0.run(tick_duration)
+            }
+        }
+
+        let outputs = |vcc: Box<dyn DynChip>| {
+            let mut board: Board<Box<dyn DynChip>> = Board::new();
+            let vcc = board.register_chip(vcc);
+            let clock = board.register_chip(Box::new(Clock::build().with_frequency(1000.0)));
+            let gates = [(); 16].map(|_| board.register_chip(Box::new(AndGate::build())));
+            let mut rail = vec![(vcc, Generator::OUT), (clock, Clock::VCC)];
+            rail.extend(
+                gates
+                    .iter()
+                    .flat_map(|gate| [(*gate, AndGate::VCC), (*gate, AndGate::A)]),
+            );
+            board.connect_many(&rail);
+            board.connect(clock, Clock::CLK, gates[0], AndGate::B);
+            for pair in gates.windows(2) {
+                board.connect(pair[0], AndGate::AB, pair[1], AndGate::B);
+            }
+
+            let mut outputs = vec![];
+            for _ in 0..40 {
+                board.run(Duration::from_micros(100));
+                outputs.extend(
+                    gates
+                        .iter()
+                        .map(|gate| board.get_chip(gate).unwrap().get_pin_state(AndGate::AB)),
+                );
+            }
+            outputs
+        };
+
+        let skipped = outputs(Box::new(Generator::build()));
+        assert!(skipped.contains(&State::High) && skipped.contains(&State::Low));
+        assert_eq!(skipped, outputs(Box::new(Unsettled(Generator::build()))));
+    }
+
+    #[test]
+    fn replaced_chip_keeps_its_traces() {
+        let mut board: Board<ChipSet> = Board::new();
+        let gate = board.register_chip(AndGate::build().into());
+        let high = board.register_chip(Generator::build().into());
+        let low = board.register_chip(Generator::build().with_state(State::Low).into());
+        board.connect_many(&[
+            (high, Generator::OUT),
+            (gate, AndGate::VCC),
+            (gate, AndGate::A),
+        ]);
+        board.connect(low, Generator::OUT, gate, AndGate::B);
+        let output =
+            |board: &Board<ChipSet>| board.get_chip(&gate).unwrap().get_pin_state(AndGate::AB);
+        board.run_during(Duration::from_millis(3), Duration::from_millis(1));
+        assert_eq!(output(&board), State::Low);
+
+        // the OR gate has the same pinout, so it works on the same traces
+        let previous = board.replace_chip(gate, OrGate::build().into());
+        assert!(matches!(previous, Some(ChipSet::AndGate(_))));
+        board.run_during(Duration::from_millis(3), Duration::from_millis(1));
+        assert_eq!(output(&board), State::High);
+    }
+
+    #[test]
+    fn five_volt_board_derives_thresholds_from_vcc() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().with_voltage(5.0).into());
+        let input = board.register_chip(Generator::build().with_voltage(3.0).into());
+        let gate = board.register_chip(AndGate::build().into());
+        board.connect_many(&[
+            (vcc, Generator::OUT),
+            (gate, AndGate::VCC),
+            (gate, AndGate::B),
+        ]);
+        board.connect(input, Generator::OUT, gate, AndGate::A);
+
+        board.run_during(Duration::from_millis(3), Duration::from_millis(1));
+
+        // 3V is above half of the 5V rail, but below the 3.3V default threshold
+        let gate = board.get_chip(&gate).unwrap();
+        assert_eq!(gate.get_pin_state(AndGate::AB), State::High);
+        assert_eq!(gate.get_pin_state(AndGate::A).as_logic(3.3), State::Low);
+    }
+
+    #[test]
+    fn connecting_a_pin_twice_errors_or_merges() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gate = board.register_chip(AndGate::build().into());
+        let other = board.register_chip(AndGate::build().into());
+        let trace = board
+            .try_connect(vcc, Generator::OUT, gate, AndGate::VCC, false)
+            .unwrap();
+
+        assert!(matches!(
+            board.try_connect(vcc, Generator::OUT, other, AndGate::VCC, false),
+            Err(ConnectError::PinAlreadyConnected(chip, Generator::OUT, id)) if chip == vcc && id == trace
+        ));
+        assert_eq!(board.traces.as_vec().len(), 1);
+
+        // with merge, the other pin joins the existing trace instead
+        assert_eq!(
+            board
+                .try_connect(vcc, Generator::OUT, other, AndGate::VCC, true)
+                .unwrap(),
+            trace
+        );
+        assert_eq!(board.traces.as_vec().len(), 1);
+        assert_eq!(
+            board.get_trace(&trace).unwrap().get_connections(),
+            [
+                (vcc, Generator::OUT),
+                (gate, AndGate::VCC),
+                (other, AndGate::VCC)
+            ]
+        );
+    }
+
+    #[test]
+    fn connecting_the_same_pins_twice_reuses_the_trace() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gate = board.register_chip(AndGate::build().into());
+
+        let trace = board.connect(vcc, Generator::OUT, gate, AndGate::VCC);
+        assert_eq!(
+            board.connect(vcc, Generator::OUT, gate, AndGate::VCC),
+            trace
+        );
+        // in any order, and on a net joining more pins
+        assert_eq!(
+            board.connect(gate, AndGate::VCC, vcc, Generator::OUT),
+            trace
+        );
+        board
+            .get_trace_mut(&trace)
+            .unwrap()
+            .connect(gate, AndGate::A);
+        assert_eq!(board.connect(gate, AndGate::A, vcc, Generator::OUT), trace);
+        assert_eq!(board.traces.as_vec().len(), 1);
+    }
+
+    #[test]
+    fn connecting_an_unknown_pin_names_the_chip_and_the_pin() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gate = board.register_chip(AndGate::build().into());
+        const TYPO: PinId = 42;
+
+        let error = board
+            .try_connect(vcc, Generator::OUT, gate, TYPO, false)
+            .unwrap_err();
+        assert!(matches!(error, ConnectError::UnknownPin(chip, 42) if chip == gate));
+        assert!(board.traces.as_vec().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "chip #1 doesn't have a pin 42")]
+    fn connect_panics_on_an_unknown_pin() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gate = board.register_chip(AndGate::build().into());
+        const TYPO: PinId = 42;
+        board.connect(vcc, Generator::OUT, gate, TYPO);
+    }
+
+    #[test]
+    fn merge_then_split_traces() {
+        let mut board: Board<ChipSet> = Board::new();
+        let gates = [(); 4].map(|_| board.register_chip(AndGate::build().into()));
+        let a = board.connect(gates[0], AndGate::A, gates[1], AndGate::A);
+        let b = board.connect(gates[2], AndGate::A, gates[3], AndGate::A);
+
+        assert_eq!(board.merge_traces(a, b), a);
+        assert!(board.get_trace(&b).is_none());
+        let all: Vec<_> = gates.iter().map(|gate| (*gate, AndGate::A)).collect();
+        assert_eq!(board.get_trace(&a).unwrap().get_connections(), all);
+
+        let moved = board
+            .get_trace_mut(&a)
+            .unwrap()
+            .split(&[(gates[1], AndGate::A), (gates[3], AndGate::A)]);
+        assert_eq!(
+            board.get_trace(&a).unwrap().get_connections(),
+            [(gates[0], AndGate::A), (gates[2], AndGate::A)]
+        );
+        assert_eq!(
+            moved.get_connections(),
+            [(gates[1], AndGate::A), (gates[3], AndGate::A)]
+        );
+    }
+
+    #[test]
+    fn find_returns_the_matching_chips() {
+        let mut board: Board<ChipSet> = Board::new();
+        let clock = board.register_chip(Clock::build().into());
+        board.register_chip(AndGate::build().into());
+        let other_clock = board.register_chip(Clock::build().into());
+        board.register_chip(Generator::build().into());
+
+        let clocks = board.find(|chip| matches!(chip, ChipSet::Clock(_)));
+        assert_eq!(clocks, [clock, other_clock]);
+        assert!(board
+            .find(|chip| matches!(chip, ChipSet::Ram256B(_)))
+            .is_empty());
+    }
+
+    #[test]
+    fn analog_states_are_clamped_to_the_rails() {
+        let mut board: Board<ChipSet> = Board::new();
+        let a = board.register_chip(Generator::build().with_voltage(7.0).into());
+        let b = board.register_chip(Generator::build().with_state(State::Undefined).into());
+        let trace = board.connect(a, Generator::OUT, b, Generator::OUT);
+        board.run(Duration::from_millis(1));
+        assert_eq!(board.get_trace_state(&trace), Some(State::Analog(5.0)));
+
+        board.set_rails(0.0..=3.3);
+        board.run(Duration::from_millis(1));
+        assert_eq!(board.get_trace_state(&trace), Some(State::Analog(3.3)));
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid supply rails")]
+    fn inverted_rails_are_rejected() {
+        let mut board: Board<ChipSet> = Board::new();
+        board.set_rails(5.0..=0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid supply rails")]
+    fn nan_rails_are_rejected() {
+        let mut board: Board<ChipSet> = Board::new();
+        board.set_rails(0.0..=f32::NAN);
+    }
+
+    #[test]
+    fn validate_reports_a_broken_board() {
+        let mut board: Board<ChipSet> = Board::new();
+        let and = board.register_chip(AndGate::build().into());
+        let high = board.register_chip(Generator::build().into());
+        let low = board.register_chip(Generator::build().with_state(State::Low).into());
+        let not = board.register_chip(NotGate::build().into());
+        let conflict = board.connect(high, Generator::OUT, low, Generator::OUT);
+        board.connect(high, Generator::OUT, not, NotGate::VCC);
+        // a chip output isn't a constant source, whatever its state before running
+        let driven = board.register_trace(Trace::from(vec![
+            (not, NotGate::NOT_A),
+            (low, Generator::OUT),
+            (and, AndGate::A),
+        ]));
+        if let Some(chip) = board.get_chip_mut(&not) {
+            chip.set_pin_state(NotGate::NOT_A, State::High);
+        }
+
+        let warnings = board.validate();
+        assert!(warnings
+            .iter()
+            .any(|warning| matches!(warning, ValidationWarning::UnpoweredChip(id) if *id == and)));
+        assert!(!warnings
+            .iter()
+            .any(|warning| matches!(warning, ValidationWarning::UnpoweredChip(id) if *id == not)));
+        assert!(warnings.iter().any(
+            |warning| matches!(warning, ValidationWarning::ConflictingSources(id) if *id == conflict)
+        ));
+        assert!(!warnings.iter().any(
+            |warning| matches!(warning, ValidationWarning::ConflictingSources(id) if *id == driven)
+        ));
+        // nothing reads the trace between the generators either
+        assert!(warnings.iter().any(
+            |warning| matches!(warning, ValidationWarning::NoConsumer(id) if *id == conflict)
+        ));
+        assert_eq!(warnings.len(), 3, "{warnings:?}");
+    }
+
+    /// A chip with two supply pins, like the logic and I/O supplies of a level shifter
+    #[derive(Debug, Clone, Default)]
+    struct DualSupply {
+        vcc_a: Pin,
+        vcc_b: Pin,
+    }
+
+    impl Chip for DualSupply {
+        fn list_pins(&self) -> Vec<(PinId, &Pin)> {
+            vec![(1, &self.vcc_a), (2, &self.vcc_b)]
+        }
+
+        fn get_pin(&self, pin: PinId) -> Option<&Pin> {
+            [&self.vcc_a, &self.vcc_b].get(pin.wrapping_sub(1)).copied()
+        }
+
+        fn get_pin_mut(&mut self, pin: PinId) -> Option<&mut Pin> {
+            match pin {
+                1 => Some(&mut self.vcc_a),
+                2 => Some(&mut self.vcc_b),
+                _ => None,
+            }
+        }
+
+        fn pin_metadata(&self) -> Vec<PinMeta> {
+            [1, 2]
+                .map(|id| PinMeta {
+                    id,
+                    name: None,
+                    role: PinRole::Power,
+                })
+                .to_vec()
+        }
+    }
+
+    impl ChipRunner for DualSupply {
+        fn run(&mut self, _: Duration) {}
+    }
+
+    #[test]
+    fn validate_reports_an_unpowered_chip_once() {
+        let mut board: Board<DualSupply> = Board::new();
+        let chip = board.register_chip(DualSupply::default());
+        let powered = board.register_chip(DualSupply::default());
+        board.force_pin(powered, 1, State::High);
+        board.force_pin(powered, 2, State::High);
+
+        let warnings = board.validate();
+        assert!(
+            matches!(warnings[..], [ValidationWarning::UnpoweredChip(id)] if id == chip),
+            "{warnings:?}"
+        );
+    }
+
+    #[test]
+    fn resolution_policies_with_two_drivers() {
+        let cases = [
+            (
+                State::High,
+                State::Low,
+                ResolutionPolicy::WiredOr,
+                State::High,
+            ),
+            (
+                State::High,
+                State::Low,
+                ResolutionPolicy::WiredAnd,
+                State::Low,
+            ),
+            (
+                State::High,
+                State::Low,
+                ResolutionPolicy::DominantLow,
+                State::Low,
+            ),
+            (
+                State::High,
+                State::Low,
+                ResolutionPolicy::Analog,
+                State::Analog(2.5),
+            ),
+            (
+                State::Analog(1.0),
+                State::Analog(4.0),
+                ResolutionPolicy::WiredOr,
+                State::Analog(4.0),
+            ),
+            (
+                State::Analog(1.0),
+                State::Analog(4.0),
+                ResolutionPolicy::WiredAnd,
+                State::Analog(1.0),
+            ),
+            (
+                State::Analog(1.0),
+                State::High,
+                ResolutionPolicy::DominantLow,
+                State::High,
+            ),
+            (
+                State::Analog(1.0),
+                State::Analog(4.0),
+                ResolutionPolicy::Analog,
+                State::Analog(2.5),
+            ),
+            // undefined drivers don't take part in the resolution
+            (
+                State::Undefined,
+                State::Analog(2.0),
+                ResolutionPolicy::Analog,
+                State::Analog(2.0),
+            ),
+        ];
+        for (a, b, policy, expected) in cases {
+            let mut board: Board<ChipSet> = Board::new();
+            let gen_a = board.register_chip(Generator::build().with_state(a).into());
+            let gen_b = board.register_chip(Generator::build().with_state(b).into());
+            let trace = board.connect(gen_a, Generator::OUT, gen_b, Generator::OUT);
+            board.set_trace_policy(trace, policy);
+            board.run(Duration::from_millis(1));
+            assert_eq!(
+                board.get_trace_state(&trace),
+                Some(expected),
+                "{a:?} and {b:?} with {policy:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn absolute_stores_land_in_the_ram() {
+        let mut computer = computer(&[
+            Opcode::LDA(AddressingMode::Immediate(0x42)).into(),
+            Opcode::STA(AddressingMode::Absolute(0x0234)).into(),
+            Opcode::LDX(AddressingMode::Immediate(0x17)).into(),
+            Opcode::STX(AddressingMode::Absolute(0x0235)).into(),
+            Opcode::LDY(AddressingMode::Immediate(0x99)).into(),
+            Opcode::STY(AddressingMode::Absolute(0x0236)).into(),
+            Opcode::BMI(-2).into(),
+            Opcode::BPL(-4).into(),
+        ]);
+        computer.run_cycles(40);
+        // the RAM is mirrored every 256 bytes
+        assert_eq!(computer.ram(0x34), 0x42);
+        assert_eq!(computer.ram(0x35), 0x17);
+        assert_eq!(computer.ram(0x36), 0x99);
+    }
+
+    #[test]
+    fn power_and_ground_rails_join_their_pins() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gnd = board.register_chip(Generator::build().with_state(State::Low).into());
+        let clock = board.register_chip(Clock::build().into());
+        let cpu = board.register_chip(Nes6502::build().into());
+        let not = board.register_chip(NotGate::build().into());
+        let rom = board.register_chip(Rom256B::build().into());
+        let ram = board.register_chip(Ram256B::build().into());
+        // the VCC rail of the test-6502 example
+        let vcc_pins = [
+            (clock, Clock::VCC),
+            (cpu, Nes6502::VCC),
+            (not, NotGate::VCC),
+            (rom, Rom256B::VCC),
+            (ram, Ram256B::VCC),
+        ];
+        let gnd_pins = [(not, NotGate::GND), (ram, Ram256B::GND)];
+
+        let power = board.power_rail(vcc, &vcc_pins);
+        let ground = board.ground_rail(gnd, &gnd_pins);
+
+        let mut expected = vec![(vcc, Generator::OUT)];
+        expected.extend(vcc_pins);
+        assert_eq!(board.get_trace(&power).unwrap().get_connections(), expected);
+        let mut expected = vec![(gnd, Generator::OUT)];
+        expected.extend(gnd_pins);
+        assert_eq!(
+            board.get_trace(&ground).unwrap().get_connections(),
+            expected
+        );
+
+        board.run(Duration::from_millis(1));
+        assert_eq!(board.get_trace_state(&power), Some(State::High));
+        assert_eq!(board.get_trace_state(&ground), Some(State::Low));
+    }
+
+    #[test]
+    fn bus_to_memory_wires_every_line() {
+        let mut board: Board<ChipSet> = Board::new();
+        let cpu = board.register_chip(Nes6502::build().into());
+        let ram = board.register_chip(Ram256B::build().into());
+        let rom = board.register_chip(Rom256B::build().into());
+        let cpu_pins = [
+            Nes6502::A0,
+            Nes6502::A1,
+            Nes6502::A2,
+            Nes6502::A3,
+            Nes6502::A4,
+            Nes6502::A5,
+            Nes6502::A6,
+            Nes6502::A7,
+            Nes6502::D0,
+            Nes6502::D1,
+            Nes6502::D2,
+            Nes6502::D3,
+            Nes6502::D4,
+            Nes6502::D5,
+            Nes6502::D6,
+            Nes6502::D7,
+            Nes6502::RW,
+        ];
+        let memory_pins = [
+            Ram256B::A0,
+            Ram256B::A1,
+            Ram256B::A2,
+            Ram256B::A3,
+            Ram256B::A4,
+            Ram256B::A5,
+            Ram256B::A6,
+            Ram256B::A7,
+            Ram256B::IO0,
+            Ram256B::IO1,
+            Ram256B::IO2,
+            Ram256B::IO3,
+            Ram256B::IO4,
+            Ram256B::IO5,
+            Ram256B::IO6,
+            Ram256B::IO7,
+            Ram256B::WE,
+        ];
+        let cpu_bus = MemoryBus {
+            address: &cpu_pins[..8],
+            data: &cpu_pins[8..16],
+            control: &cpu_pins[16..],
+        };
+        let memory_bus = MemoryBus {
+            address: &memory_pins[..8],
+            data: &memory_pins[8..16],
+            control: &memory_pins[16..],
+        };
+
+        let traces = board.connect_bus_to_memory(cpu, &cpu_bus, ram, &memory_bus);
+        assert_eq!(traces.len(), 17);
+        assert_eq!(board.traces.as_vec().len(), 17);
+        for ((trace, cpu_pin), memory_pin) in traces.iter().zip(cpu_pins).zip(memory_pins) {
+            assert_eq!(
+                board.get_trace(trace).unwrap().get_connections(),
+                [(cpu, cpu_pin), (ram, memory_pin)]
+            );
+        }
+
+        // a second memory joins the traces of the first one, the ROM having the RAM's pinout
+        let address_and_data = MemoryBus {
+            control: &[],
+            ..memory_bus
+        };
+        let rom_traces = board.connect_bus_to_memory(cpu, &cpu_bus, rom, &address_and_data);
+        assert_eq!(rom_traces, traces[..16]);
+        assert_eq!(
+            board.get_trace(&traces[0]).unwrap().get_connections(),
+            [(cpu, Nes6502::A0), (ram, Ram256B::A0), (rom, Rom256B::A0)]
+        );
+    }
+
+    #[test]
+    fn memory_events_are_polled_after_a_run() {
+        let mut computer = computer(&[
+            Opcode::LDA(AddressingMode::Immediate(0x42)).into(),
+            Opcode::STA(AddressingMode::ZeroPage(0x10)).into(),
+            Opcode::LDX(AddressingMode::ZeroPage(0x10)).into(),
+            Opcode::STX(AddressingMode::Absolute(0x0011)).into(),
+            Opcode::BMI(-2).into(),
+            Opcode::BPL(-4).into(),
+        ]);
+        let ram = computer.ram;
+        computer
+            .board
+            .replace_chip(ram, Ram256B::build().with_events(true).into());
+        computer.run_cycles(40);
+
+        let Some(ChipSet::Ram256B(ram)) = computer.board.get_chip_mut(&ram) else {
+            unreachable!()
+        };
+        // the RAM isn't selected while the CPU reads the program from the ROM
+        assert_eq!(
+            ram.take_events(),
+            [
+                MemoryEvent::Write {
+                    address: 0x10,
+                    value: 0x42
+                },
+                MemoryEvent::Read {
+                    address: 0x10,
+                    value: 0x42
+                },
+                MemoryEvent::Write {
+                    address: 0x11,
+                    value: 0x42
+                },
+            ]
+        );
+        assert!(ram.take_events().is_empty());
+    }
+
+    #[test]
+    fn profile_report_times_every_chip_type() {
+        let mut computer = computer(&[Opcode::BMI(-2).into(), Opcode::BPL(-4).into()]);
+
+        let report = computer.board.run_profiled(STEP);
+        assert_eq!(
+            report.chips.keys().copied().collect::<Vec<_>>(),
+            [
+                "Clock",
+                "Generator",
+                "Nes6502",
+                "NotGate",
+                "Ram256B",
+                "Rom256B"
+            ]
+        );
+        assert!(report.chips.values().all(|time| !time.is_zero()));
+        assert!(!report.traces.is_zero());
+
+        // constant sources aren't run again once settled
+        let report = computer.board.run_profiled(STEP);
+        assert!(!report.chips.contains_key("Generator"));
+        assert_eq!(report.chips.len(), 5);
+    }
+
+    #[test]
+    fn run_until_stops_once_the_cpu_halts() {
+        let mut computer = computer(&[Opcode::BMI(-2).into(), Opcode::BPL(-4).into()]);
+        let cpu = computer.cpu;
+        let board = &mut computer.board;
+        // power the CPU for 20ms only
+        let vcc = board.find(|chip| matches!(chip, ChipSet::Generator(_)))[0];
+        let power = board.register_chip(
+            PatternGenerator::build()
+                .with_pattern(&[true, false])
+                .with_period(Duration::from_millis(20))
+                .into(),
+        );
+        for (_, trace) in board.traces.as_mut_vec() {
+            trace.disconnect(cpu, Nes6502::VCC);
+        }
+        board.connect(vcc, Generator::OUT, power, PatternGenerator::VCC);
+        board.connect(power, PatternGenerator::OUT, cpu, Nes6502::VCC);
+
+        let halted = board.run_until(
+            STEP,
+            Duration::from_millis(100),
+            |board| matches!(board.get_chip(&cpu), Some(ChipSet::Nes6502(cpu)) if cpu.is_halted()),
+        );
+
+        assert!(halted);
+    }
+
+    #[test]
+    fn pullup_sets_the_level_of_a_released_button() {
+        let mut board: Board<ChipSet> = Board::new();
+        let gnd = board.register_chip(Generator::build().with_state(State::Low).into());
+        let button = board.register_chip(Button::build().into());
+        board.connect(gnd, Generator::OUT, button, Button::I);
+        let net = board.connect_many(&[(button, Button::O)]);
+
+        board.run(Duration::from_millis(1));
+        assert_eq!(board.get_trace_state(&net), Some(State::Undefined));
+
+        board.add_pullup(net);
+        board.run(Duration::from_millis(1));
+        assert_eq!(board.get_trace_state(&net), Some(State::High));
+
+        // the button drives the net once pressed
+        let Some(ChipSet::Button(chip)) = board.get_chip_mut(&button) else {
+            unreachable!()
+        };
+        chip.press();
+        board.run(Duration::from_millis(1));
+        assert_eq!(board.get_trace_state(&net), Some(State::Low));
+    }
+
+    #[test]
+    fn active_low_button_pulls_its_net_down_when_pressed() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().with_state(State::High).into());
+        let button = board.register_chip(Button::build().with_active_low(true).into());
+        board.connect(vcc, Generator::OUT, button, Button::I);
+        let net = board.connect_many(&[(button, Button::O)]);
+        board.add_pullup(net);
+
+        let toggle = |board: &mut Board<ChipSet>, down: bool| {
+            let Some(ChipSet::Button(chip)) = board.get_chip_mut(&button) else {
+                unreachable!()
+            };
+            if down {
+                chip.press();
+            } else {
+                chip.release();
+            }
+            board.run(Duration::from_millis(1));
+            board.get_trace_state(&net)
+        };
+        assert_eq!(toggle(&mut board, false), Some(State::High));
+        // IN is ignored, the button shorts its net to the ground
+        assert_eq!(toggle(&mut board, true), Some(State::Low));
+        assert_eq!(toggle(&mut board, false), Some(State::High));
+    }
+
+    #[test]
+    fn restoring_a_snapshot_brings_back_the_pre_run_state() {
+        let mut computer = computer(&[
+            Opcode::LDA(AddressingMode::Immediate(0x42)).into(),
+            Opcode::STA(AddressingMode::ZeroPage(0x10)).into(),
+            Opcode::BMI(-2).into(),
+            Opcode::BPL(-4).into(),
+        ]);
+        let trace = computer.board.connect_many(&[(computer.cpu, Nes6502::IRQ)]);
+        computer.board.add_pullup(trace);
+        computer.board.set_position(computer.cpu, 1.0, 2.0);
+        let before = format!("{:?}", computer.board);
+        let snapshot = computer.board.snapshot();
+
+        computer.run_cycles(40);
+        computer.board.set_rails(0.0..=3.3);
+        computer.board.remove_pull(trace);
+        computer.board.add_pulldown(trace);
+        computer
+            .board
+            .set_trace_policy(trace, ResolutionPolicy::WiredAnd);
+        computer.board.set_position(computer.cpu, 3.0, 4.0);
+        assert_eq!(computer.ram(0x10), 0x42);
+        assert_ne!(format!("{:?}", computer.board), before);
+
+        computer.board.restore(&snapshot);
+        assert_eq!(format!("{:?}", computer.board), before);
+        assert_eq!(computer.board.position(&computer.cpu), Some((1.0, 2.0)));
+        assert_eq!(computer.board.get_trace_state(&trace), Some(State::High));
+    }
+
+    #[test]
+    fn ring_of_three_inverters_oscillates() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let inverters = [(); 3].map(|_| board.register_chip(NotGate::build().into()));
+        board.connect_many(&[
+            (vcc, Generator::OUT),
+            (inverters[0], NotGate::VCC),
+            (inverters[1], NotGate::VCC),
+            (inverters[2], NotGate::VCC),
+        ]);
+        for (index, inverter) in inverters.iter().enumerate() {
+            board.connect(
+                *inverter,
+                NotGate::NOT_A,
+                inverters[(index + 1) % 3],
+                NotGate::A,
+            );
+        }
+
+        match board.run_until_stable(Duration::from_millis(1), 100) {
+            Err(StabilityError::OscillationDetected(chips)) => {
+                // the generator never changes, so only the inverters are involved
+                assert_eq!(chips.len(), 3);
+                assert!(inverters.iter().all(|inverter| chips.contains(inverter)));
+            }
+            result => panic!("expected an oscillation, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn timeline_restores_an_earlier_count() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+        let counter = board.register_chip(UpDownCounter4Bit::build().into());
+        board.power_rail(
+            vcc,
+            &[(clock, Clock::VCC), (counter, UpDownCounter4Bit::VCC)],
+        );
+        board.connect(clock, Clock::CLK, counter, UpDownCounter4Bit::CLK);
+        let count = |board: &Board<ChipSet>| match board.get_chip(&counter) {
+            Some(ChipSet::UpDownCounter4Bit(counter)) => counter.value(),
+            _ => unreachable!(),
+        };
+        let step = Duration::from_micros(100);
+        // a snapshot every millisecond, keeping the last 5
+        let mut recorder = TimelineRecorder::new(10, 5);
+
+        recorder.run_during(&mut board, Duration::from_millis(7), step);
+        let earlier = count(&board);
+        recorder.run_during(&mut board, Duration::from_millis(3), step);
+        assert_ne!(count(&board), earlier);
+        assert_eq!(recorder.tick(), 100);
+        // the oldest snapshots were dropped
+        assert!(recorder.snapshot_at(50).is_none());
+
+        assert!(recorder.restore_to(&mut board, 70));
+        assert_eq!(count(&board), earlier);
+        assert_eq!(recorder.tick(), 70);
+        assert!(recorder.snapshot_at(80).is_none());
+    }
+
+    #[test]
+    fn switching_counts_the_clock_transitions() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+        board.connect(vcc, Generator::OUT, clock, Clock::VCC);
+        board.run(Duration::from_micros(100));
+        assert_eq!(board.estimate_switching(), None);
+
+        board.enable_switching_stats();
+        let switching: Vec<_> = (0..20)
+            .map(|_| {
+                board.run(Duration::from_micros(100));
+                board.estimate_switching().unwrap()
+            })
+            .collect();
+        // the clock toggles every 5 steps
+        assert_eq!(
+            switching,
+            [0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0]
+        );
+
+        board.disable_switching_stats();
+        board.run(Duration::from_micros(100));
+        assert_eq!(board.estimate_switching(), None);
+    }
+
+    #[test]
+    fn run_until_inspects_the_board_between_steps() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+        board.connect(vcc, Generator::OUT, clock, Clock::VCC);
+
+        // the condition is where a host reacts to the board, instead of from within a run
+        let mut seen = vec![];
+        let met = board.run_until(
+            Duration::from_micros(100),
+            Duration::from_millis(10),
+            |board| {
+                seen.push(board.get_chip(&clock).unwrap().get_pin_state(Clock::CLK));
+                seen.len() == 12
+            },
+        );
+
+        assert!(met);
+        assert_eq!(
+            seen,
+            [&[State::Low; 4][..], &[State::High; 5], &[State::Low; 3]].concat()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn loading_an_unknown_version_fails_clearly() {
+        use super::{BoardError, SAVE_VERSION};
+
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gate = board.register_chip(AndGate::build().into());
+        board.connect(vcc, Generator::OUT, gate, AndGate::VCC);
+        let mut saved = vec![];
+        board.save_to_writer(&mut saved).unwrap();
+        let saved = String::from_utf8(saved).unwrap();
+
+        let loaded: Board<ChipSet> = Board::load_from_reader(saved.as_bytes()).unwrap();
+        assert_eq!(format!("{loaded:?}"), format!("{board:?}"));
+
+        let newer = saved.replacen(
+            &format!("version: {SAVE_VERSION}"),
+            &format!("version: {}", SAVE_VERSION + 1),
+            1,
+        );
+        assert_ne!(newer, saved);
+        assert!(matches!(
+            Board::<ChipSet>::load_from_reader(newer.as_bytes()),
+            Err(BoardError::UnsupportedVersion { found, supported: SAVE_VERSION })
+                if found == SAVE_VERSION + 1
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn boards_saved_before_versioning_still_load() {
+        use crate::chip::outputs::SegmentDisplay;
+
+        // serialized directly by the code preceding `save_to_writer`, without any version
+        let saved = include_str!("../tests/fixtures/v0_board.ron");
+        let mut board: Board<ChipSet> = Board::load_from_reader(saved.as_bytes()).unwrap();
+
+        let names: Vec<_> = board
+            .chips
+            .as_vec()
+            .into_iter()
+            .map(|(_, chip)| chip.chip_name())
+            .collect();
+        assert_eq!(
+            names,
+            ["Generator", "AndGate", "Ram256B", "SegmentDisplay", "Clock"]
+        );
+        board.run(Duration::from_millis(1));
+        let display = board.find(|chip| matches!(chip, ChipSet::SegmentDisplay(_)))[0];
+        let Some(ChipSet::SegmentDisplay(display)) = board.get_chip(&display) else {
+            unreachable!()
+        };
+        assert_eq!(display.a.state, State::High);
+        assert_eq!(display.get_pin_state(SegmentDisplay::DP), State::Undefined);
+    }
+
+    #[test]
+    fn clock_net_history_alternates() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+        let gate = board.register_chip(NotGate::build().into());
+        board.power_rail(vcc, &[(clock, Clock::VCC), (gate, NotGate::VCC)]);
+        let net = board.connect(clock, Clock::CLK, gate, NotGate::A);
+        board.get_trace_mut(&net).unwrap().enable_history();
+
+        board.run_during(Duration::from_millis(4), Duration::from_micros(250));
+        // the clock toggles every 2 steps, and the net lags it by one step as it gets resolved
+        // before the clock runs
+        let mut expected = vec![State::Undefined, State::Low];
+        for _ in 0..7 {
+            let level = *expected.last().unwrap() == State::Low;
+            expected.extend([State::from(level); 2]);
+        }
+        assert_eq!(board.get_trace(&net).unwrap().history(), expected);
+
+        board.get_trace_mut(&net).unwrap().disable_history();
+        board.run(Duration::from_micros(250));
+        assert!(board.get_trace(&net).unwrap().history().is_empty());
+    }
+
+    #[test]
+    fn positions_are_stored_per_chip() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let gate = board.register_chip(AndGate::build().into());
+
+        board.set_position(vcc, 10.0, 20.0);
+        board.set_position(gate, 30.0, 40.0);
+        board.set_position(gate, 35.0, 45.0);
+        assert_eq!(board.position(&vcc), Some((10.0, 20.0)));
+        assert_eq!(board.position(&gate), Some((35.0, 45.0)));
+
+        let other = board.register_chip(AndGate::build().into());
+        assert_eq!(board.position(&other), None);
+    }
+
+    #[test]
+    fn run_cycles_counts_rising_edges() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+        board.connect(vcc, Generator::OUT, clock, Clock::VCC);
+
+        let steps = board
+            .run_cycles(clock, Clock::CLK, 5, Duration::from_micros(100))
+            .unwrap();
+
+        // the clock starts low and rises after half a period, then once every 10 steps
+        assert_eq!(steps, 45);
+        let Some(ChipSet::Clock(clock)) = board.get_chip(&clock) else {
+            unreachable!()
+        };
+        // 5 rising edges, and the 4 falling edges between them
+        assert_eq!(clock.pending_edges(), 9);
+    }
+
+    #[test]
+    fn run_cycles_bounded_gives_up_on_a_stopped_clock() {
+        let mut board: Board<ChipSet> = Board::new();
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+
+        assert!(matches!(
+            board.run_cycles_bounded(clock, Clock::CLK, 5, Duration::from_micros(100), 1000),
+            Err(CycleError::StepCapReached { edges: 0 })
+        ));
+        assert!(matches!(
+            board.run_cycles(clock, 42, 5, Duration::from_micros(100)),
+            Err(CycleError::UnknownPin(_, 42))
+        ));
+    }
+
+    #[test]
+    fn power_cycle_reinitializes_the_ram() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let ram = board.register_chip(Ram256B::build().with_init(RamInit::Pattern(0xA5)).into());
+        board.connect(vcc, Generator::OUT, ram, Ram256B::VCC);
+        let data = |board: &Board<ChipSet>| match board.get_chip(&ram) {
+            Some(ChipSet::Ram256B(ram)) => ram.data().to_vec(),
+            _ => unreachable!(),
+        };
+
+        // write 0x00 at address 0x00
+        for pin in [
+            Ram256B::CS,
+            Ram256B::WE,
+            Ram256B::A0,
+            Ram256B::A1,
+            Ram256B::A2,
+            Ram256B::A3,
+            Ram256B::A4,
+            Ram256B::A5,
+            Ram256B::A6,
+            Ram256B::A7,
+            Ram256B::IO0,
+            Ram256B::IO1,
+            Ram256B::IO2,
+            Ram256B::IO3,
+            Ram256B::IO4,
+            Ram256B::IO5,
+            Ram256B::IO6,
+            Ram256B::IO7,
+        ] {
+            board.force_pin(ram, pin, State::Low);
+        }
+        board.run(Duration::from_millis(1));
+        assert_eq!(data(&board)[0], 0x00);
+        assert!(data(&board)[1..].iter().all(|byte| *byte == 0xA5));
+
+        board.force_pin(ram, Ram256B::WE, State::High);
+        board.set_power(false);
+        board.run(Duration::from_millis(1));
+        // the memory only gets refilled once the power comes back
+        assert_eq!(data(&board)[0], 0x00);
+
+        board.set_power(true);
+        board.run(Duration::from_millis(1));
+        assert!(data(&board).iter().all(|byte| *byte == 0xA5));
     }
 }
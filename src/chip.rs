@@ -1,10 +1,20 @@
+pub mod analog;
+pub mod arithmetic;
+pub mod bus;
 pub mod clocks;
+pub mod counters;
 pub mod cpu;
 pub mod gates;
 pub mod generators;
 pub mod inputs;
+pub mod latches;
 pub mod memories;
+pub mod multiplexers;
 pub mod outputs;
+pub mod ports;
+pub mod registers;
+pub mod serial;
+pub mod subcircuit;
 
 use std::{fmt::Debug, time::Duration};
 
@@ -18,18 +28,206 @@ pub trait ChipBuilder<C: Chip> {
 
 pub trait ChipRunner {
     fn run(&mut self, tick_duration: Duration);
+
+    /// Bring the chip back to its power-on state, does nothing for stateless chips
+    fn reset(&mut self) {}
 }
 
 pub trait Chip: Debug + Clone + ChipRunner {
     fn list_pins(&self) -> Vec<(PinId, &Pin)>;
     fn get_pin(&self, pin: PinId) -> Option<&Pin>;
     fn get_pin_mut(&mut self, pin: PinId) -> Option<&mut Pin>;
+
+    /// Get the state of a pin, or `State::Undefined` if the pin doesn't exist
+    fn get_pin_state(&self, pin: PinId) -> State {
+        self.get_pin(pin).map(|p| p.state).unwrap_or_default()
+    }
+
+    /// Get the name of a pin, or `None` if the pin doesn't exist or the chip doesn't name its pins
+    fn get_pin_name(&self, _pin: PinId) -> Option<&'static str> {
+        None
+    }
+
+    /// Get the number of pins listed by `list_pins`, without listing them when the chip
+    /// knows it beforehand
+    fn pin_count(&self) -> usize {
+        self.list_pins().len()
+    }
+
+    /// Whether the chip is a constant source, whose outputs only change when it gets modified
+    /// from outside of the board, like a generator.
+    /// The board skips running such chips until one of its chips gets modified.
+    fn is_constant_source(&self) -> bool {
+        false
+    }
+
+    /// Get the name of the chip, defaults to the name of its type
+    fn chip_name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        // the generic arguments of the type hold paths too, so they're cut off first
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// Set the state of a pin, does nothing if the pin doesn't exist
+    fn set_pin_state(&mut self, pin: PinId, state: State) {
+        if let Some(p) = self.get_pin_mut(pin) {
+            p.state = state;
+        }
+    }
+
+    /// Describe every pin of the chip, sorted by id.
+    /// Roles are deduced from the pin names and their current type.
+    fn pin_metadata(&self) -> Vec<PinMeta> {
+        let mut pins: Vec<_> = self
+            .list_pins()
+            .into_iter()
+            .map(|(id, pin)| {
+                let name = self.get_pin_name(id);
+                let role = match (name, pin.pin_type) {
+                    (Some("vcc"), _) => PinRole::Power,
+                    (Some("gnd"), _) => PinRole::Ground,
+                    (Some("clk"), _) => PinRole::Clock,
+                    (_, PinType::Input) => PinRole::Input,
+                    (_, PinType::Output) => PinRole::Output,
+                    (_, PinType::Floating) => PinRole::Bidirectional,
+                };
+                PinMeta { id, name, role }
+            })
+            .collect();
+        pins.sort_by_key(|pin| pin.id);
+        pins
+    }
+}
+
+/// Description of a pin given by `Chip::pin_metadata`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PinMeta {
+    pub id: PinId,
+    pub name: Option<&'static str>,
+    pub role: PinRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinRole {
+    Power,
+    Ground,
+    Clock,
+    Input,
+    Output,
+    /// The pin changes direction while the chip runs, like a data bus
+    Bidirectional,
+}
+
+/// Object-safe counterpart of [`Chip`], implemented for every chip.
+///
+/// A `Board<Box<dyn DynChip>>` can mix built-in and custom chips without declaring a wrapper
+/// enum, at the cost of not being serializable.
+pub trait DynChip: Debug + ChipRunner {
+    fn dyn_list_pins(&self) -> Vec<(PinId, &Pin)>;
+    fn dyn_get_pin(&self, pin: PinId) -> Option<&Pin>;
+    fn dyn_get_pin_mut(&mut self, pin: PinId) -> Option<&mut Pin>;
+    fn dyn_get_pin_name(&self, pin: PinId) -> Option<&'static str>;
+    fn dyn_pin_count(&self) -> usize;
+    fn dyn_is_constant_source(&self) -> bool;
+    fn dyn_chip_name(&self) -> &'static str;
+    fn clone_box(&self) -> Box<dyn DynChip>;
+}
+
+impl<T> DynChip for T
+where
+    T: Chip + 'static,
+{
+    fn dyn_list_pins(&self) -> Vec<(PinId, &Pin)> {
+        Chip::list_pins(self)
+    }
+
+    fn dyn_get_pin(&self, pin: PinId) -> Option<&Pin> {
+        Chip::get_pin(self, pin)
+    }
+
+    fn dyn_get_pin_mut(&mut self, pin: PinId) -> Option<&mut Pin> {
+        Chip::get_pin_mut(self, pin)
+    }
+
+    fn dyn_get_pin_name(&self, pin: PinId) -> Option<&'static str> {
+        Chip::get_pin_name(self, pin)
+    }
+
+    fn dyn_pin_count(&self) -> usize {
+        Chip::pin_count(self)
+    }
+
+    fn dyn_is_constant_source(&self) -> bool {
+        Chip::is_constant_source(self)
+    }
+
+    fn dyn_chip_name(&self) -> &'static str {
+        Chip::chip_name(self)
+    }
+
+    fn clone_box(&self) -> Box<dyn DynChip> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn DynChip> {
+    fn clone(&self) -> Self {
+        self.as_ref().clone_box()
+    }
+}
+
+impl ChipRunner for Box<dyn DynChip> {
+    fn run(&mut self, tick_duration: Duration) {
+        self.as_mut().run(tick_duration)
+    }
+
+    fn reset(&mut self) {
+        self.as_mut().reset()
+    }
+}
+
+impl Chip for Box<dyn DynChip> {
+    fn list_pins(&self) -> Vec<(PinId, &Pin)> {
+        DynChip::dyn_list_pins(self.as_ref())
+    }
+
+    fn get_pin(&self, pin: PinId) -> Option<&Pin> {
+        DynChip::dyn_get_pin(self.as_ref(), pin)
+    }
+
+    fn get_pin_mut(&mut self, pin: PinId) -> Option<&mut Pin> {
+        DynChip::dyn_get_pin_mut(self.as_mut(), pin)
+    }
+
+    fn get_pin_name(&self, pin: PinId) -> Option<&'static str> {
+        DynChip::dyn_get_pin_name(self.as_ref(), pin)
+    }
+
+    fn pin_count(&self) -> usize {
+        DynChip::dyn_pin_count(self.as_ref())
+    }
+
+    fn is_constant_source(&self) -> bool {
+        DynChip::dyn_is_constant_source(self.as_ref())
+    }
+
+    fn chip_name(&self) -> &'static str {
+        DynChip::dyn_chip_name(self.as_ref())
+    }
 }
 
 #[macro_export]
 macro_rules! impl_chip_type {
     ( $type:ident: ($($variant:ident),*)) => {
         impl $crate::chip::Chip for $type {
+            /// Get the name of the variant holding the chip
+            fn chip_name(&self) -> &'static str {
+                match self {
+                    $($type::$variant(_) => stringify!($variant)),*
+                }
+            }
+
             fn list_pins(&self) -> ::std::vec::Vec<($crate::chip::PinId, &$crate::chip::Pin)> {
                 match self {
                     $($type::$variant(chip) => chip.list_pins()),*
@@ -47,6 +245,24 @@ macro_rules! impl_chip_type {
                     $($type::$variant(chip) => chip.get_pin_mut(pin)),*
                 }
             }
+
+            fn pin_count(&self) -> usize {
+                match self {
+                    $($type::$variant(chip) => chip.pin_count()),*
+                }
+            }
+
+            fn is_constant_source(&self) -> bool {
+                match self {
+                    $($type::$variant(chip) => chip.is_constant_source()),*
+                }
+            }
+
+            fn get_pin_name(&self, pin: $crate::chip::PinId) -> ::std::option::Option<&'static str> {
+                match self {
+                    $($type::$variant(chip) => chip.get_pin_name(pin)),*
+                }
+            }
         }
         impl $crate::chip::ChipRunner for $type {
             fn run(&mut self, tick_duration: ::std::time::Duration) {
@@ -54,6 +270,12 @@ macro_rules! impl_chip_type {
                     $($type::$variant(chip) => chip.run(tick_duration)),*
                 }
             }
+
+            fn reset(&mut self) {
+                match self {
+                    $($type::$variant(chip) => chip.reset()),*
+                }
+            }
         }
     };
 }
@@ -70,16 +292,91 @@ pub enum ChipSet {
     ThreeInputNorGate(gates::ThreeInputNorGate),
     NorGate(gates::NorGate),
     NotGate(gates::NotGate),
+    HalfAdder(arithmetic::HalfAdder),
+    FullAdder(arithmetic::FullAdder),
+    Comparator8(arithmetic::Comparator8),
     Generator(generators::Generator),
     Clock(clocks::Clock),
+    PatternGenerator(clocks::PatternGenerator),
+    Oscillator(clocks::Oscillator),
+    Timer555(clocks::Timer555),
+    TwoPhaseClock(clocks::TwoPhaseClock),
+    RcFilter(analog::RcFilter),
+    Adc8(analog::Adc8),
+    Dac8(analog::Dac8),
+    Mixer(analog::Mixer),
     Ram256B(memories::Ram256B),
     Ram8KB(memories::Ram8KB),
     Rom256B(memories::Rom256B),
     Rom8KB(memories::Rom8KB),
+    BankedRom(memories::BankedRom),
+    MemoryMap(bus::MemoryMap),
+    Demux1to8(multiplexers::Demux1to8),
+    Register8(registers::Register8),
+    DemuxAddressLatch(registers::DemuxAddressLatch),
+    GrayCounter4Bit(counters::GrayCounter4Bit),
+    UpDownCounter4Bit(counters::UpDownCounter4Bit),
+    ClockDivider(counters::ClockDivider),
+    FrequencyCounter(counters::FrequencyCounter),
+    Timer(counters::Timer),
+    Watchdog(counters::Watchdog),
+    SrLatch(latches::SrLatch),
+    DLatch(latches::DLatch),
     Button(inputs::Button),
+    KeypadMatrix4x4(inputs::KeypadMatrix4x4),
+    InputBridge(inputs::InputBridge),
+    GpioPort8(ports::GpioPort8),
+    Uart(serial::Uart),
+    I2cController(serial::I2cController),
+    I2cSlave(serial::I2cSlave),
+    SpiMaster(serial::SpiMaster),
+    SpiSlave(serial::SpiSlave),
     Nes6502(Box<cpu::nes6502::Nes6502>),
     SevenSegmentDecoder(outputs::SevenSegmentsDecoder),
     SegmentDisplay(outputs::SegmentDisplay),
+    LcdHd44780(outputs::LcdHd44780),
+    LogicAnalyzer(outputs::LogicAnalyzer),
+    Subcircuit(Box<subcircuit::Subcircuit<ChipSet>>),
+}
+
+#[cfg(feature = "json")]
+impl ChipSet {
+    /// Describe the pinout of the chip as JSON: its name, pin count, and the id, name and type
+    /// of each pin. Fails if the pinout can't be serialized.
+    pub fn pinout_json(&self) -> Result<String, serde_json::Error> {
+        #[derive(serde::Serialize)]
+        struct PinoutPin {
+            id: PinId,
+            name: Option<&'static str>,
+            #[serde(rename = "type")]
+            pin_type: PinType,
+        }
+        #[derive(serde::Serialize)]
+        struct Pinout {
+            name: &'static str,
+            pin_count: usize,
+            pins: Vec<PinoutPin>,
+        }
+
+        let pins: Vec<_> = self
+            .pin_metadata()
+            .into_iter()
+            .map(|meta| PinoutPin {
+                id: meta.id,
+                name: meta.name,
+                pin_type: self
+                    .get_pin(meta.id)
+                    .map(|pin| pin.pin_type)
+                    .unwrap_or_default(),
+            })
+            .collect();
+        let pinout = Pinout {
+            name: self.chip_name(),
+            pin_count: self.pin_count(),
+            pins,
+        };
+        serde_json::to_string(&pinout)
+    }
 }
 
 #[deprecated(since = "0.5.1", note = "Please use `ChipSet` instead")]
@@ -97,16 +394,51 @@ impl_chip_type!(
             NorGate,
             ThreeInputNorGate,
             NotGate,
+            HalfAdder,
+            FullAdder,
+            Comparator8,
             Generator,
             Clock,
+            PatternGenerator,
+            Oscillator,
+            Timer555,
+            TwoPhaseClock,
+            RcFilter,
+            Adc8,
+            Dac8,
+            Mixer,
             Ram256B,
             Ram8KB,
             Rom256B,
             Rom8KB,
+            BankedRom,
+            MemoryMap,
+            Demux1to8,
+            Register8,
+            DemuxAddressLatch,
+            GrayCounter4Bit,
+            UpDownCounter4Bit,
+            ClockDivider,
+            FrequencyCounter,
+            Timer,
+            Watchdog,
+            SrLatch,
+            DLatch,
             Button,
+            KeypadMatrix4x4,
+            InputBridge,
+            GpioPort8,
+            Uart,
+            I2cController,
+            I2cSlave,
+            SpiMaster,
+            SpiSlave,
             Nes6502,
             SevenSegmentDecoder,
-            SegmentDisplay
+            SegmentDisplay,
+            LcdHd44780,
+            LogicAnalyzer,
+            Subcircuit
         )
 );
 
@@ -158,6 +490,27 @@ impl Pin {
         }
         value > 0
     }
+
+    /// Write a given value to a set of pins.
+    /// If the value overflows, the pins still receive its lowest bits and the amount of bits
+    /// that didn't fit is reported.
+    pub fn try_write(pins: &mut [&mut Pin], value: usize) -> Result<(), WriteOverflow> {
+        if Pin::write(pins, value) {
+            let overflow = value >> pins.len();
+            Err(WriteOverflow {
+                bits: (usize::BITS - overflow.leading_zeros()) as usize,
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Returned by `Pin::try_write` when a value doesn't fit in the pins
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOverflow {
+    /// Amount of bits that were cut off
+    pub bits: usize,
 }
 
 impl From<PinType> for Pin {
@@ -171,6 +524,24 @@ impl From<PinType> for Pin {
 
 #[macro_export]
 macro_rules! generate_chip {
+    ($struct_name:ident => $enum_name:ident, $($pin_name:ident: $chip:ident::$pin_const:ident),*) => {
+        #[doc = concat!("Pins of [`", stringify!($struct_name), "`], usable wherever a `PinId` is expected")]
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $enum_name {
+            $( $pin_const, )*
+        }
+
+        impl ::std::convert::From<$enum_name> for $crate::chip::PinId {
+            fn from(value: $enum_name) -> Self {
+                match value {
+                    $( $enum_name::$pin_const => $chip::$pin_const, )*
+                }
+            }
+        }
+
+        $crate::generate_chip!($struct_name, $($pin_name: $chip::$pin_const),*);
+    };
     ($struct_name:ident, $($pin_name:ident: $pin_id:expr),*) => {
         impl $crate::chip::Chip for $struct_name {
             fn list_pins(&self) -> ::std::vec::Vec<($crate::chip::PinId, &$crate::chip::Pin)> {
@@ -192,6 +563,199 @@ macro_rules! generate_chip {
                     _ => ::std::option::Option::None,
                 }
             }
+
+            fn get_pin_name(&self, pin: $crate::chip::PinId) -> ::std::option::Option<&'static str> {
+                match pin {
+                    $( pin_id if pin_id == $pin_id => ::std::option::Option::Some(stringify!($pin_name)), )*
+                    _ => ::std::option::Option::None,
+                }
+            }
+
+            fn pin_count(&self) -> usize {
+                [$( stringify!($pin_name), )*].len()
+            }
         }
     };
 }
+
+/// Declare a chip struct from its pins, instead of writing the fields, the pin constants, the
+/// default pins and the `generate_chip!` call separately.
+///
+/// Each pin is given as `field: CONST = id => PinType`, extra state fields can be added in an
+/// optional `state` block along with their default value. The attributes given before the struct
+/// (docs, derives...) are kept as is, and a `Default` implementation building the pins with their
+/// type is generated.
+///
+/// ```
+/// use std::time::Duration;
+/// use virt_ic::{chip::ChipRunner, define_chip};
+///
+/// define_chip! {
+///     /// A single inverter
+///     #[derive(Debug, Clone)]
+///     pub struct Inverter => InverterPin {
+///         pins {
+///             a: A = 1 => Input,
+///             na: NA = 2 => Output,
+///             gnd: GND = 3 => Output,
+///             vcc: VCC = 4 => Input,
+///         }
+///         state {
+///             inverted: usize = 0,
+///         }
+///     }
+/// }
+///
+/// impl ChipRunner for Inverter {
+///     fn run(&mut self, _: Duration) {
+///         self.na.state = virt_ic::State::from(!self.a.state.as_bool());
+///         self.inverted += 1;
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_chip {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $struct_name:ident => $enum_name:ident {
+            pins {
+                $( $pin_name:ident: $pin_const:ident = $pin_id:literal => $pin_type:ident ),* $(,)?
+            }
+            $(
+                state {
+                    $( $(#[$field_meta:meta])* $field:ident: $field_type:ty = $field_default:expr ),* $(,)?
+                }
+            )?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $struct_name {
+            $($( $(#[$field_meta])* $field: $field_type, )*)?
+            $( pub $pin_name: $crate::chip::Pin, )*
+        }
+
+        impl $struct_name {
+            $( pub const $pin_const: $crate::chip::PinId = $pin_id; )*
+        }
+
+        impl ::std::default::Default for $struct_name {
+            fn default() -> Self {
+                $struct_name {
+                    $($( $field: $field_default, )*)?
+                    $( $pin_name: $crate::chip::Pin::from($crate::chip::PinType::$pin_type), )*
+                }
+            }
+        }
+
+        $crate::generate_chip!(
+            $struct_name => $enum_name,
+            $( $pin_name: $struct_name::$pin_const ),*
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        arithmetic::HalfAdder, counters::ClockDivider, gates::AndGate, generators::Generator,
+        subcircuit::Subcircuit, Chip, ChipBuilder, ChipSet, DynChip, Pin, PinMeta, PinRole,
+        PinType, WriteOverflow,
+    };
+    use crate::{board::Board, State};
+
+    #[test]
+    fn get_and_set_pin_state() {
+        let mut gate = AndGate::build();
+        gate.set_pin_state(AndGate::A, State::High);
+        assert_eq!(gate.get_pin_state(AndGate::A), State::High);
+        assert_eq!(gate.a.state, State::High);
+        // unknown pins are ignored, and read as undefined
+        gate.set_pin_state(42, State::High);
+        assert_eq!(gate.get_pin_state(42), State::Undefined);
+    }
+
+    #[test]
+    fn pin_count_matches_the_listed_pins() {
+        let gate = AndGate::build();
+        assert_eq!(gate.pin_count(), 14);
+        assert_eq!(gate.pin_count(), gate.list_pins().len());
+        // through the chip sets too
+        assert_eq!(ChipSet::from(gate.clone()).pin_count(), 14);
+        let boxed: Box<dyn DynChip> = Box::new(gate);
+        assert_eq!(boxed.pin_count(), 14);
+        assert_eq!(Generator::build().pin_count(), 1);
+    }
+
+    #[test]
+    fn chip_name_drops_the_module_path_and_generics() {
+        assert_eq!(AndGate::build().chip_name(), "AndGate");
+        assert_eq!(
+            Subcircuit::<ChipSet>::new(Board::new()).chip_name(),
+            "Subcircuit"
+        );
+    }
+
+    #[test]
+    fn pin_metadata_names_the_pins() {
+        let pin = |id, name, role| PinMeta {
+            id,
+            name: Some(name),
+            role,
+        };
+        assert_eq!(
+            HalfAdder::build().pin_metadata(),
+            [
+                pin(HalfAdder::A, "a", PinRole::Input),
+                pin(HalfAdder::B, "b", PinRole::Input),
+                pin(HalfAdder::GND, "gnd", PinRole::Ground),
+                pin(HalfAdder::SUM, "sum", PinRole::Output),
+                pin(HalfAdder::CARRY, "carry", PinRole::Output),
+                pin(HalfAdder::VCC, "vcc", PinRole::Power),
+            ]
+        );
+        assert!(ClockDivider::build().pin_metadata().contains(&pin(
+            ClockDivider::CLK,
+            "clk",
+            PinRole::Clock
+        )));
+    }
+
+    #[test]
+    fn try_write_reports_the_overflowing_bits() {
+        let mut pins = [
+            Pin::from(PinType::Output),
+            Pin::from(PinType::Output),
+            Pin::from(PinType::Output),
+            Pin::from(PinType::Output),
+        ];
+        let [p0, p1, p2, p3] = &mut pins;
+        assert_eq!(Pin::try_write(&mut [p0, p1, p2, p3], 0b1010), Ok(()));
+        let [p0, p1, p2, p3] = &mut pins;
+        assert_eq!(
+            Pin::try_write(&mut [p0, p1, p2, p3], 0b110_0101),
+            Err(WriteOverflow { bits: 3 })
+        );
+        // the lowest bits are still written
+        let states: Vec<_> = pins.iter().map(|pin| pin.state).collect();
+        assert_eq!(states, [State::High, State::Low, State::High, State::Low]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn and_gate_pinout_json() {
+        use super::ChipSet;
+
+        let gate: ChipSet = AndGate::build().into();
+        let pinout: serde_json::Value = serde_json::from_str(&gate.pinout_json().unwrap()).unwrap();
+        assert_eq!(pinout["pin_count"], 14);
+        let pins = pinout["pins"].as_array().unwrap();
+        assert_eq!(pins.len(), 14);
+        let id_of = |name: &str| {
+            pins.iter()
+                .find(|pin| pin["name"] == name)
+                .map(|pin| pin["id"].clone())
+        };
+        assert_eq!(id_of("vcc"), Some(14.into()));
+        assert_eq!(id_of("gnd"), Some(7.into()));
+    }
+}
@@ -0,0 +1,583 @@
+use std::time::Duration;
+
+use crate::{generate_chip, State, DEFAULT_RAILS};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
+
+/// # A passive RC low-pass filter
+///
+/// OUT exponentially approaches the voltage of IN, with the given time constant (tau).
+/// Logic inputs are considered as 0V when Low and 3.3V when High.
+///
+/// # Diagram
+/// ```txt
+///         --------
+///    IN --|1    3|-- OUT
+///         |     2|-- GND
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RcFilter {
+    tau: Duration,
+    voltage: f32,
+    pub input: Pin,
+    pub gnd: Pin,
+    pub output: Pin,
+}
+
+impl RcFilter {
+    pub const IN: PinId = 1;
+    pub const GND: PinId = 2;
+    pub const OUT: PinId = 3;
+
+    /// Set the time constant of the filter, defaults to 1ms
+    pub fn with_tau(mut self, tau: Duration) -> Self {
+        self.tau = tau;
+        self
+    }
+
+    /// Get the current output voltage
+    pub fn voltage(&self) -> f32 {
+        self.voltage
+    }
+}
+
+impl ChipBuilder<RcFilter> for RcFilter {
+    fn build() -> RcFilter {
+        RcFilter {
+            tau: Duration::from_millis(1),
+            voltage: 0.0,
+            input: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            output: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<RcFilter> for ChipSet {
+    fn from(value: RcFilter) -> Self {
+        ChipSet::RcFilter(value)
+    }
+}
+
+generate_chip!(
+    RcFilter => RcFilterPin,
+    input: RcFilter::IN,
+    gnd: RcFilter::GND,
+    output: RcFilter::OUT
+);
+
+impl ChipRunner for RcFilter {
+    fn run(&mut self, tick_duration: Duration) {
+        self.gnd.state = State::Low;
+        let State::Analog(target) = self.input.state.as_analog(3.3) else {
+            unreachable!()
+        };
+        if self.tau.is_zero() {
+            self.voltage = target;
+        } else {
+            let ratio = tick_duration.as_secs_f32() / self.tau.as_secs_f32();
+            self.voltage += (target - self.voltage) * (1.0 - (-ratio).exp());
+        }
+        self.output.state = State::Analog(self.voltage);
+    }
+
+    fn reset(&mut self) {
+        self.voltage = 0.0;
+    }
+}
+
+/// # An 8-bit analog to digital converter
+///
+/// A rising edge on START samples the voltage of VIN and starts a conversion, DRDY goes low
+/// until the conversion time has elapsed, then D0-D7 hold the sampled voltage quantized
+/// against the reference voltage, and DRDY goes high again.
+/// Logic inputs are considered as 0V when Low and as the reference voltage when High.
+///
+/// # Diagram
+/// VIN: Analog input
+/// START: Start conversion
+/// DRDY: Data ready
+/// ```txt
+///          ---__---
+///    VIN --|1   14|-- VCC
+///  START --|2   13|-- D7
+///   DRDY --|3   12|-- D6
+///     D0 --|4   11|-- D5
+///     D1 --|5   10|-- D4
+///     D2 --|6    9|-- D3
+///    GND --|7    8|-- UNUSED
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Adc8 {
+    reference: f32,
+    conversion_time: Duration,
+    /// sampled code and time left before it gets output, while converting
+    conversion: Option<(u8, Duration)>,
+    code: u8,
+    start_was_high: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub vin: Pin,
+    pub start: Pin,
+    pub drdy: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+}
+
+impl Adc8 {
+    pub const VIN: PinId = 1;
+    pub const START: PinId = 2;
+    pub const DRDY: PinId = 3;
+    pub const D0: PinId = 4;
+    pub const D1: PinId = 5;
+    pub const D2: PinId = 6;
+    pub const GND: PinId = 7;
+    pub const D3: PinId = 9;
+    pub const D4: PinId = 10;
+    pub const D5: PinId = 11;
+    pub const D6: PinId = 12;
+    pub const D7: PinId = 13;
+    pub const VCC: PinId = 14;
+
+    /// Set the voltage converted to the highest code (255), defaults to 3.3V
+    pub fn with_reference(mut self, reference: f32) -> Self {
+        self.reference = reference;
+        self
+    }
+
+    /// Set the time a conversion takes, defaults to 100µs
+    pub fn with_conversion_time(mut self, conversion_time: Duration) -> Self {
+        self.conversion_time = conversion_time;
+        self
+    }
+
+    /// Get the code of the last finished conversion
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+
+    fn quantize(&self, state: State) -> u8 {
+        let State::Analog(voltage) = state.as_analog(self.reference) else {
+            unreachable!()
+        };
+        if self.reference <= 0.0 {
+            return 0;
+        }
+        ((voltage / self.reference).clamp(0.0, 1.0) * 255.0).round() as u8
+    }
+}
+
+impl ChipBuilder<Adc8> for Adc8 {
+    fn build() -> Adc8 {
+        Adc8 {
+            reference: 3.3,
+            conversion_time: Duration::from_micros(100),
+            conversion: None,
+            code: 0,
+            start_was_high: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            vin: Pin::from(PinType::Input),
+            start: Pin::from(PinType::Input),
+            drdy: Pin::from(PinType::Output),
+            d0: Pin::from(PinType::Output),
+            d1: Pin::from(PinType::Output),
+            d2: Pin::from(PinType::Output),
+            d3: Pin::from(PinType::Output),
+            d4: Pin::from(PinType::Output),
+            d5: Pin::from(PinType::Output),
+            d6: Pin::from(PinType::Output),
+            d7: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<Adc8> for ChipSet {
+    fn from(value: Adc8) -> Self {
+        ChipSet::Adc8(value)
+    }
+}
+
+generate_chip!(
+    Adc8 => Adc8Pin,
+    vcc: Adc8::VCC,
+    gnd: Adc8::GND,
+    vin: Adc8::VIN,
+    start: Adc8::START,
+    drdy: Adc8::DRDY,
+    d0: Adc8::D0,
+    d1: Adc8::D1,
+    d2: Adc8::D2,
+    d3: Adc8::D3,
+    d4: Adc8::D4,
+    d5: Adc8::D5,
+    d6: Adc8::D6,
+    d7: Adc8::D7
+);
+
+impl ChipRunner for Adc8 {
+    fn run(&mut self, tick_duration: Duration) {
+        let threshold = self.vcc.state.rail_threshold(3.3);
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+
+            let start = self.start.state.as_logic(threshold) == State::High;
+            if start && !self.start_was_high {
+                self.conversion = Some((self.quantize(self.vin.state), self.conversion_time));
+            }
+            self.start_was_high = start;
+
+            if let Some((code, remaining)) = self.conversion {
+                if remaining <= tick_duration {
+                    self.code = code;
+                    self.conversion = None;
+                } else {
+                    self.conversion = Some((code, remaining - tick_duration));
+                }
+            }
+
+            self.drdy.state = State::from(self.conversion.is_none());
+            Pin::write(
+                &mut [
+                    &mut self.d0,
+                    &mut self.d1,
+                    &mut self.d2,
+                    &mut self.d3,
+                    &mut self.d4,
+                    &mut self.d5,
+                    &mut self.d6,
+                    &mut self.d7,
+                ],
+                self.code as usize,
+            );
+        }
+    }
+
+    fn reset(&mut self) {
+        self.conversion = None;
+        self.code = 0;
+        self.start_was_high = false;
+    }
+}
+
+/// # An 8-bit digital to analog converter
+///
+/// VOUT continuously drives the code read on D0-D7 as a fraction of the reference voltage:
+/// 0 gives 0V and 255 gives the reference voltage.
+///
+/// # Diagram
+/// VOUT: Analog output
+/// ```txt
+///          ---__---
+///     D0 --|1   12|-- VCC
+///     D1 --|2   11|-- VOUT
+///     D2 --|3   10|-- UNUSED
+///     D3 --|4    9|-- D7
+///     D4 --|5    8|-- D6
+///    GND --|6    7|-- D5
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dac8 {
+    reference: f32,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub vout: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+}
+
+impl Dac8 {
+    pub const D0: PinId = 1;
+    pub const D1: PinId = 2;
+    pub const D2: PinId = 3;
+    pub const D3: PinId = 4;
+    pub const D4: PinId = 5;
+    pub const GND: PinId = 6;
+    pub const D5: PinId = 7;
+    pub const D6: PinId = 8;
+    pub const D7: PinId = 9;
+    pub const VOUT: PinId = 11;
+    pub const VCC: PinId = 12;
+
+    /// Set the voltage output for the highest code (255), defaults to 3.3V
+    pub fn with_reference(mut self, reference: f32) -> Self {
+        self.reference = reference;
+        self
+    }
+}
+
+impl ChipBuilder<Dac8> for Dac8 {
+    fn build() -> Dac8 {
+        Dac8 {
+            reference: 3.3,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            vout: Pin::from(PinType::Output),
+            d0: Pin::from(PinType::Input),
+            d1: Pin::from(PinType::Input),
+            d2: Pin::from(PinType::Input),
+            d3: Pin::from(PinType::Input),
+            d4: Pin::from(PinType::Input),
+            d5: Pin::from(PinType::Input),
+            d6: Pin::from(PinType::Input),
+            d7: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<Dac8> for ChipSet {
+    fn from(value: Dac8) -> Self {
+        ChipSet::Dac8(value)
+    }
+}
+
+generate_chip!(
+    Dac8 => Dac8Pin,
+    vcc: Dac8::VCC,
+    gnd: Dac8::GND,
+    vout: Dac8::VOUT,
+    d0: Dac8::D0,
+    d1: Dac8::D1,
+    d2: Dac8::D2,
+    d3: Dac8::D3,
+    d4: Dac8::D4,
+    d5: Dac8::D5,
+    d6: Dac8::D6,
+    d7: Dac8::D7
+);
+
+impl ChipRunner for Dac8 {
+    fn run(&mut self, _: Duration) {
+        let threshold = self.vcc.state.rail_threshold(3.3);
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let code = Pin::read_threshold(
+                &[
+                    &self.d0, &self.d1, &self.d2, &self.d3, &self.d4, &self.d5, &self.d6, &self.d7,
+                ],
+                threshold,
+            );
+            self.vout.state = State::Analog(code as f32 / 255.0 * self.reference);
+        }
+    }
+}
+
+/// # An analog mixer
+///
+/// OUT drives the sum of the voltages of IN0-IN3, each multiplied by its own gain, saturating
+/// at 0V and at the VCC voltage (or the default rails when VCC is a logic state).
+/// Logic inputs are considered as 0V when Low and 3.3V when High.
+///
+/// # Diagram
+/// ```txt
+///          ---__---
+///    IN0 --|1    8|-- VCC
+///    IN1 --|2    7|-- OUT
+///    IN2 --|3    6|-- UNUSED
+///    GND --|4    5|-- IN3
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mixer {
+    gains: [f32; 4],
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub in0: Pin,
+    pub in1: Pin,
+    pub in2: Pin,
+    pub in3: Pin,
+    pub output: Pin,
+}
+
+impl Mixer {
+    pub const IN0: PinId = 1;
+    pub const IN1: PinId = 2;
+    pub const IN2: PinId = 3;
+    pub const GND: PinId = 4;
+    pub const IN3: PinId = 5;
+    pub const OUT: PinId = 7;
+    pub const VCC: PinId = 8;
+
+    /// Set the gain of one of the inputs, given by its pin id, defaults to 1.0.
+    /// Nothing happens if the pin isn't an input.
+    pub fn with_gain(mut self, input: PinId, gain: f32) -> Self {
+        let index = match input {
+            Self::IN0 => 0,
+            Self::IN1 => 1,
+            Self::IN2 => 2,
+            Self::IN3 => 3,
+            _ => return self,
+        };
+        self.gains[index] = gain;
+        self
+    }
+}
+
+impl ChipBuilder<Mixer> for Mixer {
+    fn build() -> Mixer {
+        Mixer {
+            gains: [1.0; 4],
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            in0: Pin::from(PinType::Input),
+            in1: Pin::from(PinType::Input),
+            in2: Pin::from(PinType::Input),
+            in3: Pin::from(PinType::Input),
+            output: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<Mixer> for ChipSet {
+    fn from(value: Mixer) -> Self {
+        ChipSet::Mixer(value)
+    }
+}
+
+generate_chip!(
+    Mixer => MixerPin,
+    vcc: Mixer::VCC,
+    gnd: Mixer::GND,
+    in0: Mixer::IN0,
+    in1: Mixer::IN1,
+    in2: Mixer::IN2,
+    in3: Mixer::IN3,
+    output: Mixer::OUT
+);
+
+impl ChipRunner for Mixer {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let supply = match self.vcc.state {
+                State::Analog(v) => v,
+                _ => *DEFAULT_RAILS.end(),
+            };
+            let sum: f32 = [&self.in0, &self.in1, &self.in2, &self.in3]
+                .into_iter()
+                .zip(self.gains)
+                .map(|(pin, gain)| match pin.state.as_analog(3.3) {
+                    State::Analog(v) => v * gain,
+                    _ => unreachable!(),
+                })
+                .sum();
+            self.output.state = State::Analog(sum.clamp(0.0, supply.max(0.0)));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Adc8, Dac8, Mixer, RcFilter};
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin},
+        State,
+    };
+
+    #[test]
+    fn rc_filter_step_response() {
+        let mut filter = RcFilter::build().with_tau(Duration::from_millis(1));
+        filter.input.state = State::High;
+        let step = Duration::from_micros(100);
+        for _ in 0..10 {
+            filter.run(step);
+        }
+        // one time constant in, the output reached 63% of the step
+        let expected = 3.3 * (1.0 - (-1.0f32).exp());
+        assert!(
+            (filter.voltage() - expected).abs() < 0.01,
+            "{}",
+            filter.voltage()
+        );
+        for _ in 0..40 {
+            filter.run(step);
+        }
+        assert!(filter.output.state.approx_eq(&State::Analog(3.3), 0.03));
+    }
+
+    #[test]
+    fn adc_converts_a_known_voltage() {
+        let mut adc = Adc8::build()
+            .with_reference(3.3)
+            .with_conversion_time(Duration::from_micros(100));
+        adc.vcc.state = State::Analog(3.3);
+        adc.vin.state = State::Analog(1.65);
+        adc.run(Duration::from_micros(10));
+        adc.start.state = State::High;
+        adc.run(Duration::from_micros(10));
+        assert_eq!(adc.drdy.state, State::Low);
+        for _ in 0..10 {
+            adc.run(Duration::from_micros(10));
+        }
+        assert_eq!(adc.drdy.state, State::High);
+        assert_eq!(adc.code(), 128);
+        let outputs = Pin::read(&[
+            &adc.d0, &adc.d1, &adc.d2, &adc.d3, &adc.d4, &adc.d5, &adc.d6, &adc.d7,
+        ]);
+        assert_eq!(outputs, 128);
+    }
+
+    #[test]
+    fn dac_outputs_half_the_reference_for_0x80() {
+        let mut dac = Dac8::build().with_reference(3.3);
+        dac.vcc.state = State::High;
+        Pin::write(
+            &mut [
+                &mut dac.d0,
+                &mut dac.d1,
+                &mut dac.d2,
+                &mut dac.d3,
+                &mut dac.d4,
+                &mut dac.d5,
+                &mut dac.d6,
+                &mut dac.d7,
+            ],
+            0x80,
+        );
+        dac.run(Duration::from_micros(10));
+        let State::Analog(voltage) = dac.vout.state else {
+            panic!("VOUT should be analog, got {:?}", dac.vout.state);
+        };
+        assert!((voltage - 1.65).abs() < 0.01, "VOUT is {voltage}V");
+    }
+
+    #[test]
+    fn mixer_sums_its_inputs_within_the_rails() {
+        let mut mixer = Mixer::build()
+            .with_gain(Mixer::IN0, 0.5)
+            .with_gain(Mixer::IN1, -2.0);
+        mixer.vcc.state = State::Analog(5.0);
+        let mut mix = |in0: f32, in1: f32| {
+            mixer.in0.state = State::Analog(in0);
+            mixer.in1.state = State::Analog(in1);
+            mixer.run(Duration::from_millis(1));
+            mixer.output.state
+        };
+
+        assert!(mix(4.0, 0.5).approx_eq(&State::Analog(1.0), 1e-6));
+        // saturating at VCC, and at the ground
+        assert_eq!(mix(12.0, 0.0), State::Analog(5.0));
+        assert_eq!(mix(1.0, 1.0), State::Analog(0.0));
+    }
+}
@@ -0,0 +1,419 @@
+use std::{cmp::Ordering, time::Duration};
+
+use crate::{define_chip, generate_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
+
+define_chip! {
+    /// # A half adder
+    ///
+    /// SUM = A xor B, CARRY = A and B
+    ///
+    /// # Diagram
+    /// ```txt
+    ///         ---__---
+    ///     A --|1    6|-- VCC
+    ///     B --|2    5|-- CARRY
+    ///   GND --|3    4|-- SUM
+    ///         --------
+    /// ```
+    #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct HalfAdder => HalfAdderPin {
+        pins {
+            vcc: VCC = 6 => Input,
+            gnd: GND = 3 => Output,
+            a: A = 1 => Input,
+            b: B = 2 => Input,
+            sum: SUM = 4 => Output,
+            carry: CARRY = 5 => Output,
+        }
+    }
+}
+
+impl ChipBuilder<HalfAdder> for HalfAdder {
+    fn build() -> HalfAdder {
+        HalfAdder::default()
+    }
+}
+
+impl From<HalfAdder> for ChipSet {
+    fn from(value: HalfAdder) -> Self {
+        ChipSet::HalfAdder(value)
+    }
+}
+
+impl ChipRunner for HalfAdder {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let a: bool = self.a.state.as_logic(threshold).into();
+            let b: bool = self.b.state.as_logic(threshold).into();
+            self.sum.state = State::from(a ^ b);
+            self.carry.state = State::from(a && b);
+        }
+    }
+}
+
+/// # A full adder
+///
+/// SUM = A xor B xor CIN, COUT is set when at least two of A, B and CIN are set
+///
+/// # Diagram
+/// CIN: Carry In
+/// COUT: Carry Out
+/// ```txt
+///         ---__---
+///     A --|1    8|-- VCC
+///     B --|2    7|-- UNUSED
+///   CIN --|3    6|-- COUT
+///   GND --|4    5|-- SUM
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FullAdder {
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub a: Pin,
+    pub b: Pin,
+    pub cin: Pin,
+    pub sum: Pin,
+    pub cout: Pin,
+}
+
+impl FullAdder {
+    pub const A: PinId = 1;
+    pub const B: PinId = 2;
+    pub const CIN: PinId = 3;
+    pub const GND: PinId = 4;
+    pub const SUM: PinId = 5;
+    pub const COUT: PinId = 6;
+    pub const VCC: PinId = 8;
+}
+
+impl ChipBuilder<FullAdder> for FullAdder {
+    fn build() -> FullAdder {
+        FullAdder {
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            a: Pin::from(PinType::Input),
+            b: Pin::from(PinType::Input),
+            cin: Pin::from(PinType::Input),
+            sum: Pin::from(PinType::Output),
+            cout: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<FullAdder> for ChipSet {
+    fn from(value: FullAdder) -> Self {
+        ChipSet::FullAdder(value)
+    }
+}
+
+generate_chip!(
+    FullAdder => FullAdderPin,
+    vcc: FullAdder::VCC,
+    gnd: FullAdder::GND,
+    a: FullAdder::A,
+    b: FullAdder::B,
+    cin: FullAdder::CIN,
+    sum: FullAdder::SUM,
+    cout: FullAdder::COUT
+);
+
+impl ChipRunner for FullAdder {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let a: bool = self.a.state.as_logic(threshold).into();
+            let b: bool = self.b.state.as_logic(threshold).into();
+            let cin: bool = self.cin.state.as_logic(threshold).into();
+            self.sum.state = State::from(a ^ b ^ cin);
+            self.cout.state = State::from((a && b) || (cin && (a ^ b)));
+        }
+    }
+}
+
+/// # An 8-bit magnitude comparator
+///
+/// Compares A and B, asserting exactly one of A>B, A=B and A<B, like a 7485 widened to 8 bits.
+/// When both words are equal, the outcome is taken from the cascade inputs, so that comparators
+/// can be chained for wider words: connect the outputs of the least significant comparator to
+/// the cascade inputs of the next one. A comparator without any cascade input set reports A=B.
+///
+/// # Diagram
+/// I>: A>B cascade input, I=: A=B cascade input, I<: A<B cascade input
+/// ```txt
+///         ---__---
+///    A0 --|1   24|-- VCC
+///    A1 --|2   23|-- B7
+///    A2 --|3   22|-- B6
+///    A3 --|4   21|-- B5
+///    A4 --|5   20|-- B4
+///    A5 --|6   19|-- B3
+///    A6 --|7   18|-- B2
+///    A7 --|8   17|-- B1
+///    I> --|9   16|-- B0
+///    I= --|10  15|-- A>B
+///    I< --|11  14|-- A=B
+///   GND --|12  13|-- A<B
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Comparator8 {
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub a0: Pin,
+    pub a1: Pin,
+    pub a2: Pin,
+    pub a3: Pin,
+    pub a4: Pin,
+    pub a5: Pin,
+    pub a6: Pin,
+    pub a7: Pin,
+    pub b0: Pin,
+    pub b1: Pin,
+    pub b2: Pin,
+    pub b3: Pin,
+    pub b4: Pin,
+    pub b5: Pin,
+    pub b6: Pin,
+    pub b7: Pin,
+    pub i_gt: Pin,
+    pub i_eq: Pin,
+    pub i_lt: Pin,
+    pub gt: Pin,
+    pub eq: Pin,
+    pub lt: Pin,
+}
+
+impl Comparator8 {
+    pub const A0: PinId = 1;
+    pub const A1: PinId = 2;
+    pub const A2: PinId = 3;
+    pub const A3: PinId = 4;
+    pub const A4: PinId = 5;
+    pub const A5: PinId = 6;
+    pub const A6: PinId = 7;
+    pub const A7: PinId = 8;
+    pub const I_GT: PinId = 9;
+    pub const I_EQ: PinId = 10;
+    pub const I_LT: PinId = 11;
+    pub const GND: PinId = 12;
+    pub const LT: PinId = 13;
+    pub const EQ: PinId = 14;
+    pub const GT: PinId = 15;
+    pub const B0: PinId = 16;
+    pub const B1: PinId = 17;
+    pub const B2: PinId = 18;
+    pub const B3: PinId = 19;
+    pub const B4: PinId = 20;
+    pub const B5: PinId = 21;
+    pub const B6: PinId = 22;
+    pub const B7: PinId = 23;
+    pub const VCC: PinId = 24;
+}
+
+impl ChipBuilder<Comparator8> for Comparator8 {
+    fn build() -> Comparator8 {
+        Comparator8 {
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            a0: Pin::from(PinType::Input),
+            a1: Pin::from(PinType::Input),
+            a2: Pin::from(PinType::Input),
+            a3: Pin::from(PinType::Input),
+            a4: Pin::from(PinType::Input),
+            a5: Pin::from(PinType::Input),
+            a6: Pin::from(PinType::Input),
+            a7: Pin::from(PinType::Input),
+            b0: Pin::from(PinType::Input),
+            b1: Pin::from(PinType::Input),
+            b2: Pin::from(PinType::Input),
+            b3: Pin::from(PinType::Input),
+            b4: Pin::from(PinType::Input),
+            b5: Pin::from(PinType::Input),
+            b6: Pin::from(PinType::Input),
+            b7: Pin::from(PinType::Input),
+            i_gt: Pin::from(PinType::Input),
+            i_eq: Pin::from(PinType::Input),
+            i_lt: Pin::from(PinType::Input),
+            gt: Pin::from(PinType::Output),
+            eq: Pin::from(PinType::Output),
+            lt: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<Comparator8> for ChipSet {
+    fn from(value: Comparator8) -> Self {
+        ChipSet::Comparator8(value)
+    }
+}
+
+generate_chip!(
+    Comparator8 => Comparator8Pin,
+    vcc: Comparator8::VCC,
+    gnd: Comparator8::GND,
+    a0: Comparator8::A0,
+    a1: Comparator8::A1,
+    a2: Comparator8::A2,
+    a3: Comparator8::A3,
+    a4: Comparator8::A4,
+    a5: Comparator8::A5,
+    a6: Comparator8::A6,
+    a7: Comparator8::A7,
+    b0: Comparator8::B0,
+    b1: Comparator8::B1,
+    b2: Comparator8::B2,
+    b3: Comparator8::B3,
+    b4: Comparator8::B4,
+    b5: Comparator8::B5,
+    b6: Comparator8::B6,
+    b7: Comparator8::B7,
+    i_gt: Comparator8::I_GT,
+    i_eq: Comparator8::I_EQ,
+    i_lt: Comparator8::I_LT,
+    gt: Comparator8::GT,
+    eq: Comparator8::EQ,
+    lt: Comparator8::LT
+);
+
+impl ChipRunner for Comparator8 {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let a = Pin::read_threshold(
+                &[
+                    &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6, &self.a7,
+                ],
+                threshold,
+            );
+            let b = Pin::read_threshold(
+                &[
+                    &self.b0, &self.b1, &self.b2, &self.b3, &self.b4, &self.b5, &self.b6, &self.b7,
+                ],
+                threshold,
+            );
+            let ordering = a.cmp(&b).then_with(|| {
+                if self.i_gt.state.as_logic(threshold) == State::High {
+                    Ordering::Greater
+                } else if self.i_lt.state.as_logic(threshold) == State::High {
+                    Ordering::Less
+                } else {
+                    Ordering::Equal
+                }
+            });
+            self.gt.state = State::from(ordering == Ordering::Greater);
+            self.eq.state = State::from(ordering == Ordering::Equal);
+            self.lt.state = State::from(ordering == Ordering::Less);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin},
+        State,
+    };
+
+    use super::{Comparator8, FullAdder, HalfAdder};
+
+    #[test]
+    fn half_adder_truth_table() {
+        let mut adder = HalfAdder::build();
+        adder.vcc.state = State::High;
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            adder.a.state = State::from(a);
+            adder.b.state = State::from(b);
+            adder.run(Duration::from_millis(1));
+            let total = a as u8 + b as u8;
+            assert_eq!(adder.sum.state, State::from(total & 1 == 1), "{a} + {b}");
+            assert_eq!(adder.carry.state, State::from(total > 1), "{a} + {b}");
+        }
+    }
+
+    #[test]
+    fn full_adder_truth_table() {
+        let mut adder = FullAdder::build();
+        adder.vcc.state = State::High;
+        for inputs in 0..8u8 {
+            let (a, b, cin) = (inputs & 1 == 1, inputs & 2 == 2, inputs & 4 == 4);
+            adder.a.state = State::from(a);
+            adder.b.state = State::from(b);
+            adder.cin.state = State::from(cin);
+            adder.run(Duration::from_millis(1));
+            let total = a as u8 + b as u8 + cin as u8;
+            assert_eq!(
+                adder.sum.state,
+                State::from(total & 1 == 1),
+                "{a} + {b} + {cin}"
+            );
+            assert_eq!(
+                adder.cout.state,
+                State::from(total > 1),
+                "{a} + {b} + {cin}"
+            );
+        }
+    }
+
+    #[test]
+    fn comparator_asserts_exactly_one_output() {
+        let mut comparator = Comparator8::build();
+        comparator.vcc.state = State::High;
+        // (a, b, cascaded A>B, expected A>B, A=B, A<B)
+        let cases = [
+            (0x00, 0x00, false, (false, true, false)),
+            (0x01, 0x00, false, (true, false, false)),
+            (0x7F, 0x80, false, (false, false, true)),
+            (0xFF, 0xFE, false, (true, false, false)),
+            (0x42, 0x42, false, (false, true, false)),
+            // equal words defer to the less significant comparator
+            (0x42, 0x42, true, (true, false, false)),
+            (0x41, 0x42, true, (false, false, true)),
+        ];
+        for (a, b, cascade_gt, (gt, eq, lt)) in cases {
+            let c = &mut comparator;
+            Pin::write(
+                &mut [
+                    &mut c.a0, &mut c.a1, &mut c.a2, &mut c.a3, &mut c.a4, &mut c.a5, &mut c.a6,
+                    &mut c.a7,
+                ],
+                a,
+            );
+            Pin::write(
+                &mut [
+                    &mut c.b0, &mut c.b1, &mut c.b2, &mut c.b3, &mut c.b4, &mut c.b5, &mut c.b6,
+                    &mut c.b7,
+                ],
+                b,
+            );
+            c.i_gt.state = State::from(cascade_gt);
+            c.run(Duration::from_millis(1));
+
+            let outputs = [c.gt.state, c.eq.state, c.lt.state];
+            assert_eq!(
+                outputs
+                    .iter()
+                    .filter(|state| **state == State::High)
+                    .count(),
+                1,
+                "{a:#04X} vs {b:#04X}"
+            );
+            assert_eq!(
+                outputs,
+                [State::from(gt), State::from(eq), State::from(lt)],
+                "{a:#04X} vs {b:#04X}"
+            );
+        }
+    }
+}
@@ -0,0 +1,555 @@
+use std::{any::Any, fmt::Debug, ops::RangeInclusive, time::Duration};
+
+use crate::{generate_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
+
+/// A peripheral that can be mapped on a bus through a [`MemoryMap`], like a timer or I/O registers
+pub trait BusDevice: Debug + BusDeviceClone {
+    /// Addresses the device answers to
+    fn range(&self) -> RangeInclusive<u16>;
+    /// Read the byte at the given address, relative to the start of the range
+    fn read(&mut self, addr: u16) -> u8;
+    /// Write a byte at the given address, relative to the start of the range
+    fn write(&mut self, addr: u16, data: u8);
+}
+
+/// Implemented for every cloneable [`BusDevice`]
+pub trait BusDeviceClone {
+    fn clone_box(&self) -> Box<dyn BusDevice>;
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T> BusDeviceClone for T
+where
+    T: BusDevice + Clone + 'static,
+{
+    fn clone_box(&self) -> Box<dyn BusDevice> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Clone for Box<dyn BusDevice> {
+    fn clone(&self) -> Self {
+        self.as_ref().clone_box()
+    }
+}
+
+/// # A memory map of bus devices
+///
+/// Presents a set of [`BusDevice`]s to a bus as if it were a RAM chip.
+/// When several devices answer to the same address, the first one added wins,
+/// and reading an address that no device answers to leaves the IO pins floating.
+///
+/// A read is made when the address changes while reading,
+/// and a write once the write ends (when WE or CS goes back high, or the address changes).
+///
+/// Devices can't be serialized, a deserialized memory map is empty.
+///
+/// # Diagram
+/// CS: Chip Select (active low)
+/// WE: Write Enable (active low)
+/// OE: Output Enable (active low)
+/// A0-15: Addresses
+/// IO0-7: Input/Output
+/// ```txt
+///         ---__---
+///    A0 --|1   30|-- VCC
+///    A1 --|2   29|-- A15
+///    A2 --|3   28|-- WE
+///    A3 --|4   27|-- OE
+///    A4 --|5   26|-- CS
+///    A5 --|6   25|-- IO7
+///    A6 --|7   24|-- IO6
+///    A7 --|8   23|-- IO5
+///    A8 --|9   22|-- IO4
+///    A9 --|10  21|-- IO3
+///   A10 --|11  20|-- IO2
+///   A11 --|12  19|-- IO1
+///   A12 --|13  18|-- IO0
+///   A13 --|14  17|-- UNUSED
+///   A14 --|15  16|-- GND
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryMap {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    devices: Vec<Box<dyn BusDevice>>,
+    /// address being read and the value read from it
+    reading: Option<(u16, Option<u8>)>,
+    /// address being written and the data currently on the bus
+    writing: Option<(u16, u8)>,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub cs: Pin,
+    pub we: Pin,
+    pub oe: Pin,
+    pub a0: Pin,
+    pub a1: Pin,
+    pub a2: Pin,
+    pub a3: Pin,
+    pub a4: Pin,
+    pub a5: Pin,
+    pub a6: Pin,
+    pub a7: Pin,
+    pub a8: Pin,
+    pub a9: Pin,
+    pub a10: Pin,
+    pub a11: Pin,
+    pub a12: Pin,
+    pub a13: Pin,
+    pub a14: Pin,
+    pub a15: Pin,
+    pub io0: Pin,
+    pub io1: Pin,
+    pub io2: Pin,
+    pub io3: Pin,
+    pub io4: Pin,
+    pub io5: Pin,
+    pub io6: Pin,
+    pub io7: Pin,
+}
+
+impl MemoryMap {
+    pub const A0: usize = 1;
+    pub const A1: usize = 2;
+    pub const A2: usize = 3;
+    pub const A3: usize = 4;
+    pub const A4: usize = 5;
+    pub const A5: usize = 6;
+    pub const A6: usize = 7;
+    pub const A7: usize = 8;
+    pub const A8: usize = 9;
+    pub const A9: usize = 10;
+    pub const A10: usize = 11;
+    pub const A11: usize = 12;
+    pub const A12: usize = 13;
+    pub const A13: usize = 14;
+    pub const A14: usize = 15;
+    pub const GND: usize = 16;
+    pub const IO0: usize = 18;
+    pub const IO1: usize = 19;
+    pub const IO2: usize = 20;
+    pub const IO3: usize = 21;
+    pub const IO4: usize = 22;
+    pub const IO5: usize = 23;
+    pub const IO6: usize = 24;
+    pub const IO7: usize = 25;
+    pub const CS: usize = 26;
+    pub const OE: usize = 27;
+    pub const WE: usize = 28;
+    pub const A15: usize = 29;
+    pub const VCC: usize = 30;
+
+    /// Map a device on the bus
+    pub fn with_device(mut self, device: impl BusDevice + 'static) -> Self {
+        self.add_device(device);
+        self
+    }
+
+    /// Map a device on the bus
+    pub fn add_device(&mut self, device: impl BusDevice + 'static) {
+        self.devices.push(Box::new(device));
+    }
+
+    /// Get the first mapped device of the given type
+    pub fn device<T: BusDevice + 'static>(&self) -> Option<&T> {
+        self.devices
+            .iter()
+            .find_map(|device| device.as_ref().as_any().downcast_ref::<T>())
+    }
+
+    fn read(&mut self, addr: u16) -> Option<u8> {
+        self.devices
+            .iter_mut()
+            .find(|device| device.range().contains(&addr))
+            .map(|device| {
+                let start = *device.range().start();
+                device.read(addr - start)
+            })
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        if let Some(device) = self
+            .devices
+            .iter_mut()
+            .find(|device| device.range().contains(&addr))
+        {
+            let start = *device.range().start();
+            device.write(addr - start, data);
+        }
+    }
+
+    /// Commit the write in progress, if any
+    fn end_write(&mut self) {
+        if let Some((addr, data)) = self.writing.take() {
+            self.write(addr, data);
+        }
+    }
+
+    fn set_io_type(&mut self, pin_type: PinType) {
+        for pin in self.io_mut() {
+            pin.pin_type = pin_type;
+        }
+    }
+
+    fn io_mut(&mut self) -> [&mut Pin; 8] {
+        [
+            &mut self.io0,
+            &mut self.io1,
+            &mut self.io2,
+            &mut self.io3,
+            &mut self.io4,
+            &mut self.io5,
+            &mut self.io6,
+            &mut self.io7,
+        ]
+    }
+}
+
+impl ChipBuilder<MemoryMap> for MemoryMap {
+    fn build() -> MemoryMap {
+        MemoryMap {
+            devices: Vec::new(),
+            reading: None,
+            writing: None,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            cs: Pin::from(PinType::Input),
+            we: Pin::from(PinType::Input),
+            oe: Pin::from(PinType::Input),
+            a0: Pin::from(PinType::Input),
+            a1: Pin::from(PinType::Input),
+            a2: Pin::from(PinType::Input),
+            a3: Pin::from(PinType::Input),
+            a4: Pin::from(PinType::Input),
+            a5: Pin::from(PinType::Input),
+            a6: Pin::from(PinType::Input),
+            a7: Pin::from(PinType::Input),
+            a8: Pin::from(PinType::Input),
+            a9: Pin::from(PinType::Input),
+            a10: Pin::from(PinType::Input),
+            a11: Pin::from(PinType::Input),
+            a12: Pin::from(PinType::Input),
+            a13: Pin::from(PinType::Input),
+            a14: Pin::from(PinType::Input),
+            a15: Pin::from(PinType::Input),
+            io0: Pin::from(PinType::Floating),
+            io1: Pin::from(PinType::Floating),
+            io2: Pin::from(PinType::Floating),
+            io3: Pin::from(PinType::Floating),
+            io4: Pin::from(PinType::Floating),
+            io5: Pin::from(PinType::Floating),
+            io6: Pin::from(PinType::Floating),
+            io7: Pin::from(PinType::Floating),
+        }
+    }
+}
+
+impl From<MemoryMap> for ChipSet {
+    fn from(value: MemoryMap) -> Self {
+        ChipSet::MemoryMap(value)
+    }
+}
+
+generate_chip!(
+    MemoryMap => MemoryMapPin,
+    vcc: MemoryMap::VCC,
+    gnd: MemoryMap::GND,
+    cs: MemoryMap::CS,
+    we: MemoryMap::WE,
+    oe: MemoryMap::OE,
+    a0: MemoryMap::A0,
+    a1: MemoryMap::A1,
+    a2: MemoryMap::A2,
+    a3: MemoryMap::A3,
+    a4: MemoryMap::A4,
+    a5: MemoryMap::A5,
+    a6: MemoryMap::A6,
+    a7: MemoryMap::A7,
+    a8: MemoryMap::A8,
+    a9: MemoryMap::A9,
+    a10: MemoryMap::A10,
+    a11: MemoryMap::A11,
+    a12: MemoryMap::A12,
+    a13: MemoryMap::A13,
+    a14: MemoryMap::A14,
+    a15: MemoryMap::A15,
+    io0: MemoryMap::IO0,
+    io1: MemoryMap::IO1,
+    io2: MemoryMap::IO2,
+    io3: MemoryMap::IO3,
+    io4: MemoryMap::IO4,
+    io5: MemoryMap::IO5,
+    io6: MemoryMap::IO6,
+    io7: MemoryMap::IO7
+);
+
+impl ChipRunner for MemoryMap {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) != State::High {
+            self.set_io_type(PinType::Floating);
+            self.reading = None;
+            self.writing = None;
+            return;
+        }
+        self.gnd.state = State::Low;
+        let threshold = self.vcc.state.rail_threshold(3.3);
+        let addr = Pin::read_threshold(
+            &[
+                &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6, &self.a7,
+                &self.a8, &self.a9, &self.a10, &self.a11, &self.a12, &self.a13, &self.a14,
+                &self.a15,
+            ],
+            threshold,
+        ) as u16;
+
+        // check Chip Select (active low)
+        if self.cs.state == State::Low {
+            // check Write Enable (active low)
+            if self.we.state == State::Low {
+                self.reading = None;
+                self.set_io_type(PinType::Input);
+                if self.writing.is_some_and(|(writing, _)| writing != addr) {
+                    self.end_write();
+                }
+                let data = Pin::read_threshold(
+                    &[
+                        &self.io0, &self.io1, &self.io2, &self.io3, &self.io4, &self.io5,
+                        &self.io6, &self.io7,
+                    ],
+                    threshold,
+                ) as u8;
+                self.writing = Some((addr, data));
+                return;
+            }
+            self.end_write();
+            if self.oe.state == State::Low {
+                let value = match self.reading {
+                    Some((reading, value)) if reading == addr => value,
+                    _ => self.read(addr),
+                };
+                self.reading = Some((addr, value));
+                if let Some(value) = value {
+                    self.set_io_type(PinType::Output);
+                    Pin::write(&mut self.io_mut(), value as usize);
+                } else {
+                    self.set_io_type(PinType::Floating);
+                }
+                return;
+            }
+        } else {
+            self.end_write();
+        }
+        self.reading = None;
+        self.set_io_type(PinType::Floating);
+    }
+
+    fn reset(&mut self) {
+        self.reading = None;
+        self.writing = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ops::RangeInclusive, time::Duration};
+
+    use super::{BusDevice, MemoryMap};
+    use crate::{
+        board::{Board, MemoryBus, Trace},
+        chip::{
+            clocks::Clock,
+            cpu::nes6502::{AddressingMode, Assembler, Item, Nes6502, Opcode},
+            gates::NotGate,
+            generators::Generator,
+            memories::Rom256B,
+            ChipBuilder, ChipSet,
+        },
+    };
+
+    /// A register at $0200 counting up every time it's read, that can be written to reload it,
+    /// and a plain register at $0201
+    #[derive(Debug, Clone, Default)]
+    struct Counter {
+        value: u8,
+        writes: usize,
+        register: u8,
+    }
+
+    impl BusDevice for Counter {
+        fn range(&self) -> RangeInclusive<u16> {
+            0x0200..=0x0201
+        }
+
+        fn read(&mut self, addr: u16) -> u8 {
+            if addr == 1 {
+                return self.register;
+            }
+            let value = self.value;
+            self.value = self.value.wrapping_add(1);
+            value
+        }
+
+        fn write(&mut self, addr: u16, data: u8) {
+            if addr == 1 {
+                self.register = data;
+            } else {
+                self.value = data;
+                self.writes += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn cpu_reads_and_writes_a_mapped_device() {
+        let program = [
+            Opcode::LDA(AddressingMode::Immediate(0x40)).into(),
+            Opcode::STA(AddressingMode::Absolute(0x0200)).into(),
+            Opcode::LDX(AddressingMode::Absolute(0x0200)).into(),
+            Opcode::STX(AddressingMode::Absolute(0x0201)).into(),
+            Opcode::BMI(-2).into(),
+            Opcode::BPL(-4).into(),
+            Item::Org(0xFC),
+            Item::Word(0x8000),
+        ];
+        let image = Assembler::assemble_items(&program).unwrap();
+
+        // the memory map sits in $0000-$7FFF, and the ROM is mirrored over $8000-$FFFF
+        let mut board: Board<ChipSet> = Board::new();
+        let rom = board.register_chip(Rom256B::build().set_data(&image).into());
+        let map = board.register_chip(MemoryMap::build().with_device(Counter::default()).into());
+        let not = board.register_chip(NotGate::build().into());
+        let vcc = board.register_chip(Generator::build().into());
+        let clock = board.register_chip(Clock::build().with_frequency(1000.0).into());
+        let cpu = board.register_chip(Nes6502::build().into());
+        board.power_rail(
+            vcc,
+            &[
+                (clock, Clock::VCC),
+                (cpu, Nes6502::VCC),
+                (not, NotGate::VCC),
+                (rom, Rom256B::VCC),
+                (map, MemoryMap::VCC),
+            ],
+        );
+        let cpu_address = [
+            Nes6502::A0,
+            Nes6502::A1,
+            Nes6502::A2,
+            Nes6502::A3,
+            Nes6502::A4,
+            Nes6502::A5,
+            Nes6502::A6,
+            Nes6502::A7,
+            Nes6502::A8,
+            Nes6502::A9,
+            Nes6502::A10,
+            Nes6502::A11,
+            Nes6502::A12,
+            Nes6502::A13,
+            Nes6502::A14,
+            Nes6502::A15,
+        ];
+        let cpu_bus = MemoryBus {
+            address: &cpu_address,
+            data: &[
+                Nes6502::D0,
+                Nes6502::D1,
+                Nes6502::D2,
+                Nes6502::D3,
+                Nes6502::D4,
+                Nes6502::D5,
+                Nes6502::D6,
+                Nes6502::D7,
+            ],
+            ..Default::default()
+        };
+        let map_bus = MemoryBus {
+            address: &[
+                MemoryMap::A0,
+                MemoryMap::A1,
+                MemoryMap::A2,
+                MemoryMap::A3,
+                MemoryMap::A4,
+                MemoryMap::A5,
+                MemoryMap::A6,
+                MemoryMap::A7,
+                MemoryMap::A8,
+                MemoryMap::A9,
+                MemoryMap::A10,
+                MemoryMap::A11,
+                MemoryMap::A12,
+                MemoryMap::A13,
+                MemoryMap::A14,
+                MemoryMap::A15,
+            ],
+            data: &[
+                MemoryMap::IO0,
+                MemoryMap::IO1,
+                MemoryMap::IO2,
+                MemoryMap::IO3,
+                MemoryMap::IO4,
+                MemoryMap::IO5,
+                MemoryMap::IO6,
+                MemoryMap::IO7,
+            ],
+            ..Default::default()
+        };
+        let rom_bus = MemoryBus {
+            address: &[
+                Rom256B::A0,
+                Rom256B::A1,
+                Rom256B::A2,
+                Rom256B::A3,
+                Rom256B::A4,
+                Rom256B::A5,
+                Rom256B::A6,
+                Rom256B::A7,
+            ],
+            data: &[
+                Rom256B::IO0,
+                Rom256B::IO1,
+                Rom256B::IO2,
+                Rom256B::IO3,
+                Rom256B::IO4,
+                Rom256B::IO5,
+                Rom256B::IO6,
+                Rom256B::IO7,
+            ],
+            ..Default::default()
+        };
+        board.connect_bus_to_memory(cpu, &cpu_bus, map, &map_bus);
+        board.connect_bus_to_memory(cpu, &cpu_bus, rom, &rom_bus);
+        board.connect(cpu, Nes6502::A15, not, NotGate::A);
+        board.connect(not, NotGate::NOT_A, rom, Rom256B::CS);
+        board.connect(cpu, Nes6502::A15, map, MemoryMap::CS);
+        board.register_trace(Trace::from(vec![
+            (cpu, Nes6502::RW),
+            (map, MemoryMap::WE),
+            (not, NotGate::B),
+        ]));
+        board.register_trace(Trace::from(vec![
+            (not, NotGate::NOT_B),
+            (rom, Rom256B::OE),
+            (map, MemoryMap::OE),
+        ]));
+        board.connect(clock, Clock::CLK, cpu, Nes6502::CLK);
+
+        board.run_during(Duration::from_millis(30), Duration::from_micros(50));
+
+        let Some(ChipSet::MemoryMap(map)) = board.get_chip(&map) else {
+            unreachable!()
+        };
+        let counter = map.device::<Counter>().unwrap();
+        // the CPU read back the value it wrote, which moved the counter on
+        assert_eq!(counter.writes, 1);
+        assert_eq!(counter.register, 0x40);
+        assert_eq!(counter.value, 0x41);
+    }
+}
@@ -6,7 +6,7 @@ use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
 
 /// A customizable simple clock
 /// CLK: clock
-/// ```
+/// ```txt
 ///        --------
 ///  CLK --|1    4|-- VCC
 ///  GND --|2    3|-- UNUSED
@@ -18,6 +18,8 @@ pub struct Clock {
     frequency: Duration,
     timer: Duration,
     active: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pending_edges: usize,
     pub vcc: Pin,
     pub gnd: Pin,
     pub clk: Pin,
@@ -28,13 +30,33 @@ impl Clock {
     pub const GND: PinId = 2;
     pub const CLK: PinId = 1;
 
+    /// Set the frequency of CLK, in hertz. Frequencies above 500MHz are capped, as the simulated
+    /// time can't be split below 1ns.
     pub fn with_frequency(mut self, mut hertz: f64) -> Self {
         if hertz < f64::EPSILON {
             hertz = f64::EPSILON;
         }
-        self.frequency = Duration::from_nanos((500_000_000.0 * (1.0 / hertz)) as u64);
+        self.frequency = Duration::from_nanos((500_000_000.0 * (1.0 / hertz)) as u64)
+            .max(Duration::from_nanos(1));
         self
     }
+
+    /// Largest step the board can be run with without skipping edges on CLK: half a period.
+    /// The clock keeps time with bigger steps, but only the last level of each step reaches the
+    /// pin, the edges in between are only counted in the pending edges.
+    pub fn max_step(&self) -> Duration {
+        self.frequency
+    }
+
+    /// Get the number of edges generated since the last call to `take_pending_edges`
+    pub fn pending_edges(&self) -> usize {
+        self.pending_edges
+    }
+
+    /// Get the number of edges generated since the last call, and start counting again
+    pub fn take_pending_edges(&mut self) -> usize {
+        std::mem::take(&mut self.pending_edges)
+    }
 }
 
 impl ChipBuilder<Clock> for Clock {
@@ -43,6 +65,7 @@ impl ChipBuilder<Clock> for Clock {
             frequency: Duration::from_secs(1),
             timer: Duration::default(),
             active: false,
+            pending_edges: 0,
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             clk: Pin::from(PinType::Output),
@@ -56,20 +79,651 @@ impl From<Clock> for ChipSet {
     }
 }
 
-generate_chip!(Clock, vcc: Clock::VCC, gnd: Clock::GND, clk: Clock::CLK);
+generate_chip!(Clock => ClockPin, vcc: Clock::VCC, gnd: Clock::GND, clk: Clock::CLK);
 
 impl ChipRunner for Clock {
     fn run(&mut self, tick_duration: Duration) {
         if self.vcc.state.as_logic(1.0) == State::High {
             self.timer += tick_duration;
-            while self.timer > self.frequency {
-                self.timer -= self.frequency;
-                self.active = !self.active;
-            }
+            let edges = take_edges(&mut self.timer, self.frequency);
+            self.active ^= edges % 2 == 1;
+            self.pending_edges = self.pending_edges.saturating_add(edges);
             self.clk.state = State::from(self.active);
         } else {
-            self.active = false;
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.active = false;
+        self.timer = Duration::default();
+        self.pending_edges = 0;
+    }
+}
+
+/// Count the edges a square wave with the given half period went through during `timer`,
+/// leaving the time elapsed since the last edge in `timer`
+fn take_edges(timer: &mut Duration, half_period: Duration) -> usize {
+    // builders never give a zero period, but a save could still hold one
+    let half_period = half_period.as_nanos().max(1);
+    let edges = timer.as_nanos() / half_period;
+    // the remainder is shorter than the half period, which fits in a Duration
+    *timer = Duration::from_nanos((timer.as_nanos() % half_period) as u64);
+    usize::try_from(edges).unwrap_or(usize::MAX)
+}
+
+/// A clock that loops over a programmable sequence of bits,
+/// each bit being held on OUT for one period
+/// OUT: current bit of the pattern
+/// ```txt
+///        --------
+///  OUT --|1    4|-- VCC
+///  GND --|2    3|-- UNUSED
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PatternGenerator {
+    pattern: Vec<bool>,
+    period: Duration,
+    timer: Duration,
+    index: usize,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub out: Pin,
+}
+
+impl PatternGenerator {
+    pub const VCC: PinId = 4;
+    pub const GND: PinId = 2;
+    pub const OUT: PinId = 1;
+
+    pub fn with_pattern(mut self, pattern: &[bool]) -> Self {
+        self.pattern = Vec::from(pattern);
+        self.index = 0;
+        self
+    }
+
+    pub fn with_period(mut self, period: Duration) -> Self {
+        self.period = period.max(Duration::from_nanos(1));
+        self
+    }
+}
+
+impl ChipBuilder<PatternGenerator> for PatternGenerator {
+    fn build() -> PatternGenerator {
+        PatternGenerator {
+            pattern: vec![],
+            period: Duration::from_secs(1),
+            timer: Duration::default(),
+            index: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            out: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<PatternGenerator> for ChipSet {
+    fn from(value: PatternGenerator) -> Self {
+        ChipSet::PatternGenerator(value)
+    }
+}
+
+generate_chip!(
+    PatternGenerator => PatternGeneratorPin,
+    vcc: PatternGenerator::VCC,
+    gnd: PatternGenerator::GND,
+    out: PatternGenerator::OUT
+);
+
+impl ChipRunner for PatternGenerator {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            if self.pattern.is_empty() {
+                self.out.state = State::Low;
+                return;
+            }
+            self.timer += tick_duration;
+            while self.timer >= self.period {
+                self.timer -= self.period;
+                self.index = (self.index + 1) % self.pattern.len();
+            }
+            self.out.state = State::from(self.pattern[self.index]);
+        } else {
+            self.index = 0;
             self.timer = Duration::default();
         }
     }
+
+    fn reset(&mut self) {
+        self.index = 0;
+        self.timer = Duration::default();
+    }
+}
+
+/// A crystal oscillator, that stays quiet for a warm-up delay after being powered up
+/// before producing its square wave
+/// CLK: clock
+/// ```txt
+///        --------
+///  CLK --|1    4|-- VCC
+///  GND --|2    3|-- UNUSED
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Oscillator {
+    frequency: Duration,
+    startup_delay: Duration,
+    warmup: Duration,
+    timer: Duration,
+    active: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pending_edges: usize,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub clk: Pin,
+}
+
+impl Oscillator {
+    pub const VCC: PinId = 4;
+    pub const GND: PinId = 2;
+    pub const CLK: PinId = 1;
+
+    /// Set the frequency of the square wave, in hertz. Frequencies above 500MHz are capped, as
+    /// the simulated time can't be split below 1ns.
+    ///
+    /// # Panics
+    /// Panics if the frequency isn't a positive number
+    pub fn with_frequency(mut self, hertz: f64) -> Self {
+        assert!(
+            hertz.is_finite() && hertz > 0.0,
+            "invalid oscillator frequency {hertz}Hz, it must be a positive number"
+        );
+        self.frequency =
+            Duration::from_nanos((500_000_000.0 / hertz) as u64).max(Duration::from_nanos(1));
+        self
+    }
+
+    /// Largest step the board can be run with without skipping edges on CLK: half a period.
+    /// The clock keeps time with bigger steps, but only the last level of each step reaches the
+    /// pin, the edges in between are only counted in the pending edges.
+    pub fn max_step(&self) -> Duration {
+        self.frequency
+    }
+
+    /// Get the number of edges generated since the last call to `take_pending_edges`
+    pub fn pending_edges(&self) -> usize {
+        self.pending_edges
+    }
+
+    /// Get the number of edges generated since the last call, and start counting again
+    pub fn take_pending_edges(&mut self) -> usize {
+        std::mem::take(&mut self.pending_edges)
+    }
+
+    /// Set the time the oscillator needs to start up once powered, defaults to 1ms
+    pub fn with_startup_delay(mut self, startup_delay: Duration) -> Self {
+        self.startup_delay = startup_delay;
+        self
+    }
+}
+
+impl ChipBuilder<Oscillator> for Oscillator {
+    fn build() -> Oscillator {
+        Oscillator {
+            frequency: Duration::from_secs(1),
+            startup_delay: Duration::from_millis(1),
+            warmup: Duration::default(),
+            timer: Duration::default(),
+            active: false,
+            pending_edges: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            clk: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<Oscillator> for ChipSet {
+    fn from(value: Oscillator) -> Self {
+        ChipSet::Oscillator(value)
+    }
+}
+
+generate_chip!(
+    Oscillator => OscillatorPin,
+    vcc: Oscillator::VCC,
+    gnd: Oscillator::GND,
+    clk: Oscillator::CLK
+);
+
+impl ChipRunner for Oscillator {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            if self.warmup < self.startup_delay {
+                self.warmup += tick_duration;
+                if self.warmup < self.startup_delay {
+                    self.clk.state = State::Low;
+                    return;
+                }
+                // the wave starts where the warm-up ended
+                self.timer = self.warmup - self.startup_delay;
+            } else {
+                self.timer += tick_duration;
+            }
+            let edges = take_edges(&mut self.timer, self.frequency);
+            self.active ^= edges % 2 == 1;
+            self.pending_edges = self.pending_edges.saturating_add(edges);
+            self.clk.state = State::from(self.active);
+        } else {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.active = false;
+        self.warmup = Duration::default();
+        self.timer = Duration::default();
+        self.pending_edges = 0;
+    }
+}
+
+/// A 555 timer wired in astable mode, free-running a square wave with independent high and low
+/// durations, so the duty cycle doesn't have to be 50%
+/// OUT: output, starts high once powered
+/// RESET: pull low to stop the timer and force OUT low
+/// ```txt
+///          ---__---
+///    GND --|1    8|-- VCC
+/// UNUSED --|2    7|-- UNUSED
+///    OUT --|3    6|-- UNUSED
+///  RESET --|4    5|-- UNUSED
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timer555 {
+    high_duration: Duration,
+    low_duration: Duration,
+    timer: Duration,
+    active: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub out: Pin,
+    pub reset: Pin,
+}
+
+impl Timer555 {
+    pub const GND: PinId = 1;
+    pub const OUT: PinId = 3;
+    pub const RESET: PinId = 4;
+    pub const VCC: PinId = 8;
+
+    /// Set how long OUT stays high during a cycle, defaults to 500ms
+    pub fn with_high_duration(mut self, duration: Duration) -> Self {
+        self.high_duration = duration.max(Duration::from_nanos(1));
+        self
+    }
+
+    /// Set how long OUT stays low during a cycle, defaults to 500ms
+    pub fn with_low_duration(mut self, duration: Duration) -> Self {
+        self.low_duration = duration.max(Duration::from_nanos(1));
+        self
+    }
+}
+
+impl ChipBuilder<Timer555> for Timer555 {
+    fn build() -> Timer555 {
+        Timer555 {
+            high_duration: Duration::from_millis(500),
+            low_duration: Duration::from_millis(500),
+            timer: Duration::default(),
+            active: true,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            out: Pin::from(PinType::Output),
+            reset: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<Timer555> for ChipSet {
+    fn from(value: Timer555) -> Self {
+        ChipSet::Timer555(value)
+    }
+}
+
+generate_chip!(
+    Timer555 => Timer555Pin,
+    vcc: Timer555::VCC,
+    gnd: Timer555::GND,
+    out: Timer555::OUT,
+    reset: Timer555::RESET
+);
+
+impl ChipRunner for Timer555 {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            // a floating RESET pin doesn't hold the timer
+            if self.reset.state != State::Undefined
+                && self
+                    .reset
+                    .state
+                    .as_logic(self.vcc.state.rail_threshold(3.3))
+                    == State::Low
+            {
+                self.reset();
+                self.out.state = State::Low;
+                return;
+            }
+            self.timer += tick_duration;
+            loop {
+                let phase = if self.active {
+                    self.high_duration
+                } else {
+                    self.low_duration
+                };
+                if self.timer < phase {
+                    break;
+                }
+                self.timer -= phase;
+                self.active = !self.active;
+            }
+            self.out.state = State::from(self.active);
+        } else {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.active = true;
+        self.timer = Duration::default();
+    }
+}
+
+/// A two-phase non-overlapping clock, as needed by some classic CPUs.
+/// PHI1 is high during the first half of each period and PHI2 during the second half, both
+/// going low a dead time before the end of their half, so they are never high together.
+/// PHI1: phase 1
+/// PHI2: phase 2
+/// ```txt
+///         --------
+///  PHI1 --|1    4|-- VCC
+///   GND --|2    3|-- PHI2
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TwoPhaseClock {
+    period: Duration,
+    dead_time: Duration,
+    timer: Duration,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub phi1: Pin,
+    pub phi2: Pin,
+}
+
+impl TwoPhaseClock {
+    pub const PHI1: PinId = 1;
+    pub const GND: PinId = 2;
+    pub const PHI2: PinId = 3;
+    pub const VCC: PinId = 4;
+
+    pub fn with_frequency(mut self, mut hertz: f64) -> Self {
+        if hertz < f64::EPSILON {
+            hertz = f64::EPSILON;
+        }
+        self.period = Duration::from_nanos((1_000_000_000.0 * (1.0 / hertz)) as u64)
+            .max(Duration::from_nanos(2));
+        self
+    }
+
+    /// Set the time both phases are low between two phases, defaults to no dead time.
+    /// It gets capped to half a period, in which case both phases stay low.
+    pub fn with_dead_time(mut self, dead_time: Duration) -> Self {
+        self.dead_time = dead_time;
+        self
+    }
+}
+
+impl ChipBuilder<TwoPhaseClock> for TwoPhaseClock {
+    fn build() -> TwoPhaseClock {
+        TwoPhaseClock {
+            period: Duration::from_secs(1),
+            dead_time: Duration::default(),
+            timer: Duration::default(),
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            phi1: Pin::from(PinType::Output),
+            phi2: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<TwoPhaseClock> for ChipSet {
+    fn from(value: TwoPhaseClock) -> Self {
+        ChipSet::TwoPhaseClock(value)
+    }
+}
+
+generate_chip!(
+    TwoPhaseClock => TwoPhaseClockPin,
+    vcc: TwoPhaseClock::VCC,
+    gnd: TwoPhaseClock::GND,
+    phi1: TwoPhaseClock::PHI1,
+    phi2: TwoPhaseClock::PHI2
+);
+
+impl ChipRunner for TwoPhaseClock {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            self.timer += tick_duration;
+            while self.timer >= self.period {
+                self.timer -= self.period;
+            }
+            let half = self.period / 2;
+            let high_time = half.saturating_sub(self.dead_time);
+            self.phi1.state = State::from(self.timer < high_time);
+            self.phi2.state = State::from(self.timer >= half && self.timer - half < high_time);
+        } else {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.timer = Duration::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Clock, Oscillator, PatternGenerator, Timer555, TwoPhaseClock};
+    use crate::{
+        chip::{ChipBuilder, ChipRunner},
+        State,
+    };
+
+    #[test]
+    fn big_step_counts_every_edge() {
+        let mut clock = Clock::build().with_frequency(1000.0);
+        clock.vcc.state = State::High;
+        assert_eq!(clock.max_step(), Duration::from_micros(500));
+
+        // a step of 10 periods toggles the clock 20 times, ending on the level it started with
+        clock.run(Duration::from_millis(10));
+        assert_eq!(clock.clk.state, State::Low);
+        assert_eq!(clock.take_pending_edges(), 20);
+        assert_eq!(clock.pending_edges(), 0);
+
+        // the leftover time is kept between steps
+        clock.run(Duration::from_micros(2750));
+        assert_eq!(clock.clk.state, State::High);
+        clock.run(Duration::from_micros(250));
+        assert_eq!(clock.take_pending_edges(), 6);
+    }
+
+    #[test]
+    fn fastest_clock_keeps_up_with_any_step() {
+        let mut clock = Clock::build().with_frequency(2e9);
+        clock.vcc.state = State::High;
+        assert_eq!(clock.max_step(), Duration::from_nanos(1));
+
+        clock.run(Duration::ZERO);
+        assert_eq!(clock.pending_edges(), 0);
+        // a whole second at 1ns per edge, counted without going through every edge
+        clock.run(Duration::from_secs(1));
+        assert_eq!(clock.take_pending_edges(), 1_000_000_000);
+        assert_eq!(clock.clk.state, State::Low);
+        clock.run(Duration::from_nanos(3));
+        assert_eq!(clock.clk.state, State::High);
+    }
+
+    #[test]
+    fn pattern_generator_follows_its_pattern() {
+        let pattern = [true, false, false, true];
+        let mut generator = PatternGenerator::build()
+            .with_pattern(&pattern)
+            .with_period(Duration::from_millis(1));
+        generator.vcc.state = State::High;
+        let step = Duration::from_micros(250);
+        let mut elapsed = Duration::ZERO;
+        for _ in 0..32 {
+            generator.run(step);
+            elapsed += step;
+            let bit = (elapsed.as_micros() / 1000) as usize % pattern.len();
+            assert_eq!(
+                generator.out.state,
+                State::from(pattern[bit]),
+                "at {elapsed:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn oscillator_is_quiet_until_warmed_up() {
+        let mut oscillator = Oscillator::build()
+            .with_frequency(1000.0)
+            .with_startup_delay(Duration::from_millis(2));
+        oscillator.vcc.state = State::High;
+        let outputs: Vec<_> = (0..40)
+            .map(|_| {
+                oscillator.run(Duration::from_micros(100));
+                oscillator.clk.state
+            })
+            .collect();
+
+        assert!(outputs[..20].iter().all(|state| *state == State::Low));
+        // then a 1kHz square wave starts, toggling every 5 steps
+        let edges: Vec<_> = outputs
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0] != pair[1])
+            .map(|(index, _)| index + 1)
+            .collect();
+        assert_eq!(edges, [24, 29, 34, 39]);
+        assert_eq!(oscillator.pending_edges(), 4);
+    }
+
+    #[test]
+    fn oscillator_frequency_is_capped_at_the_nanosecond() {
+        let mut oscillator = Oscillator::build()
+            .with_frequency(2e9)
+            .with_startup_delay(Duration::ZERO);
+        assert_eq!(oscillator.max_step(), Duration::from_nanos(1));
+        oscillator.vcc.state = State::High;
+        oscillator.run(Duration::from_micros(1));
+        assert_eq!(oscillator.pending_edges(), 1000);
+        oscillator.run(Duration::from_secs(1));
+        assert_eq!(oscillator.pending_edges(), 1_000_001_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid oscillator frequency")]
+    fn oscillator_rejects_a_zero_frequency() {
+        Oscillator::build().with_frequency(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid oscillator frequency")]
+    fn oscillator_rejects_an_infinite_frequency() {
+        Oscillator::build().with_frequency(f64::INFINITY);
+    }
+
+    #[test]
+    fn timer_555_follows_its_high_and_low_durations() {
+        let mut timer = Timer555::build()
+            .with_high_duration(Duration::from_millis(3))
+            .with_low_duration(Duration::from_millis(1));
+        timer.vcc.state = State::High;
+        let step = Duration::from_micros(250);
+        let mut outputs = vec![];
+        for _ in 0..64 {
+            timer.run(step);
+            outputs.push(timer.out.state);
+        }
+
+        assert_eq!(outputs[0], State::High);
+        // (index, state entered) of each edge
+        let edges: Vec<_> = outputs
+            .windows(2)
+            .enumerate()
+            .filter(|(_, pair)| pair[0] != pair[1])
+            .map(|(index, pair)| (index + 1, pair[1]))
+            .collect();
+        // (state, how long it lasted until the next edge)
+        let intervals: Vec<_> = edges
+            .windows(2)
+            .map(|pair| (pair[0].1, step * (pair[1].0 - pair[0].0) as u32))
+            .collect();
+        assert_eq!(
+            intervals,
+            [
+                (State::Low, Duration::from_millis(1)),
+                (State::High, Duration::from_millis(3)),
+                (State::Low, Duration::from_millis(1)),
+                (State::High, Duration::from_millis(3)),
+                (State::Low, Duration::from_millis(1)),
+                (State::High, Duration::from_millis(3)),
+                (State::Low, Duration::from_millis(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn two_phases_never_overlap() {
+        let mut clock = TwoPhaseClock::build()
+            .with_frequency(1000.0)
+            .with_dead_time(Duration::from_micros(100));
+        clock.vcc.state = State::High;
+        let phases: Vec<_> = (0..100)
+            .map(|_| {
+                clock.run(Duration::from_micros(50));
+                (bool::from(clock.phi1.state), bool::from(clock.phi2.state))
+            })
+            .collect();
+
+        assert!(phases.iter().all(|(phi1, phi2)| !(*phi1 && *phi2)));
+        // each phase rises once per 1ms period, 20 steps, and stays high for 400us
+        let rising_edges = |phase: fn(&(bool, bool)) -> bool| {
+            phases
+                .windows(2)
+                .enumerate()
+                .filter(|(_, pair)| !phase(&pair[0]) && phase(&pair[1]))
+                .map(|(index, _)| index + 1)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rising_edges(|(phi1, _)| *phi1), [19, 39, 59, 79, 99]);
+        assert_eq!(rising_edges(|(_, phi2)| *phi2), [9, 29, 49, 69, 89]);
+        assert_eq!(phases.iter().filter(|(phi1, _)| *phi1).count(), 5 * 8);
+        assert_eq!(phases.iter().filter(|(_, phi2)| *phi2).count(), 5 * 8);
+    }
 }
@@ -0,0 +1,950 @@
+use std::time::Duration;
+
+use crate::{generate_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
+
+/// # A 4-bit Gray code counter
+///
+/// Counts on each rising edge of CLK, in Gray code order so that only one output changes per step.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///   CLK --|1    8|-- VCC
+///    Q0 --|2    7|-- UNUSED
+///    Q1 --|3    6|-- Q3
+///   GND --|4    5|-- Q2
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GrayCounter4Bit {
+    clock: bool,
+    count: u8,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub clk: Pin,
+    pub q0: Pin,
+    pub q1: Pin,
+    pub q2: Pin,
+    pub q3: Pin,
+}
+
+impl GrayCounter4Bit {
+    pub const CLK: PinId = 1;
+    pub const Q0: PinId = 2;
+    pub const Q1: PinId = 3;
+    pub const GND: PinId = 4;
+    pub const Q2: PinId = 5;
+    pub const Q3: PinId = 6;
+    pub const VCC: PinId = 8;
+
+    /// Get the current output value, in Gray code
+    pub fn value(&self) -> u8 {
+        self.count ^ (self.count >> 1)
+    }
+}
+
+impl ChipBuilder<GrayCounter4Bit> for GrayCounter4Bit {
+    fn build() -> GrayCounter4Bit {
+        GrayCounter4Bit {
+            clock: false,
+            count: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            clk: Pin::from(PinType::Input),
+            q0: Pin::from(PinType::Output),
+            q1: Pin::from(PinType::Output),
+            q2: Pin::from(PinType::Output),
+            q3: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<GrayCounter4Bit> for ChipSet {
+    fn from(value: GrayCounter4Bit) -> Self {
+        ChipSet::GrayCounter4Bit(value)
+    }
+}
+
+generate_chip!(
+    GrayCounter4Bit => GrayCounter4BitPin,
+    vcc: GrayCounter4Bit::VCC,
+    gnd: GrayCounter4Bit::GND,
+    clk: GrayCounter4Bit::CLK,
+    q0: GrayCounter4Bit::Q0,
+    q1: GrayCounter4Bit::Q1,
+    q2: GrayCounter4Bit::Q2,
+    q3: GrayCounter4Bit::Q3
+);
+
+impl ChipRunner for GrayCounter4Bit {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let clock: bool = self.clk.state.as_logic(threshold).into();
+            if clock && !self.clock {
+                self.count = (self.count + 1) & 0x0F;
+            }
+            self.clock = clock;
+
+            let value = self.value() as usize;
+            Pin::write(
+                &mut [&mut self.q0, &mut self.q1, &mut self.q2, &mut self.q3],
+                value,
+            );
+        } else {
+            self.reset();
+            for pin in [&mut self.q0, &mut self.q1, &mut self.q2, &mut self.q3] {
+                pin.state = State::Undefined;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = false;
+        self.count = 0;
+    }
+}
+
+/// # A 4-bit up/down counter with parallel load
+///
+/// Modeled after the 74191: on each rising edge of CLK, counts up, or down while D/!U is high.
+/// While !LOAD is low, the rising edge captures P0-P3 instead of counting.
+/// Leaving !LOAD unconnected keeps the counter counting.
+///
+/// # Diagram
+/// D/!U: Down/Up
+/// ```txt
+///          ---__---
+///     P1 --|1   16|-- VCC
+///     Q1 --|2   15|-- P0
+///     Q0 --|3   14|-- CLK
+/// UNUSED --|4   13|-- UNUSED
+///   D/!U --|5   12|-- UNUSED
+///     Q2 --|6   11|-- !LOAD
+///     Q3 --|7   10|-- P2
+///    GND --|8    9|-- P3
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpDownCounter4Bit {
+    clock: bool,
+    count: u8,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub clk: Pin,
+    pub down: Pin,
+    pub load: Pin,
+    pub p0: Pin,
+    pub p1: Pin,
+    pub p2: Pin,
+    pub p3: Pin,
+    pub q0: Pin,
+    pub q1: Pin,
+    pub q2: Pin,
+    pub q3: Pin,
+}
+
+impl UpDownCounter4Bit {
+    pub const P1: PinId = 1;
+    pub const Q1: PinId = 2;
+    pub const Q0: PinId = 3;
+    pub const DOWN: PinId = 5;
+    pub const Q2: PinId = 6;
+    pub const Q3: PinId = 7;
+    pub const GND: PinId = 8;
+    pub const P3: PinId = 9;
+    pub const P2: PinId = 10;
+    pub const LOAD: PinId = 11;
+    pub const CLK: PinId = 14;
+    pub const P0: PinId = 15;
+    pub const VCC: PinId = 16;
+
+    /// Get the current count
+    pub fn value(&self) -> u8 {
+        self.count
+    }
+}
+
+impl ChipBuilder<UpDownCounter4Bit> for UpDownCounter4Bit {
+    fn build() -> UpDownCounter4Bit {
+        UpDownCounter4Bit {
+            clock: false,
+            count: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            clk: Pin::from(PinType::Input),
+            down: Pin::from(PinType::Input),
+            load: Pin::from(PinType::Input),
+            p0: Pin::from(PinType::Input),
+            p1: Pin::from(PinType::Input),
+            p2: Pin::from(PinType::Input),
+            p3: Pin::from(PinType::Input),
+            q0: Pin::from(PinType::Output),
+            q1: Pin::from(PinType::Output),
+            q2: Pin::from(PinType::Output),
+            q3: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<UpDownCounter4Bit> for ChipSet {
+    fn from(value: UpDownCounter4Bit) -> Self {
+        ChipSet::UpDownCounter4Bit(value)
+    }
+}
+
+generate_chip!(
+    UpDownCounter4Bit => UpDownCounter4BitPin,
+    vcc: UpDownCounter4Bit::VCC,
+    gnd: UpDownCounter4Bit::GND,
+    clk: UpDownCounter4Bit::CLK,
+    down: UpDownCounter4Bit::DOWN,
+    load: UpDownCounter4Bit::LOAD,
+    p0: UpDownCounter4Bit::P0,
+    p1: UpDownCounter4Bit::P1,
+    p2: UpDownCounter4Bit::P2,
+    p3: UpDownCounter4Bit::P3,
+    q0: UpDownCounter4Bit::Q0,
+    q1: UpDownCounter4Bit::Q1,
+    q2: UpDownCounter4Bit::Q2,
+    q3: UpDownCounter4Bit::Q3
+);
+
+impl ChipRunner for UpDownCounter4Bit {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let clock: bool = self.clk.state.as_logic(threshold).into();
+            if clock && !self.clock {
+                if self.load.state != State::Undefined
+                    && self.load.state.as_logic(threshold) == State::Low
+                {
+                    self.count =
+                        Pin::read_threshold(&[&self.p0, &self.p1, &self.p2, &self.p3], threshold)
+                            as u8;
+                } else if self.down.state.as_logic(threshold) == State::High {
+                    self.count = self.count.wrapping_sub(1) & 0x0F;
+                } else {
+                    self.count = (self.count + 1) & 0x0F;
+                }
+            }
+            self.clock = clock;
+
+            Pin::write(
+                &mut [&mut self.q0, &mut self.q1, &mut self.q2, &mut self.q3],
+                self.count as usize,
+            );
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = false;
+        self.count = 0;
+    }
+}
+
+/// # A clock divider
+///
+/// OUT toggles once every N edges (rising or falling) of CLK, so that its frequency is the one
+/// of CLK divided by N. N is set using `with_divisor` and defaults to 2.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///   CLK --|1    4|-- VCC
+///   OUT --|2    3|-- GND
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClockDivider {
+    divisor: u32,
+    clock: bool,
+    edges: u32,
+    active: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub clk: Pin,
+    pub out: Pin,
+}
+
+impl ClockDivider {
+    pub const CLK: PinId = 1;
+    pub const OUT: PinId = 2;
+    pub const GND: PinId = 3;
+    pub const VCC: PinId = 4;
+
+    /// Set the factor by which the clock frequency is divided
+    pub fn with_divisor(mut self, divisor: u32) -> Self {
+        self.divisor = divisor.max(1);
+        self
+    }
+}
+
+impl ChipBuilder<ClockDivider> for ClockDivider {
+    fn build() -> ClockDivider {
+        ClockDivider {
+            divisor: 2,
+            clock: false,
+            edges: 0,
+            active: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            clk: Pin::from(PinType::Input),
+            out: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<ClockDivider> for ChipSet {
+    fn from(value: ClockDivider) -> Self {
+        ChipSet::ClockDivider(value)
+    }
+}
+
+generate_chip!(
+    ClockDivider => ClockDividerPin,
+    vcc: ClockDivider::VCC,
+    gnd: ClockDivider::GND,
+    clk: ClockDivider::CLK,
+    out: ClockDivider::OUT
+);
+
+impl ChipRunner for ClockDivider {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let clock: bool = self.clk.state.as_logic(threshold).into();
+            if clock != self.clock {
+                self.edges += 1;
+                if self.edges >= self.divisor {
+                    self.edges = 0;
+                    self.active = !self.active;
+                }
+            }
+            self.clock = clock;
+            self.out.state = State::from(self.active);
+        } else {
+            self.reset();
+            self.out.state = State::Undefined;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = false;
+        self.edges = 0;
+        self.active = false;
+    }
+}
+
+/// # A frequency counter
+///
+/// Counts the rising edges of CLK while GATE is high, and measures how long the gate stayed
+/// open. A new measurement starts every time GATE goes high, the last one is kept once it
+/// goes low.
+///
+/// # Diagram
+/// ```txt
+///          ---__---
+///    CLK --|1    4|-- VCC
+///   GATE --|2    3|-- GND
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrequencyCounter {
+    clock: bool,
+    gate_open: bool,
+    edges: u32,
+    window: Duration,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub clk: Pin,
+    pub gate: Pin,
+}
+
+impl FrequencyCounter {
+    pub const CLK: PinId = 1;
+    pub const GATE: PinId = 2;
+    pub const GND: PinId = 3;
+    pub const VCC: PinId = 4;
+
+    /// Get the amount of rising edges counted during the last measurement
+    pub fn count(&self) -> u32 {
+        self.edges
+    }
+
+    /// Get the frequency measured during the last measurement, or 0 if the gate never opened
+    pub fn frequency_hz(&self) -> f32 {
+        if self.window.is_zero() {
+            0.0
+        } else {
+            self.edges as f32 / self.window.as_secs_f32()
+        }
+    }
+}
+
+impl ChipBuilder<FrequencyCounter> for FrequencyCounter {
+    fn build() -> FrequencyCounter {
+        FrequencyCounter {
+            clock: false,
+            gate_open: false,
+            edges: 0,
+            window: Duration::ZERO,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            clk: Pin::from(PinType::Input),
+            gate: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<FrequencyCounter> for ChipSet {
+    fn from(value: FrequencyCounter) -> Self {
+        ChipSet::FrequencyCounter(value)
+    }
+}
+
+generate_chip!(
+    FrequencyCounter => FrequencyCounterPin,
+    vcc: FrequencyCounter::VCC,
+    gnd: FrequencyCounter::GND,
+    clk: FrequencyCounter::CLK,
+    gate: FrequencyCounter::GATE
+);
+
+impl ChipRunner for FrequencyCounter {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let clock: bool = self.clk.state.as_logic(threshold).into();
+            let gate: bool = self.gate.state.as_logic(threshold).into();
+            if gate {
+                if !self.gate_open {
+                    self.edges = 0;
+                    self.window = Duration::ZERO;
+                } else if clock && !self.clock {
+                    self.edges += 1;
+                }
+                self.window += tick_duration;
+            }
+            self.clock = clock;
+            self.gate_open = gate;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = false;
+        self.gate_open = false;
+        self.edges = 0;
+        self.window = Duration::ZERO;
+    }
+}
+
+/// # A programmable interval timer
+///
+/// Raises an interrupt request every N rising edges of CLK, N being the 16-bit reload value.
+/// The reload value is written through the data bus while !CS and R/!W are low, A0 selecting
+/// its low (A0 low) or high (A0 high) byte. Writing the high byte also restarts the count.
+///
+/// Each time the down-counter reaches zero, !IRQ goes low until the next rising edge of CLK and
+/// the counter is reloaded. A reload value of zero stops the timer.
+/// To get periodic interrupts on a 6502, connect !IRQ to `Nes6502::IRQ` and the data bus,
+/// A0 and R/!W to the ones of the CPU, with some address decoding driving !CS.
+/// Note that the `Nes6502` doesn't handle interrupts yet and ignores its IRQ pin, so until it
+/// does, !IRQ can only be polled by other chips or watched with a `LogicAnalyzer`.
+///
+/// # Diagram
+/// CS: Chip Select (active low)
+/// R/!W: Read/Write, the timer only accepts writes
+/// ```txt
+///          ---__---
+///     D0 --|1   16|-- VCC
+///     D1 --|2   15|-- UNUSED
+///     D2 --|3   14|-- !IRQ
+///     D3 --|4   13|-- CLK
+///     D4 --|5   12|-- R/!W
+///     D5 --|6   11|-- !CS
+///     D6 --|7   10|-- A0
+///    GND --|8    9|-- D7
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timer {
+    clock: bool,
+    reload: u16,
+    counter: u16,
+    interrupt: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub clk: Pin,
+    pub cs: Pin,
+    pub rw: Pin,
+    pub a0: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+    pub irq: Pin,
+}
+
+impl Timer {
+    pub const D0: PinId = 1;
+    pub const D1: PinId = 2;
+    pub const D2: PinId = 3;
+    pub const D3: PinId = 4;
+    pub const D4: PinId = 5;
+    pub const D5: PinId = 6;
+    pub const D6: PinId = 7;
+    pub const GND: PinId = 8;
+    pub const D7: PinId = 9;
+    pub const A0: PinId = 10;
+    pub const CS: PinId = 11;
+    pub const RW: PinId = 12;
+    pub const CLK: PinId = 13;
+    pub const IRQ: PinId = 14;
+    pub const VCC: PinId = 16;
+
+    /// Set the reload value the timer starts with, defaults to 0 (stopped)
+    pub fn with_reload(mut self, reload: u16) -> Self {
+        self.reload = reload;
+        self.counter = reload;
+        self
+    }
+
+    /// Get the reload value
+    pub fn reload(&self) -> u16 {
+        self.reload
+    }
+
+    /// Get the number of CLK edges left before the next interrupt request
+    pub fn counter(&self) -> u16 {
+        self.counter
+    }
+}
+
+impl ChipBuilder<Timer> for Timer {
+    fn build() -> Timer {
+        Timer {
+            clock: false,
+            reload: 0,
+            counter: 0,
+            interrupt: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            clk: Pin::from(PinType::Input),
+            cs: Pin::from(PinType::Input),
+            rw: Pin::from(PinType::Input),
+            a0: Pin::from(PinType::Input),
+            d0: Pin::from(PinType::Input),
+            d1: Pin::from(PinType::Input),
+            d2: Pin::from(PinType::Input),
+            d3: Pin::from(PinType::Input),
+            d4: Pin::from(PinType::Input),
+            d5: Pin::from(PinType::Input),
+            d6: Pin::from(PinType::Input),
+            d7: Pin::from(PinType::Input),
+            irq: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<Timer> for ChipSet {
+    fn from(value: Timer) -> Self {
+        ChipSet::Timer(value)
+    }
+}
+
+generate_chip!(
+    Timer => TimerPin,
+    vcc: Timer::VCC,
+    gnd: Timer::GND,
+    clk: Timer::CLK,
+    cs: Timer::CS,
+    rw: Timer::RW,
+    a0: Timer::A0,
+    d0: Timer::D0,
+    d1: Timer::D1,
+    d2: Timer::D2,
+    d3: Timer::D3,
+    d4: Timer::D4,
+    d5: Timer::D5,
+    d6: Timer::D6,
+    d7: Timer::D7,
+    irq: Timer::IRQ
+);
+
+impl ChipRunner for Timer {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            // check Chip Select and Write (active low)
+            if self.cs.state == State::Low && self.rw.state == State::Low {
+                let data = Pin::read_threshold(
+                    &[
+                        &self.d0, &self.d1, &self.d2, &self.d3, &self.d4, &self.d5, &self.d6,
+                        &self.d7,
+                    ],
+                    threshold,
+                ) as u16;
+                if self.a0.state.as_logic(threshold) == State::High {
+                    self.reload = (self.reload & 0x00FF) | (data << 8);
+                    self.counter = self.reload;
+                } else {
+                    self.reload = (self.reload & 0xFF00) | data;
+                }
+            }
+
+            let clock: bool = self.clk.state.as_logic(threshold).into();
+            if clock && !self.clock {
+                self.interrupt = false;
+                if self.reload != 0 {
+                    self.counter = self.counter.saturating_sub(1);
+                    if self.counter == 0 {
+                        self.interrupt = true;
+                        self.counter = self.reload;
+                    }
+                }
+            }
+            self.clock = clock;
+
+            self.irq.state = State::from(!self.interrupt);
+        } else {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = false;
+        self.counter = self.reload;
+        self.interrupt = false;
+    }
+}
+
+/// # A watchdog timer
+///
+/// Expects a rising edge on KICK at least once every timeout. When it doesn't get one in time,
+/// !RESET goes low for the length of the reset pulse, then the watchdog starts waiting for a
+/// kick again. Kicking the watchdog during the pulse ends it right away.
+///
+/// # Diagram
+/// KICK: Clears the watchdog on a rising edge
+/// !RESET: Reset output (active low)
+/// ```txt
+///           --------
+///    KICK --|1    4|-- VCC
+///     GND --|2    3|-- !RESET
+///           --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Watchdog {
+    timeout: Duration,
+    pulse_width: Duration,
+    timer: Duration,
+    resetting: bool,
+    kick_was_high: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub kick: Pin,
+    pub reset: Pin,
+}
+
+impl Watchdog {
+    pub const KICK: PinId = 1;
+    pub const GND: PinId = 2;
+    pub const RESET: PinId = 3;
+    pub const VCC: PinId = 4;
+
+    /// Set the time the watchdog waits for a kick before resetting, defaults to 1s
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how long !RESET stays low once the watchdog timed out, defaults to 1ms
+    pub fn with_pulse_width(mut self, pulse_width: Duration) -> Self {
+        self.pulse_width = pulse_width;
+        self
+    }
+
+    /// Whether the watchdog timed out and is currently resetting
+    pub fn is_resetting(&self) -> bool {
+        self.resetting
+    }
+}
+
+impl ChipBuilder<Watchdog> for Watchdog {
+    fn build() -> Watchdog {
+        Watchdog {
+            timeout: Duration::from_secs(1),
+            pulse_width: Duration::from_millis(1),
+            timer: Duration::default(),
+            resetting: false,
+            kick_was_high: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            kick: Pin::from(PinType::Input),
+            reset: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<Watchdog> for ChipSet {
+    fn from(value: Watchdog) -> Self {
+        ChipSet::Watchdog(value)
+    }
+}
+
+generate_chip!(
+    Watchdog => WatchdogPin,
+    vcc: Watchdog::VCC,
+    gnd: Watchdog::GND,
+    kick: Watchdog::KICK,
+    reset: Watchdog::RESET
+);
+
+impl ChipRunner for Watchdog {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let kick: bool = self.kick.state.as_logic(threshold).into();
+            if kick && !self.kick_was_high {
+                self.timer = Duration::default();
+                self.resetting = false;
+            } else {
+                self.timer += tick_duration;
+                if self.resetting && self.timer >= self.pulse_width {
+                    self.timer = Duration::default();
+                    self.resetting = false;
+                } else if !self.resetting && self.timer >= self.timeout {
+                    self.timer = Duration::default();
+                    self.resetting = true;
+                }
+            }
+            self.kick_was_high = kick;
+
+            self.reset.state = State::from(!self.resetting);
+        } else {
+            self.reset();
+        }
+    }
+
+    fn reset(&mut self) {
+        self.timer = Duration::default();
+        self.resetting = false;
+        self.kick_was_high = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        ClockDivider, FrequencyCounter, GrayCounter4Bit, Timer, UpDownCounter4Bit, Watchdog,
+    };
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin},
+        State,
+    };
+
+    #[test]
+    fn gray_counter_changes_one_bit_per_edge() {
+        let mut counter = GrayCounter4Bit::build();
+        counter.vcc.state = State::High;
+        let read = |counter: &GrayCounter4Bit| {
+            Pin::read(&[&counter.q0, &counter.q1, &counter.q2, &counter.q3]) as u8
+        };
+        counter.run(Duration::from_micros(1));
+        let mut outputs = vec![read(&counter)];
+        for _ in 0..16 {
+            for clock in [true, false] {
+                counter.clk.state = State::from(clock);
+                counter.run(Duration::from_micros(1));
+            }
+            outputs.push(read(&counter));
+        }
+        assert_eq!(
+            outputs,
+            [0, 1, 3, 2, 6, 7, 5, 4, 12, 13, 15, 14, 10, 11, 9, 8, 0]
+        );
+        assert!(outputs
+            .windows(2)
+            .all(|pair| (pair[0] ^ pair[1]).count_ones() == 1));
+
+        // a power loss clears the count
+        counter.clk.state = State::High;
+        counter.run(Duration::from_micros(1));
+        counter.vcc.state = State::Low;
+        counter.run(Duration::from_micros(1));
+        assert_eq!(counter.q0.state, State::Undefined);
+        counter.vcc.state = State::High;
+        counter.clk.state = State::Low;
+        counter.run(Duration::from_micros(1));
+        assert_eq!(counter.value(), 0);
+        assert_eq!(read(&counter), 0);
+    }
+
+    #[test]
+    fn up_down_counter_counts_both_ways_and_loads() {
+        let mut counter = UpDownCounter4Bit::build();
+        counter.vcc.state = State::High;
+        let three_edges = |counter: &mut UpDownCounter4Bit| {
+            let mut outputs = vec![];
+            for _ in 0..3 {
+                for clock in [true, false] {
+                    counter.clk.state = State::from(clock);
+                    counter.run(Duration::from_micros(1));
+                }
+                outputs.push(counter.value());
+            }
+            outputs
+        };
+
+        assert_eq!(three_edges(&mut counter), [1, 2, 3]);
+        counter.down.state = State::High;
+        assert_eq!(three_edges(&mut counter), [2, 1, 0]);
+        // counting down wraps around
+        assert_eq!(three_edges(&mut counter), [15, 14, 13]);
+
+        // the parallel inputs are captured instead of counting while LOAD is low
+        Pin::write(
+            &mut [
+                &mut counter.p0,
+                &mut counter.p1,
+                &mut counter.p2,
+                &mut counter.p3,
+            ],
+            9,
+        );
+        counter.load.state = State::Low;
+        assert_eq!(three_edges(&mut counter), [9, 9, 9]);
+        counter.load.state = State::High;
+        counter.down.state = State::Low;
+        assert_eq!(three_edges(&mut counter), [10, 11, 12]);
+        assert_eq!(
+            Pin::read(&[&counter.q0, &counter.q1, &counter.q2, &counter.q3]),
+            12
+        );
+    }
+
+    #[test]
+    fn clock_divider_divides_the_frequency() {
+        let mut divider = ClockDivider::build().with_divisor(4);
+        divider.vcc.state = State::High;
+        let mut output = false;
+        let mut rising_edges = 0;
+        for _ in 0..100 {
+            for clock in [true, false] {
+                divider.clk.state = State::from(clock);
+                divider.run(Duration::from_micros(1));
+                let out = bool::from(divider.out.state);
+                if out && !output {
+                    rising_edges += 1;
+                }
+                output = out;
+            }
+        }
+        // the output completes one period every 4 input periods
+        assert_eq!(rising_edges, 25);
+
+        // a power loss restarts the division
+        for clock in [true, false, true, false] {
+            divider.clk.state = State::from(clock);
+            divider.run(Duration::from_micros(1));
+        }
+        assert_eq!(divider.out.state, State::High);
+        divider.vcc.state = State::Low;
+        divider.run(Duration::from_micros(1));
+        assert_eq!(divider.out.state, State::Undefined);
+        divider.vcc.state = State::High;
+        divider.run(Duration::from_micros(1));
+        assert_eq!(divider.out.state, State::Low);
+    }
+
+    #[test]
+    fn frequency_counter_measures_a_50hz_clock() {
+        let mut counter = FrequencyCounter::build();
+        counter.vcc.state = State::High;
+        counter.gate.state = State::High;
+        // a 50Hz clock toggles every 10ms, sampled every millisecond for a second
+        for tick in 0..1000 {
+            counter.clk.state = State::from(tick / 10 % 2 == 1);
+            counter.run(Duration::from_millis(1));
+        }
+        counter.gate.state = State::Low;
+        counter.run(Duration::from_millis(1));
+
+        assert_eq!(counter.count(), 50);
+        assert!((counter.frequency_hz() - 50.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn timer_requests_an_interrupt_every_reload_edges() {
+        let mut timer = Timer::build().with_reload(3);
+        timer.vcc.state = State::High;
+        timer.cs.state = State::High;
+        let mut interrupts = vec![];
+        for edge in 1..=9 {
+            for clock in [true, false] {
+                timer.clk.state = State::from(clock);
+                timer.run(Duration::from_micros(1));
+                if clock && timer.irq.state == State::Low {
+                    interrupts.push(edge);
+                }
+            }
+        }
+        assert_eq!(interrupts, [3, 6, 9]);
+        // the request is released on the next rising edge
+        timer.clk.state = State::High;
+        timer.run(Duration::from_micros(1));
+        assert_eq!(timer.irq.state, State::High);
+    }
+
+    #[test]
+    fn watchdog_resets_once_no_longer_kicked() {
+        let mut watchdog = Watchdog::build()
+            .with_timeout(Duration::from_millis(10))
+            .with_pulse_width(Duration::from_millis(2));
+        watchdog.vcc.state = State::High;
+        let mut step = |kick: bool| {
+            watchdog.kick.state = State::from(kick);
+            watchdog.run(Duration::from_millis(1));
+            watchdog.reset.state
+        };
+
+        // kicked every 5ms, the watchdog never fires
+        for tick in 0..50 {
+            assert_eq!(step(tick % 5 == 0), State::High, "at {tick}ms");
+        }
+
+        // the last kick was 5ms ago, the reset pulse starts 10ms after it
+        let outputs: Vec<_> = (0..10).map(|_| step(false)).collect();
+        assert_eq!(
+            outputs,
+            [&[State::High; 5][..], &[State::Low; 2], &[State::High; 3]].concat()
+        );
+    }
+}
@@ -19,6 +19,46 @@ macro_rules! default_impl {
             pub fn dec(&mut self) {
                 self.inner = self.inner.wrapping_sub(1)
             }
+
+            /// Add a value, or get None if the result doesn't fit in the register
+            pub fn checked_add(self, rhs: $name) -> ::std::option::Option<Self> {
+                self.inner.checked_add(rhs).map(Self::from)
+            }
+
+            /// Subtract a value, or get None if the result would go below zero
+            pub fn checked_sub(self, rhs: $name) -> ::std::option::Option<Self> {
+                self.inner.checked_sub(rhs).map(Self::from)
+            }
+
+            /// Add a value with wrapping, along with whether it overflowed (the carry)
+            pub fn overflowing_add(self, rhs: $name) -> (Self, bool) {
+                let (value, overflow) = self.inner.overflowing_add(rhs);
+                (value.into(), overflow)
+            }
+
+            /// Subtract a value with wrapping, along with whether it went below zero (the borrow)
+            pub fn overflowing_sub(self, rhs: $name) -> (Self, bool) {
+                let (value, overflow) = self.inner.overflowing_sub(rhs);
+                (value.into(), overflow)
+            }
+        }
+
+        impl ::std::fmt::Display for $crate::chip::cpu::Reg<$name> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.inner, f)
+            }
+        }
+
+        impl ::std::fmt::LowerHex for $crate::chip::cpu::Reg<$name> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::LowerHex::fmt(&self.inner, f)
+            }
+        }
+
+        impl ::std::fmt::UpperHex for $crate::chip::cpu::Reg<$name> {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::UpperHex::fmt(&self.inner, f)
+            }
         }
         impl ::std::convert::From<$name> for $crate::chip::cpu::Reg<$name> {
             fn from(value: $name) -> Self {
@@ -214,3 +254,31 @@ macro_rules! default_impl {
 }
 
 default_impl!(u8, u16);
+
+#[cfg(test)]
+mod tests {
+    use super::Reg;
+
+    #[test]
+    fn registers_format_like_their_value() {
+        let a = Reg::from(0x2Au8);
+        assert_eq!(format!("{a}"), "42");
+        assert_eq!(format!("{a:02x}"), "2a");
+        assert_eq!(format!("{a:#04X}"), "0x2A");
+        let pc = Reg::from(0x8000u16);
+        assert_eq!(format!("{pc:04X}"), "8000");
+    }
+
+    #[test]
+    fn checked_and_overflowing_arithmetic_detect_the_carry() {
+        let a = Reg::from(0xF0u8);
+        assert_eq!(a.checked_add(0x0F), Some(Reg::from(0xFF)));
+        assert_eq!(a.checked_add(0x10), None);
+        assert_eq!(a.overflowing_add(0x20), (Reg::from(0x10), true));
+        assert_eq!(a.overflowing_add(0x01), (Reg::from(0xF1), false));
+
+        let sp = Reg::from(0x0001u16);
+        assert_eq!(sp.checked_sub(2), None);
+        assert_eq!(sp.overflowing_sub(2), (Reg::from(0xFFFF), true));
+    }
+}
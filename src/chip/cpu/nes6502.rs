@@ -1,9 +1,11 @@
 pub mod assembler;
 pub mod opcodes;
 
-pub use assembler::Assembler;
+pub use assembler::{Assembler, Item};
 pub use opcodes::{AddressingMode, Opcode};
 
+use std::fmt;
+
 use crate::{
     chip::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType},
     generate_chip, State,
@@ -62,10 +64,11 @@ pub struct Registers {
     pub p: StatusRegister,
 }
 
-impl ToString for Registers {
-    fn to_string(&self) -> std::string::String {
-        format!(
-            "A={:0X}\tX={:0X}\tY={:0X}\tS={:0X}\tPC={:0X}\tP={}{}{}{}{}{}{}{}",
+impl fmt::Display for Registers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "A={:0X}\tX={:0X}\tY={:0X}\tS={:0X}\tPC={:0X}\tP={}{}-{}{}{}{}{}",
             *self.a,
             *self.x,
             *self.y,
@@ -81,7 +84,6 @@ impl ToString for Registers {
             } else {
                 "-"
             },
-            "-",
             if self.p.contains(StatusRegister::B) {
                 "B"
             } else {
@@ -111,6 +113,23 @@ impl ToString for Registers {
     }
 }
 
+/// An instruction executed by the CPU, as recorded in its execution trace
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TraceEntry {
+    /// Address of the opcode
+    pub pc: u16,
+    pub opcode: Opcode,
+    /// Registers once the instruction has been executed
+    pub registers: Registers,
+}
+
+impl fmt::Display for TraceEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04X}  {:?}\t{}", self.pc, self.opcode, self.registers)
+    }
+}
+
 /// <https://www.nesdev.org/wiki/CPU_pinout>
 /// Without the APU part yet  
 /// Neither the interrupt handling and decimal mode  
@@ -148,6 +167,21 @@ pub struct Nes6502 {
     state: CpuState,
     registers: Registers,
     buffer: u16,
+    #[cfg_attr(feature = "serde", serde(default))]
+    breakpoints: Vec<u16>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    watchpoints: Vec<u16>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    breakpoint_hit: Option<u16>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    watchpoint_hit: Option<u16>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    trace: Option<Vec<TraceEntry>>,
+    /// address and opcode of the instruction being executed, while tracing
+    #[cfg_attr(feature = "serde", serde(default))]
+    instruction: Option<(u16, Opcode)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    illegal_opcodes: bool,
     pub vcc: Pin,
     pub gnd: Pin,
     pub rst: Pin,
@@ -203,7 +237,8 @@ impl Nes6502 {
     pub const RW: usize = 34;
     /// Non-maskable interrupt pin. See the 6502 manual and CPU interrupts for more details.
     pub const NMI: usize = 33;
-    /// Interrupt pin. See the 6502 manual and CPU interrupts for more details
+    /// Interrupt pin. See the 6502 manual and CPU interrupts for more details.
+    /// Interrupts aren't emulated yet, this pin is ignored.
     pub const IRQ: usize = 32;
     pub const M2: usize = 31;
     pub const TST: usize = 30;
@@ -237,7 +272,7 @@ impl Nes6502 {
 }
 
 generate_chip!(
-    Nes6502,
+    Nes6502 => Nes6502Pin,
     vcc: Nes6502::VCC,
     gnd: Nes6502::GND,
     rst: Nes6502::RST,
@@ -280,14 +315,21 @@ generate_chip!(
     d7: Nes6502::D7
 );
 
-impl ChipBuilder<ChipSet> for Nes6502 {
-    fn build() -> ChipSet {
-        ChipSet::Nes6502(Box::new(Nes6502 {
+impl ChipBuilder<Nes6502> for Nes6502 {
+    fn build() -> Nes6502 {
+        Nes6502 {
             powered: false,
             clock: false,
             state: CpuState::Reset,
             registers: Registers::default(),
             buffer: 0,
+            breakpoints: vec![],
+            watchpoints: vec![],
+            breakpoint_hit: None,
+            watchpoint_hit: None,
+            trace: None,
+            instruction: None,
+            illegal_opcodes: false,
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             rst: Pin::from(PinType::Input),
@@ -328,11 +370,98 @@ impl ChipBuilder<ChipSet> for Nes6502 {
             d5: Pin::from(PinType::Floating),
             d6: Pin::from(PinType::Floating),
             d7: Pin::from(PinType::Floating),
-        }))
+        }
+    }
+}
+
+impl From<Nes6502> for ChipSet {
+    fn from(value: Nes6502) -> Self {
+        ChipSet::Nes6502(Box::new(value))
     }
 }
 
 impl Nes6502 {
+    /// Decode and execute the stable illegal opcodes (LAX, SAX, DCP, ISC, SLO, RLA, SRE, RRA)
+    /// instead of treating them as NOP, defaults to false
+    pub fn with_illegal_opcodes(mut self, enabled: bool) -> Self {
+        self.illegal_opcodes = enabled;
+        self
+    }
+
+    /// Flag the CPU when it fetches an opcode at the given address
+    pub fn add_breakpoint(&mut self, addr: u16) {
+        if !self.breakpoints.contains(&addr) {
+            self.breakpoints.push(addr);
+        }
+    }
+
+    pub fn remove_breakpoint(&mut self, addr: u16) {
+        self.breakpoints.retain(|&a| a != addr);
+    }
+
+    /// Address of the last breakpoint reached, until cleared with `clear_hits`
+    pub fn hit_breakpoint(&self) -> Option<u16> {
+        self.breakpoint_hit
+    }
+
+    /// Flag the CPU when an instruction reads or writes data at the given address
+    pub fn add_watchpoint(&mut self, addr: u16) {
+        if !self.watchpoints.contains(&addr) {
+            self.watchpoints.push(addr);
+        }
+    }
+
+    pub fn remove_watchpoint(&mut self, addr: u16) {
+        self.watchpoints.retain(|&a| a != addr);
+    }
+
+    /// Address of the last watchpoint accessed, until cleared with `clear_hits`
+    pub fn hit_watchpoint(&self) -> Option<u16> {
+        self.watchpoint_hit
+    }
+
+    /// Forget about the breakpoints and watchpoints that were hit
+    pub fn clear_hits(&mut self) {
+        self.breakpoint_hit = None;
+        self.watchpoint_hit = None;
+    }
+
+    /// Get whether the CPU is halted, which happens when it loses power
+    pub fn is_halted(&self) -> bool {
+        matches!(self.state, CpuState::Halted)
+    }
+
+    /// Start recording the executed instructions
+    pub fn enable_trace(&mut self) {
+        self.trace.get_or_insert_with(Vec::new);
+    }
+
+    /// Stop recording the executed instructions, and forget about the recorded ones
+    pub fn disable_trace(&mut self) {
+        self.trace = None;
+        self.instruction = None;
+    }
+
+    /// Take the instructions recorded since the last call, empty if tracing isn't enabled
+    pub fn take_trace(&mut self) -> Vec<TraceEntry> {
+        self.trace.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Start executing an opcode once all of its arguments are known
+    fn execute(&mut self, opcode: Opcode) {
+        if let Some((_, decoded)) = &mut self.instruction {
+            *decoded = opcode;
+        }
+        self.state = CpuState::Execute(opcode, 0);
+    }
+
+    fn get_addr(&self) -> u16 {
+        Pin::read(&[
+            &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6, &self.a7,
+            &self.a8, &self.a9, &self.a10, &self.a11, &self.a12, &self.a13, &self.a14, &self.a15,
+        ]) as u16
+    }
+
     pub fn set_addr(&mut self, addr: u16) {
         Pin::write(
             &mut [
@@ -358,6 +487,13 @@ impl Nes6502 {
     }
 
     fn set_data_type(&mut self, pin_type: PinType) {
+        // instructions set the data direction whenever they access memory
+        if matches!(self.state, CpuState::Execute(_, _))
+            && !matches!(pin_type, PinType::Floating)
+            && self.watchpoints.contains(&self.get_addr())
+        {
+            self.watchpoint_hit = Some(self.get_addr());
+        }
         match pin_type {
             PinType::Input => self.rw.state = State::High,
             PinType::Output => self.rw.state = State::Low,
@@ -403,18 +539,11 @@ impl ChipRunner for Nes6502 {
     fn run(&mut self, _: std::time::Duration) {
         if self.vcc.state.as_logic(3.3) == State::High {
             if !self.powered {
-                self.state = CpuState::Reset;
-                self.registers.p = StatusRegister::from_bits_retain(0x34);
-                self.registers.a = 0.into();
-                self.registers.x = 0.into();
-                self.registers.y = 0.into();
-                self.registers.s = 0xFD.into();
-                self.registers.pc = 0xFFFC.into();
-
+                self.reset();
                 self.powered = true;
             }
 
-            if self.clock != self.clk.state.as_logic(3.3).into() {
+            if self.clock != bool::from(self.clk.state.as_logic(3.3)) {
                 self.clock = self.clk.state.as_logic(3.3).into();
                 self.m2.state = State::from(self.clock);
                 if self.clock {
@@ -439,16 +568,17 @@ impl ChipRunner for Nes6502 {
                         CpuState::Reset => {
                             self.set_addr(*self.registers.pc);
                             self.registers.pc.inc();
-                            self.state = CpuState::ResetCollectHighByte;
+                            self.state = CpuState::ResetCollectLowByte;
                         }
-                        CpuState::ResetCollectHighByte => {
-                            self.buffer = (self.get_data() as u16) << 8;
+                        // vectors are stored little-endian, like any other address
+                        CpuState::ResetCollectLowByte => {
+                            self.buffer = self.get_data() as u16;
                             self.set_addr(*self.registers.pc);
                             self.registers.pc.inc();
-                            self.state = CpuState::ResetCollectLowByte;
+                            self.state = CpuState::ResetCollectHighByte;
                         }
-                        CpuState::ResetCollectLowByte => {
-                            self.buffer = self.buffer.wrapping_add(self.get_data() as u16);
+                        CpuState::ResetCollectHighByte => {
+                            self.buffer = self.buffer.wrapping_add((self.get_data() as u16) << 8);
                             self.registers.pc = self.buffer.into();
                             self.set_addr(*self.registers.pc);
                             self.registers.pc.inc();
@@ -459,13 +589,19 @@ impl ChipRunner for Nes6502 {
                         CpuState::IrqCollectHighByte => todo!(),
                         CpuState::IrqCollectLowByte => todo!(),
                         CpuState::Fetch => {
-                            let opcode = Opcode::from(self.get_data());
+                            if self.breakpoints.contains(&self.get_addr()) {
+                                self.breakpoint_hit = Some(self.get_addr());
+                            }
+                            let opcode = Opcode::decode(self.get_data(), self.illegal_opcodes);
+                            if self.trace.is_some() {
+                                self.instruction = Some((self.get_addr(), opcode));
+                            }
                             if opcode.require_arg1() {
                                 self.set_addr(*self.registers.pc);
                                 self.registers.pc.inc();
                                 self.state = CpuState::Arg1(opcode);
                             } else {
-                                self.state = CpuState::Execute(opcode, 0);
+                                self.execute(opcode);
                             }
                         }
                         CpuState::Arg1(mut opcode) => {
@@ -475,12 +611,12 @@ impl ChipRunner for Nes6502 {
                                 self.registers.pc.inc();
                                 self.state = CpuState::Arg2(opcode);
                             } else {
-                                self.state = CpuState::Execute(opcode, 0);
+                                self.execute(opcode);
                             }
                         }
                         CpuState::Arg2(mut opcode) => {
                             opcode.set_arg2(self.get_data());
-                            self.state = CpuState::Execute(opcode, 0);
+                            self.execute(opcode);
                         }
                         CpuState::Execute(mut opcode, mut step) => {
                             if opcode.need_compute() {
@@ -1163,7 +1299,28 @@ impl ChipRunner for Nes6502 {
                                         }
                                         _ => unreachable!(),
                                     },
-                                    Opcode::RTI => todo!(),
+                                    Opcode::RTI => {
+                                        if step == 0 {
+                                            self.pop_stack_prepare();
+                                            step += 1;
+                                        } else if step == 1 {
+                                            // B only exists on the stack, and the unused bit always reads as set
+                                            self.registers.p = StatusRegister::from_bits_retain(
+                                                (self.get_data() & !StatusRegister::B.bits())
+                                                    | 0b00100000,
+                                            );
+                                            self.pop_stack_prepare();
+                                            step += 1;
+                                        } else if step == 2 {
+                                            self.buffer = self.get_data() as u16;
+                                            self.pop_stack_prepare();
+                                            step += 1;
+                                        } else {
+                                            self.buffer += (self.get_data() as u16) << 8;
+                                            self.registers.pc = self.buffer.into();
+                                            self.state = CpuState::Fetch;
+                                        }
+                                    }
                                     Opcode::RTS => {
                                         if step == 0 {
                                             self.buffer = 0;
@@ -1254,8 +1411,6 @@ impl ChipRunner for Nes6502 {
                                         }
                                         AddressingMode::Absolute(a) => {
                                             if step == 0 {
-                                                step += 1;
-                                            } else if step == 1 {
                                                 self.run_st(*self.registers.a, a);
                                                 step += 1;
                                             } else {
@@ -1275,8 +1430,6 @@ impl ChipRunner for Nes6502 {
                                         }
                                         AddressingMode::Absolute(a) => {
                                             if step == 0 {
-                                                step += 1;
-                                            } else if step == 1 {
                                                 self.run_st(*self.registers.x, a);
                                                 step += 1;
                                             } else {
@@ -1296,8 +1449,6 @@ impl ChipRunner for Nes6502 {
                                         }
                                         AddressingMode::Absolute(a) => {
                                             if step == 0 {
-                                                step += 1;
-                                            } else if step == 1 {
                                                 self.run_st(*self.registers.y, a);
                                                 step += 1;
                                             } else {
@@ -1306,12 +1457,88 @@ impl ChipRunner for Nes6502 {
                                         }
                                         _ => unreachable!(),
                                     },
+                                    Opcode::LAX(a) => {
+                                        let addr = match a {
+                                            AddressingMode::ZeroPage(z) => z as u16,
+                                            AddressingMode::Absolute(a) => a,
+                                            _ => unreachable!(),
+                                        };
+                                        if step == 0 {
+                                            self.set_addr(addr);
+                                            self.set_data_type(PinType::Input);
+                                            step += 1;
+                                        } else {
+                                            self.run_lax(self.get_data());
+                                        }
+                                    }
+                                    Opcode::SAX(a) => {
+                                        let addr = match a {
+                                            AddressingMode::ZeroPage(z) => z as u16,
+                                            AddressingMode::Absolute(a) => a,
+                                            _ => unreachable!(),
+                                        };
+                                        if step == 0 {
+                                            self.run_st(
+                                                *self.registers.a & *self.registers.x,
+                                                addr,
+                                            );
+                                            step += 1;
+                                        } else {
+                                            self.state = CpuState::Fetch;
+                                        }
+                                    }
+                                    Opcode::DCP(a)
+                                    | Opcode::ISC(a)
+                                    | Opcode::SLO(a)
+                                    | Opcode::RLA(a)
+                                    | Opcode::SRE(a)
+                                    | Opcode::RRA(a) => {
+                                        let addr = match a {
+                                            AddressingMode::ZeroPage(z) => z as u16,
+                                            AddressingMode::Absolute(a) => a,
+                                            _ => unreachable!(),
+                                        };
+                                        if step == 0 {
+                                            self.set_addr(addr);
+                                            self.set_data_type(PinType::Input);
+                                            step += 1;
+                                        } else if step == 1 {
+                                            // write the modified value back, then combine it with A
+                                            let data = self.modify_illegal(opcode, self.get_data());
+                                            self.set_data(data);
+                                            self.set_data_type(PinType::Output);
+                                            step += 1;
+                                        } else {
+                                            let data = self.get_data();
+                                            match opcode {
+                                                Opcode::DCP(_) => {
+                                                    self.run_cmp(self.registers.a, data)
+                                                }
+                                                Opcode::ISC(_) => self.run_sbc(data),
+                                                Opcode::SLO(_) => self.run_ora(data),
+                                                Opcode::RLA(_) => self.run_and(data),
+                                                Opcode::SRE(_) => self.run_eor(data),
+                                                Opcode::RRA(_) => self.run_adc(data),
+                                                _ => unreachable!(),
+                                            }
+                                        }
+                                    }
+                                    Opcode::Illegal(_) => self.state = CpuState::Fetch,
                                 }
                             }
                             if matches!(self.state, CpuState::Execute(_, _)) {
                                 self.state = CpuState::Execute(opcode, step);
                             }
                             if matches!(self.state, CpuState::Fetch) {
+                                if let (Some(trace), Some((pc, opcode))) =
+                                    (&mut self.trace, self.instruction.take())
+                                {
+                                    trace.push(TraceEntry {
+                                        pc,
+                                        opcode,
+                                        registers: self.registers,
+                                    });
+                                }
                                 self.set_addr(*self.registers.pc);
                                 self.registers.pc.inc();
                             }
@@ -1325,6 +1552,16 @@ impl ChipRunner for Nes6502 {
             self.powered = false;
         }
     }
+
+    fn reset(&mut self) {
+        self.state = CpuState::Reset;
+        self.registers.p = StatusRegister::from_bits_retain(0x34);
+        self.registers.a = 0.into();
+        self.registers.x = 0.into();
+        self.registers.y = 0.into();
+        self.registers.s = 0xFD.into();
+        self.registers.pc = 0xFFFC.into();
+    }
 }
 
 impl Nes6502 {
@@ -1411,6 +1648,12 @@ impl Nes6502 {
         self.set_flags_nz(*self.registers.a);
         self.state = CpuState::Fetch;
     }
+    fn run_lax(&mut self, val: u8) {
+        self.registers.a = val.into();
+        self.registers.x = val.into();
+        self.set_flags_nz(val);
+        self.state = CpuState::Fetch;
+    }
     fn run_ldx(&mut self, val: u8) {
         self.registers.x = val.into();
         self.set_flags_nz(*self.registers.x);
@@ -1434,6 +1677,33 @@ impl Nes6502 {
         self.run_adc(!val)
     }
 
+    /// Apply the read-modify-write part of an illegal opcode to the given memory value
+    fn modify_illegal(&mut self, opcode: Opcode, val: u8) -> u8 {
+        match opcode {
+            Opcode::DCP(_) => val.wrapping_sub(1),
+            Opcode::ISC(_) => val.wrapping_add(1),
+            Opcode::SLO(_) => {
+                self.registers.p.set(StatusRegister::C, (val & 0x80) > 0);
+                val << 1
+            }
+            Opcode::RLA(_) => {
+                let old_carry = self.registers.p.contains(StatusRegister::C) as u8;
+                self.registers.p.set(StatusRegister::C, (val & 0x80) > 0);
+                (val << 1) | old_carry
+            }
+            Opcode::SRE(_) => {
+                self.registers.p.set(StatusRegister::C, (val & 0x01) > 0);
+                val >> 1
+            }
+            Opcode::RRA(_) => {
+                let old_carry = self.registers.p.contains(StatusRegister::C) as u8;
+                self.registers.p.set(StatusRegister::C, (val & 0x01) > 0);
+                (val >> 1) | (old_carry << 7)
+            }
+            _ => val,
+        }
+    }
+
     fn run_st(&mut self, val: u8, addr: u16) {
         self.set_addr(addr);
         self.set_data(val);
@@ -1441,8 +1711,247 @@ impl Nes6502 {
     }
 }
 
-impl ToString for Nes6502 {
-    fn to_string(&self) -> std::string::String {
-        format!("state={:?}\n{}", self.state, self.registers.to_string())
+impl fmt::Display for Nes6502 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "state={:?}\n{}", self.state, self.registers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{AddressingMode, CpuState, Nes6502, Opcode, StatusRegister};
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin, PinType},
+        State,
+    };
+
+    /// A CPU wired to 64KB of memory, the program being loaded at $8000
+    struct Machine {
+        cpu: Nes6502,
+        memory: Vec<u8>,
+    }
+
+    impl Machine {
+        fn new(cpu: Nes6502, program: &[u8]) -> Self {
+            let mut memory = vec![0; 0x10000];
+            memory[0x8000..0x8000 + program.len()].copy_from_slice(program);
+            memory[0xFFFC] = 0x00;
+            memory[0xFFFD] = 0x80;
+            let mut machine = Machine { cpu, memory };
+            machine.cpu.vcc.state = State::High;
+            machine
+        }
+
+        /// Run a whole clock period, the memory answering after each edge
+        fn cycle(&mut self) {
+            for clock in [true, false] {
+                self.cpu.clk.state = State::from(clock);
+                self.cpu.run(Duration::from_nanos(500));
+                let addr = self.cpu.get_addr() as usize;
+                match self.cpu.d0.pin_type {
+                    // the upper half is read-only, like a ROM
+                    PinType::Output if addr < 0x8000 => self.memory[addr] = self.cpu.get_data(),
+                    PinType::Input => {
+                        let data = self.memory[addr] as usize;
+                        Pin::write(
+                            &mut [
+                                &mut self.cpu.d0,
+                                &mut self.cpu.d1,
+                                &mut self.cpu.d2,
+                                &mut self.cpu.d3,
+                                &mut self.cpu.d4,
+                                &mut self.cpu.d5,
+                                &mut self.cpu.d6,
+                                &mut self.cpu.d7,
+                            ],
+                            data,
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        /// Run until the CPU fetches the opcode at the given address
+        fn run_to(&mut self, pc: u16) {
+            for _ in 0..1000 {
+                self.cycle();
+                if matches!(self.cpu.state, CpuState::Fetch) && self.cpu.get_addr() == pc {
+                    return;
+                }
+            }
+            panic!("the CPU never reached {pc:04X}");
+        }
+    }
+
+    #[test]
+    fn reset_vector_is_little_endian() {
+        let mut machine = Machine::new(Nes6502::build(), &[]);
+        machine.memory[0xFFFC] = 0x34;
+        machine.memory[0xFFFD] = 0x92;
+        machine.run_to(0x9234);
+    }
+
+    #[test]
+    fn indirect_addressing_modes() {
+        let mut machine = Machine::new(
+            Nes6502::build(),
+            &[
+                0xA2, 0x04, // LDX #$04
+                0xA0, 0x02, // LDY #$02
+                0xA1, 0x10, // LDA ($10,X)
+                0x91, 0x20, // STA ($20),Y
+                0xB1, 0x22, // LDA ($22),Y
+            ],
+        );
+        machine.memory[0x14] = 0x00;
+        machine.memory[0x15] = 0x03;
+        machine.memory[0x0300] = 0x42;
+        machine.memory[0x20] = 0x00;
+        machine.memory[0x21] = 0x04;
+        machine.memory[0x22] = 0x10;
+        machine.memory[0x23] = 0x04;
+        machine.memory[0x0412] = 0x99;
+
+        machine.run_to(0x800A);
+
+        assert_eq!(machine.memory[0x0402], 0x42);
+        assert_eq!(*machine.cpu.registers.a, 0x99);
+    }
+
+    #[test]
+    fn illegal_indirect_opcodes_dont_panic() {
+        let mut machine = Machine::new(
+            Nes6502::build().with_illegal_opcodes(true),
+            &[
+                0xA0, 0x01, // LDY #$01
+                0xB3, 0x10, // LAX ($10),Y
+                0x03, 0x10, // SLO ($10,X)
+            ],
+        );
+        machine.memory[0x10] = 0x00;
+        machine.memory[0x11] = 0x02;
+        machine.memory[0x0201] = 0x37;
+        machine.memory[0x47] = 0x00;
+        machine.memory[0x48] = 0x05;
+        machine.memory[0x0500] = 0x81;
+
+        machine.run_to(0x8006);
+
+        assert_eq!(*machine.cpu.registers.x, 0x37);
+        assert_eq!(machine.memory[0x0500], 0x02);
+        // SLO ORs the shifted byte into A, which LAX loaded
+        assert_eq!(*machine.cpu.registers.a, 0x37);
+        assert!(machine.cpu.registers.p.contains(StatusRegister::C));
+    }
+
+    #[test]
+    fn rti_restores_the_status_and_the_pc() {
+        let mut machine = Machine::new(
+            Nes6502::build(),
+            &[
+                0xA9, 0x90, // LDA #$90
+                0x48, // PHA
+                0xA9, 0x12, // LDA #$12
+                0x48, // PHA
+                0xA9, 0xD3, // LDA #$D3
+                0x48, // PHA
+                0x40, // RTI
+            ],
+        );
+
+        // the next opcode is fetched from the pulled address
+        machine.run_to(0x9012);
+
+        // B is dropped and the unused bit is set
+        assert_eq!(machine.cpu.registers.p.bits(), 0xE3);
+    }
+
+    #[test]
+    fn reset_brings_back_the_power_on_state() {
+        let mut machine = Machine::new(
+            Nes6502::build(),
+            &[
+                0xA9, 0x42, // LDA #$42
+                0xA2, 0x17, // LDX #$17
+                0x48, // PHA
+                0x38, // SEC
+            ],
+        );
+        machine.run_to(0x8006);
+        assert_eq!(*machine.cpu.registers.a, 0x42);
+
+        machine.cpu.reset();
+
+        assert!(matches!(machine.cpu.state, CpuState::Reset));
+        assert_eq!(*machine.cpu.registers.a, 0);
+        assert_eq!(*machine.cpu.registers.x, 0);
+        assert_eq!(*machine.cpu.registers.y, 0);
+        assert_eq!(*machine.cpu.registers.s, 0xFD);
+        assert_eq!(*machine.cpu.registers.pc, 0xFFFC);
+        assert_eq!(machine.cpu.registers.p.bits(), 0x34);
+        // the program starts over from the reset vector
+        machine.run_to(0x8000);
+    }
+
+    #[test]
+    fn breakpoint_triggers_when_the_pc_reaches_it() {
+        let mut machine = Machine::new(
+            Nes6502::build(),
+            &[
+                0xEA, // NOP
+                0xEA, // NOP
+                0x85, 0x10, // STA $10
+                0xEA, // NOP
+            ],
+        );
+        machine.cpu.add_breakpoint(0x8004);
+        machine.cpu.add_watchpoint(0x0010);
+
+        machine.run_to(0x8002);
+        assert_eq!(machine.cpu.hit_breakpoint(), None);
+        assert_eq!(machine.cpu.hit_watchpoint(), None);
+        machine.run_to(0x8004);
+        assert_eq!(machine.cpu.hit_watchpoint(), Some(0x0010));
+        assert_eq!(machine.cpu.hit_breakpoint(), None);
+        // the breakpoint is flagged once the opcode at its address is fetched
+        machine.cycle();
+        assert_eq!(machine.cpu.hit_breakpoint(), Some(0x8004));
+
+        machine.cpu.clear_hits();
+        assert_eq!(machine.cpu.hit_breakpoint(), None);
+    }
+
+    #[test]
+    fn trace_records_the_executed_instructions() {
+        let mut machine = Machine::new(
+            Nes6502::build(),
+            &[
+                0xA9, 0x42, // LDA #$42
+                0xAA, // TAX
+                0xE8, // INX
+                0x85, 0x10, // STA $10
+                0xEA, // NOP
+            ],
+        );
+        machine.cpu.enable_trace();
+        machine.run_to(0x8007);
+
+        let trace = machine.cpu.take_trace();
+        let steps: Vec<_> = trace.iter().map(|entry| (entry.pc, entry.opcode)).collect();
+        assert_eq!(
+            steps,
+            [
+                (0x8000, Opcode::LDA(AddressingMode::Immediate(0x42))),
+                (0x8002, Opcode::TAX),
+                (0x8003, Opcode::INX),
+                (0x8004, Opcode::STA(AddressingMode::ZeroPage(0x10))),
+                (0x8006, Opcode::NOP),
+            ]
+        );
+        assert_eq!(*trace[2].registers.x, 0x43);
+        assert!(machine.cpu.take_trace().is_empty());
     }
 }
@@ -1,5 +1,25 @@
 use super::opcodes::{Opcode, ParseError};
 
+/// An element of a program given to `Assembler::assemble_items`
+#[derive(Debug, Clone)]
+pub enum Item {
+    /// An instruction
+    Op(Opcode),
+    /// Move the output position to the given offset in the image, padding with zeros if needed.
+    /// The bytes emitted after it can't overlap bytes that were already emitted.
+    Org(u16),
+    /// Raw bytes
+    Byte(Vec<u8>),
+    /// A little-endian 16-bit value, like an address or a vector
+    Word(u16),
+}
+
+impl From<Opcode> for Item {
+    fn from(value: Opcode) -> Self {
+        Item::Op(value)
+    }
+}
+
 pub struct Assembler;
 
 impl Assembler {
@@ -11,22 +31,90 @@ impl Assembler {
         Ok(payload)
     }
 
-    // pub fn disassemble(payload: &[u8]) -> Vec<(u16, Opcode)> {
-    //     let mut operations = vec![];
-    //     let mut state = MicrocodeState::Fetch;
-    //     let mut op_index = 0;
-    //     for (index, byte) in payload.iter().enumerate() {
-    //         state = state.advance(*byte);
-    //         if matches!(state, MicrocodeState::Execute(_, _, _, _, _)) {
-    //             operations.push((op_index, Operation::from(&state)));
-    //             state = MicrocodeState::Fetch;
-    //             op_index = index as u16 + 1
-    //         }
-    //     }
-    //     // process incomplete operation
-    //     operations.push((op_index, Operation::from(&state)));
-    //     operations
-    // }
+    /// Assemble a program along with its data and vectors into a single image.
+    /// Items are laid out one after another, starting at offset 0 unless moved with `Item::Org`.
+    /// Fails with `ParseError::OverlappingItems` when an item would overwrite emitted bytes.
+    pub fn assemble_items(items: &[Item]) -> Result<Vec<u8>, ParseError> {
+        let mut image = vec![];
+        // whether each byte of the image was emitted, or is padding
+        let mut emitted = vec![];
+        let mut position = 0;
+        for item in items.iter() {
+            let bytes = match item {
+                Item::Op(opcode) => Vec::<u8>::try_from(*opcode)?,
+                Item::Org(offset) => {
+                    position = *offset as usize;
+                    continue;
+                }
+                Item::Byte(bytes) => bytes.clone(),
+                Item::Word(word) => word.to_le_bytes().to_vec(),
+            };
+            let end = position + bytes.len();
+            if image.len() < end {
+                image.resize(end, 0);
+                emitted.resize(end, false);
+            }
+            if let Some(offset) = (position..end).find(|offset| emitted[*offset]) {
+                return Err(ParseError::OverlappingItems(format!(
+                    "{item:?} overwrites the byte already emitted at offset {offset:04X}"
+                )));
+            }
+            image[position..end].copy_from_slice(&bytes);
+            emitted[position..end].fill(true);
+            position = end;
+        }
+        Ok(image)
+    }
+
+    /// Assemble a program, then disassemble it to make sure that every opcode decodes back
+    /// to itself
+    pub fn assemble_checked(code: &[Opcode]) -> Result<Vec<u8>, ParseError> {
+        let payload = Self::assemble(code)?;
+        let decoded = Self::disassemble(&payload);
+        for (index, opcode) in code.iter().enumerate() {
+            match decoded.get(index) {
+                Some((_, decoded)) if decoded == opcode => {}
+                Some((offset, decoded)) => {
+                    return Err(ParseError::RoundTripMismatch(format!(
+                        "{opcode:?} decodes back as {decoded:?} at offset {offset:04X}"
+                    )))
+                }
+                None => {
+                    return Err(ParseError::RoundTripMismatch(format!(
+                        "{opcode:?} is missing from the disassembled program"
+                    )))
+                }
+            }
+        }
+        if let Some((offset, decoded)) = decoded.get(code.len()) {
+            return Err(ParseError::RoundTripMismatch(format!(
+                "unexpected {decoded:?} at offset {offset:04X}"
+            )));
+        }
+        Ok(payload)
+    }
+
+    /// Decode a program into opcodes along with their offset, illegal opcodes included.
+    /// The arguments of an incomplete trailing instruction are left to zero.
+    pub fn disassemble(payload: &[u8]) -> Vec<(u16, Opcode)> {
+        let mut operations = vec![];
+        let mut bytes = payload.iter().enumerate();
+        while let Some((offset, byte)) = bytes.next() {
+            let mut opcode = Opcode::decode(*byte, true);
+            if opcode.require_arg1() {
+                if let Some((_, arg)) = bytes.next() {
+                    opcode.set_arg1(*arg);
+                }
+                if opcode.require_arg2() {
+                    if let Some((_, arg)) = bytes.next() {
+                        opcode.set_arg2(*arg);
+                    }
+                }
+            }
+            operations.push((offset as u16, opcode));
+        }
+        operations
+    }
 
     // pub fn from_code(code: &str) -> (Vec<Opcode>, Vec<(usize, OperationParseError)>) {
     //     let mut operations = vec![];
@@ -49,3 +137,221 @@ impl Assembler {
     //     (operations, errors)
     // }
 }
+
+/// Assemble a 6502 program written with the usual mnemonics, instructions being separated by `;`.
+/// Expands to a call to `Assembler::assemble`, so invalid addressing modes are reported as a
+/// `ParseError` when the program is assembled.
+///
+/// Supported operands:
+/// - `ASL A`: accumulator
+/// - `LDA #0x5A`: immediate
+/// - `LDA 0x10`, `LDA 0x10, X`, `LDX 0x10, Y`: zero page when the address fits in a byte,
+///   absolute otherwise (always absolute for `JMP` and `JSR`)
+/// - `JMP (0x1234)`, `LDA (0x10, X)`, `LDA (0x10), Y`: indirect
+/// - `BNE -5`: relative offset for branches
+///
+/// Each instruction is one recursion step of the macro, long programs may need to raise the
+/// `recursion_limit`.
+///
+/// ```
+/// use virt_ic::{
+///     asm6502,
+///     chip::cpu::nes6502::{AddressingMode, Assembler, Opcode},
+/// };
+///
+/// let program = asm6502! {
+///     CLC;
+///     LDA #0x5A;
+///     STA 0x00, X;
+///     BPL -5;
+/// }
+/// .unwrap();
+/// let expected = Assembler::assemble(&[
+///     Opcode::CLC,
+///     Opcode::LDA(AddressingMode::Immediate(0x5A)),
+///     Opcode::STA(AddressingMode::ZeroPageIndexedX(0x00)),
+///     Opcode::BPL(-5),
+/// ])
+/// .unwrap();
+/// assert_eq!(program, expected);
+/// ```
+#[macro_export]
+macro_rules! asm6502 {
+    (@ops [$($ops:expr),*]) => {
+        $crate::chip::cpu::nes6502::Assembler::assemble(&[$($ops),*])
+    };
+    (@ops [$($ops:expr),*] ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops),*] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::chip::cpu::nes6502::Opcode::$op] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident A ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::chip::cpu::nes6502::AddressingMode::Implicit
+        )] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident # $value:expr ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::chip::cpu::nes6502::AddressingMode::Immediate($value)
+        )] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident ($value:expr, X) ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::chip::cpu::nes6502::AddressingMode::IndexedIndirect($value)
+        )] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident ($value:expr), Y ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::chip::cpu::nes6502::AddressingMode::IndirectIndexed($value)
+        )] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident ($value:expr) ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::chip::cpu::nes6502::AddressingMode::Indirect($value)
+        )] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident $value:expr, X ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::asm6502!(@address $value, ZeroPageIndexedX, AbsoluteIndexedX)
+        )] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident $value:expr, Y ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::asm6502!(@indexed_y $op $value)] $($rest)*)
+    };
+    (@ops [$($ops:expr),*] $op:ident $value:expr ; $($rest:tt)*) => {
+        $crate::asm6502!(@ops [$($ops,)* $crate::asm6502!(@direct $op $value)] $($rest)*)
+    };
+    (@indexed_y $op:ident $value:expr) => {
+        $crate::asm6502!(@indexed_y_zero_page $op $value)
+    };
+    (@indexed_y_zero_page LDX $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::LDX(
+            $crate::asm6502!(@address $value, ZeroPageIndexedY, AbsoluteIndexedY)
+        )
+    };
+    (@indexed_y_zero_page LAX $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::LAX(
+            $crate::asm6502!(@address $value, ZeroPageIndexedY, AbsoluteIndexedY)
+        )
+    };
+    (@indexed_y_zero_page STX $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::STX(
+            $crate::chip::cpu::nes6502::AddressingMode::ZeroPageIndexedY($value)
+        )
+    };
+    (@indexed_y_zero_page SAX $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::SAX(
+            $crate::chip::cpu::nes6502::AddressingMode::ZeroPageIndexedY($value)
+        )
+    };
+    (@indexed_y_zero_page $op:ident $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::chip::cpu::nes6502::AddressingMode::AbsoluteIndexedY($value)
+        )
+    };
+    (@direct BPL $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BPL($value) };
+    (@direct BMI $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BMI($value) };
+    (@direct BVC $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BVC($value) };
+    (@direct BVS $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BVS($value) };
+    (@direct BCC $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BCC($value) };
+    (@direct BCS $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BCS($value) };
+    (@direct BNE $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BNE($value) };
+    (@direct BEQ $value:expr) => { $crate::chip::cpu::nes6502::Opcode::BEQ($value) };
+    (@direct JMP $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::JMP(
+            $crate::chip::cpu::nes6502::AddressingMode::Absolute($value)
+        )
+    };
+    (@direct JSR $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::JSR(
+            $crate::chip::cpu::nes6502::AddressingMode::Absolute($value)
+        )
+    };
+    (@direct $op:ident $value:expr) => {
+        $crate::chip::cpu::nes6502::Opcode::$op(
+            $crate::asm6502!(@address $value, ZeroPage, Absolute)
+        )
+    };
+    (@address $value:expr, $zero_page:ident, $absolute:ident) => {{
+        let address: u16 = $value;
+        if address <= 0xFF {
+            $crate::chip::cpu::nes6502::AddressingMode::$zero_page(address as u8)
+        } else {
+            $crate::chip::cpu::nes6502::AddressingMode::$absolute(address)
+        }
+    }};
+    ($($code:tt)*) => {
+        $crate::asm6502!(@ops [] $($code)* ;)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Assembler, Item};
+    use crate::chip::cpu::nes6502::{opcodes::ParseError, AddressingMode, Opcode};
+
+    #[test]
+    fn code_and_reset_vector_in_one_pass() {
+        let image = Assembler::assemble_items(&[
+            Opcode::LDA(AddressingMode::Immediate(0x5A)).into(),
+            Opcode::BMI(-2).into(),
+            Item::Org(0x10),
+            Item::Byte(vec![0xDE, 0xAD]),
+            Item::Org(0xFC),
+            Item::Word(0x8000),
+        ])
+        .unwrap();
+        assert_eq!(image.len(), 0xFE);
+        assert_eq!(image[..4], [0xA9, 0x5A, 0x30, 0xFE]);
+        assert_eq!(image[0x10..0x12], [0xDE, 0xAD]);
+        assert_eq!(image[0xFC..], [0x00, 0x80]);
+    }
+
+    #[test]
+    fn org_can_fill_padding_out_of_order() {
+        let image =
+            Assembler::assemble_items(&[Item::Org(0x04), Item::Word(0x1234), Item::Org(0x00)])
+                .unwrap();
+        assert_eq!(image, [0x00, 0x00, 0x00, 0x00, 0x34, 0x12]);
+        let image = Assembler::assemble_items(&[
+            Item::Org(0x04),
+            Item::Word(0x1234),
+            Item::Org(0x02),
+            Item::Word(0x5678),
+        ])
+        .unwrap();
+        assert_eq!(image, [0x00, 0x00, 0x78, 0x56, 0x34, 0x12]);
+    }
+
+    #[test]
+    fn org_over_emitted_bytes_is_rejected() {
+        let result = Assembler::assemble_items(&[
+            Item::Byte(vec![0x01, 0x02, 0x03]),
+            Item::Org(0x02),
+            Item::Word(0xFFFF),
+        ]);
+        assert!(matches!(result, Err(ParseError::OverlappingItems(_))));
+    }
+
+    #[test]
+    fn checked_assembly_of_every_addressing_mode() {
+        let program = [
+            Opcode::LDA(AddressingMode::Immediate(0x10)),
+            Opcode::LDA(AddressingMode::ZeroPage(0x20)),
+            Opcode::LDA(AddressingMode::ZeroPageIndexedX(0x21)),
+            Opcode::LDX(AddressingMode::ZeroPageIndexedY(0x22)),
+            Opcode::LDA(AddressingMode::Absolute(0x1234)),
+            Opcode::LDA(AddressingMode::AbsoluteIndexedX(0x1235)),
+            Opcode::LDA(AddressingMode::AbsoluteIndexedY(0x1236)),
+            Opcode::LDA(AddressingMode::IndexedIndirect(0x30)),
+            Opcode::STA(AddressingMode::IndirectIndexed(0x31)),
+            Opcode::JMP(AddressingMode::Indirect(0x4000)),
+            Opcode::ASL(AddressingMode::Implicit),
+            Opcode::BNE(-4),
+            Opcode::TAX,
+        ];
+        let payload = Assembler::assemble_checked(&program).unwrap();
+        assert_eq!(payload, Assembler::assemble(&program).unwrap());
+    }
+}
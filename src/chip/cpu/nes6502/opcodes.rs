@@ -2,7 +2,7 @@ use crate::chip::PinType;
 
 use super::Nes6502;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AddressingMode {
     Implicit,
@@ -130,14 +130,42 @@ impl AddressingMode {
             AddressingMode::AbsoluteIndexedY(a) => {
                 AddressingMode::Absolute(a + *cpu.registers.y as u16)
             }
-            AddressingMode::IndexedIndirect(_) => todo!(),
-            AddressingMode::IndirectIndexed(_) => todo!(),
+            AddressingMode::IndexedIndirect(z) => {
+                // the pointer is read from the zero page, wrapping around it
+                let pointer = *(cpu.registers.x + *z);
+                if step == 0 {
+                    cpu.set_addr(pointer as u16);
+                    cpu.set_data_type(PinType::Input);
+                    *self
+                } else if step == 1 {
+                    cpu.buffer = cpu.get_data() as u16;
+                    cpu.set_addr(pointer.wrapping_add(1) as u16);
+                    *self
+                } else {
+                    cpu.buffer = cpu.buffer.wrapping_add((cpu.get_data() as u16) << 8);
+                    AddressingMode::Absolute(cpu.buffer)
+                }
+            }
+            AddressingMode::IndirectIndexed(z) => {
+                if step == 0 {
+                    cpu.set_addr(*z as u16);
+                    cpu.set_data_type(PinType::Input);
+                    *self
+                } else if step == 1 {
+                    cpu.buffer = cpu.get_data() as u16;
+                    cpu.set_addr(z.wrapping_add(1) as u16);
+                    *self
+                } else {
+                    cpu.buffer = cpu.buffer.wrapping_add((cpu.get_data() as u16) << 8);
+                    AddressingMode::Absolute(cpu.buffer.wrapping_add(*cpu.registers.y as u16))
+                }
+            }
             _ => *self,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Opcode {
     ADC(AddressingMode),
@@ -196,6 +224,17 @@ pub enum Opcode {
     PLP,
     STX(AddressingMode),
     STY(AddressingMode),
+    // stable illegal opcodes
+    LAX(AddressingMode),
+    SAX(AddressingMode),
+    DCP(AddressingMode),
+    ISC(AddressingMode),
+    SLO(AddressingMode),
+    RLA(AddressingMode),
+    SRE(AddressingMode),
+    RRA(AddressingMode),
+    /// Any other illegal opcode, executed as a NOP
+    Illegal(u8),
 }
 
 impl Opcode {
@@ -223,7 +262,15 @@ impl Opcode {
             | Opcode::ROL(a)
             | Opcode::ROR(a)
             | Opcode::STX(a)
-            | Opcode::STY(a) => a.set_arg1(arg),
+            | Opcode::STY(a)
+            | Opcode::LAX(a)
+            | Opcode::SAX(a)
+            | Opcode::DCP(a)
+            | Opcode::ISC(a)
+            | Opcode::SLO(a)
+            | Opcode::RLA(a)
+            | Opcode::SRE(a)
+            | Opcode::RRA(a) => a.set_arg1(arg),
             Opcode::BPL(a)
             | Opcode::BMI(a)
             | Opcode::BVC(a)
@@ -233,6 +280,7 @@ impl Opcode {
             | Opcode::BNE(a)
             | Opcode::BEQ(a) => *a = arg as i8,
             Opcode::NOP
+            | Opcode::Illegal(_)
             | Opcode::CLC
             | Opcode::SEC
             | Opcode::CLI
@@ -281,8 +329,17 @@ impl Opcode {
             | Opcode::ROL(a)
             | Opcode::ROR(a)
             | Opcode::STX(a)
-            | Opcode::STY(a) => a.set_arg2(arg),
+            | Opcode::STY(a)
+            | Opcode::LAX(a)
+            | Opcode::SAX(a)
+            | Opcode::DCP(a)
+            | Opcode::ISC(a)
+            | Opcode::SLO(a)
+            | Opcode::RLA(a)
+            | Opcode::SRE(a)
+            | Opcode::RRA(a) => a.set_arg2(arg),
             Opcode::NOP
+            | Opcode::Illegal(_)
             | Opcode::CLC
             | Opcode::SEC
             | Opcode::CLI
@@ -340,7 +397,15 @@ impl Opcode {
             | Opcode::ROL(a)
             | Opcode::ROR(a)
             | Opcode::STX(a)
-            | Opcode::STY(a) => a.require_arg1(),
+            | Opcode::STY(a)
+            | Opcode::LAX(a)
+            | Opcode::SAX(a)
+            | Opcode::DCP(a)
+            | Opcode::ISC(a)
+            | Opcode::SLO(a)
+            | Opcode::RLA(a)
+            | Opcode::SRE(a)
+            | Opcode::RRA(a) => a.require_arg1(),
             Opcode::BPL(_)
             | Opcode::BMI(_)
             | Opcode::BVC(_)
@@ -350,6 +415,7 @@ impl Opcode {
             | Opcode::BNE(_)
             | Opcode::BEQ(_) => true,
             Opcode::NOP
+            | Opcode::Illegal(_)
             | Opcode::CLC
             | Opcode::SEC
             | Opcode::CLI
@@ -398,8 +464,17 @@ impl Opcode {
             | Opcode::ROL(a)
             | Opcode::ROR(a)
             | Opcode::STX(a)
-            | Opcode::STY(a) => a.require_arg2(),
+            | Opcode::STY(a)
+            | Opcode::LAX(a)
+            | Opcode::SAX(a)
+            | Opcode::DCP(a)
+            | Opcode::ISC(a)
+            | Opcode::SLO(a)
+            | Opcode::RLA(a)
+            | Opcode::SRE(a)
+            | Opcode::RRA(a) => a.require_arg2(),
             Opcode::NOP
+            | Opcode::Illegal(_)
             | Opcode::CLC
             | Opcode::SEC
             | Opcode::CLI
@@ -456,8 +531,17 @@ impl Opcode {
             | Opcode::ROL(a)
             | Opcode::ROR(a)
             | Opcode::STX(a)
-            | Opcode::STY(a) => a.need_compute(),
+            | Opcode::STY(a)
+            | Opcode::LAX(a)
+            | Opcode::SAX(a)
+            | Opcode::DCP(a)
+            | Opcode::ISC(a)
+            | Opcode::SLO(a)
+            | Opcode::RLA(a)
+            | Opcode::SRE(a)
+            | Opcode::RRA(a) => a.need_compute(),
             Opcode::NOP
+            | Opcode::Illegal(_)
             | Opcode::CLC
             | Opcode::SEC
             | Opcode::CLI
@@ -514,8 +598,17 @@ impl Opcode {
             | Opcode::ROL(a)
             | Opcode::ROR(a)
             | Opcode::STX(a)
-            | Opcode::STY(a) => *a = a.compute(cpu, step),
+            | Opcode::STY(a)
+            | Opcode::LAX(a)
+            | Opcode::SAX(a)
+            | Opcode::DCP(a)
+            | Opcode::ISC(a)
+            | Opcode::SLO(a)
+            | Opcode::RLA(a)
+            | Opcode::SRE(a)
+            | Opcode::RRA(a) => *a = a.compute(cpu, step),
             Opcode::NOP
+            | Opcode::Illegal(_)
             | Opcode::CLC
             | Opcode::SEC
             | Opcode::CLI
@@ -738,6 +831,84 @@ impl From<u8> for Opcode {
     }
 }
 
+impl Opcode {
+    /// Decode an opcode, including the stable illegal ones when `illegal_opcodes` is set.
+    /// Other unknown opcodes are then decoded as `Opcode::Illegal` instead of `Opcode::NOP`.
+    pub fn decode(value: u8, illegal_opcodes: bool) -> Self {
+        if !illegal_opcodes {
+            return Opcode::from(value);
+        }
+        match value {
+            0xA3 => Opcode::LAX(AddressingMode::IndexedIndirect(0)),
+            0xA7 => Opcode::LAX(AddressingMode::ZeroPage(0)),
+            0xAF => Opcode::LAX(AddressingMode::Absolute(0)),
+            0xB3 => Opcode::LAX(AddressingMode::IndirectIndexed(0)),
+            0xB7 => Opcode::LAX(AddressingMode::ZeroPageIndexedY(0)),
+            0xBF => Opcode::LAX(AddressingMode::AbsoluteIndexedY(0)),
+
+            0x83 => Opcode::SAX(AddressingMode::IndexedIndirect(0)),
+            0x87 => Opcode::SAX(AddressingMode::ZeroPage(0)),
+            0x8F => Opcode::SAX(AddressingMode::Absolute(0)),
+            0x97 => Opcode::SAX(AddressingMode::ZeroPageIndexedY(0)),
+
+            0xC3 => Opcode::DCP(AddressingMode::IndexedIndirect(0)),
+            0xC7 => Opcode::DCP(AddressingMode::ZeroPage(0)),
+            0xCF => Opcode::DCP(AddressingMode::Absolute(0)),
+            0xD3 => Opcode::DCP(AddressingMode::IndirectIndexed(0)),
+            0xD7 => Opcode::DCP(AddressingMode::ZeroPageIndexedX(0)),
+            0xDB => Opcode::DCP(AddressingMode::AbsoluteIndexedY(0)),
+            0xDF => Opcode::DCP(AddressingMode::AbsoluteIndexedX(0)),
+
+            0xE3 => Opcode::ISC(AddressingMode::IndexedIndirect(0)),
+            0xE7 => Opcode::ISC(AddressingMode::ZeroPage(0)),
+            0xEF => Opcode::ISC(AddressingMode::Absolute(0)),
+            0xF3 => Opcode::ISC(AddressingMode::IndirectIndexed(0)),
+            0xF7 => Opcode::ISC(AddressingMode::ZeroPageIndexedX(0)),
+            0xFB => Opcode::ISC(AddressingMode::AbsoluteIndexedY(0)),
+            0xFF => Opcode::ISC(AddressingMode::AbsoluteIndexedX(0)),
+
+            0x03 => Opcode::SLO(AddressingMode::IndexedIndirect(0)),
+            0x07 => Opcode::SLO(AddressingMode::ZeroPage(0)),
+            0x0F => Opcode::SLO(AddressingMode::Absolute(0)),
+            0x13 => Opcode::SLO(AddressingMode::IndirectIndexed(0)),
+            0x17 => Opcode::SLO(AddressingMode::ZeroPageIndexedX(0)),
+            0x1B => Opcode::SLO(AddressingMode::AbsoluteIndexedY(0)),
+            0x1F => Opcode::SLO(AddressingMode::AbsoluteIndexedX(0)),
+
+            0x23 => Opcode::RLA(AddressingMode::IndexedIndirect(0)),
+            0x27 => Opcode::RLA(AddressingMode::ZeroPage(0)),
+            0x2F => Opcode::RLA(AddressingMode::Absolute(0)),
+            0x33 => Opcode::RLA(AddressingMode::IndirectIndexed(0)),
+            0x37 => Opcode::RLA(AddressingMode::ZeroPageIndexedX(0)),
+            0x3B => Opcode::RLA(AddressingMode::AbsoluteIndexedY(0)),
+            0x3F => Opcode::RLA(AddressingMode::AbsoluteIndexedX(0)),
+
+            0x43 => Opcode::SRE(AddressingMode::IndexedIndirect(0)),
+            0x47 => Opcode::SRE(AddressingMode::ZeroPage(0)),
+            0x4F => Opcode::SRE(AddressingMode::Absolute(0)),
+            0x53 => Opcode::SRE(AddressingMode::IndirectIndexed(0)),
+            0x57 => Opcode::SRE(AddressingMode::ZeroPageIndexedX(0)),
+            0x5B => Opcode::SRE(AddressingMode::AbsoluteIndexedY(0)),
+            0x5F => Opcode::SRE(AddressingMode::AbsoluteIndexedX(0)),
+
+            0x63 => Opcode::RRA(AddressingMode::IndexedIndirect(0)),
+            0x67 => Opcode::RRA(AddressingMode::ZeroPage(0)),
+            0x6F => Opcode::RRA(AddressingMode::Absolute(0)),
+            0x73 => Opcode::RRA(AddressingMode::IndirectIndexed(0)),
+            0x77 => Opcode::RRA(AddressingMode::ZeroPageIndexedX(0)),
+            0x7B => Opcode::RRA(AddressingMode::AbsoluteIndexedY(0)),
+            0x7F => Opcode::RRA(AddressingMode::AbsoluteIndexedX(0)),
+
+            // CLD and SED are legal but not implemented
+            0xEA | 0xD8 | 0xF8 => Opcode::NOP,
+            _ => match Opcode::from(value) {
+                Opcode::NOP => Opcode::Illegal(value),
+                opcode => opcode,
+            },
+        }
+    }
+}
+
 fn opcode_with_u16(opcode: u8, arg: u16) -> Vec<u8> {
     vec![opcode, (arg & 0xFF) as u8, (arg >> 8) as u8]
 }
@@ -746,6 +917,10 @@ fn opcode_with_u16(opcode: u8, arg: u16) -> Vec<u8> {
 pub enum ParseError {
     InvalidOpcode(String),
     InvalidAddressMode(String),
+    /// The assembled bytes don't decode back to the original program
+    RoundTripMismatch(String),
+    /// An `Item::Org` moved back over bytes that were already emitted
+    OverlappingItems(String),
 }
 
 impl TryFrom<Opcode> for Vec<u8> {
@@ -1000,6 +1175,92 @@ impl TryFrom<Opcode> for Vec<u8> {
             Opcode::STY(a) => Err(ParseError::InvalidAddressMode(format!(
                 "Invalid Addressing mode {a:?} for STY"
             ))),
+
+            Opcode::LAX(AddressingMode::IndexedIndirect(a)) => Ok(vec![0xA3, a]),
+            Opcode::LAX(AddressingMode::ZeroPage(a)) => Ok(vec![0xA7, a]),
+            Opcode::LAX(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0xAF, a)),
+            Opcode::LAX(AddressingMode::IndirectIndexed(a)) => Ok(vec![0xB3, a]),
+            Opcode::LAX(AddressingMode::ZeroPageIndexedY(a)) => Ok(vec![0xB7, a]),
+            Opcode::LAX(AddressingMode::AbsoluteIndexedY(a)) => Ok(opcode_with_u16(0xBF, a)),
+            Opcode::LAX(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for LAX"
+            ))),
+
+            Opcode::SAX(AddressingMode::IndexedIndirect(a)) => Ok(vec![0x83, a]),
+            Opcode::SAX(AddressingMode::ZeroPage(a)) => Ok(vec![0x87, a]),
+            Opcode::SAX(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0x8F, a)),
+            Opcode::SAX(AddressingMode::ZeroPageIndexedY(a)) => Ok(vec![0x97, a]),
+            Opcode::SAX(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for SAX"
+            ))),
+
+            Opcode::DCP(AddressingMode::IndexedIndirect(a)) => Ok(vec![0xC3, a]),
+            Opcode::DCP(AddressingMode::ZeroPage(a)) => Ok(vec![0xC7, a]),
+            Opcode::DCP(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0xCF, a)),
+            Opcode::DCP(AddressingMode::IndirectIndexed(a)) => Ok(vec![0xD3, a]),
+            Opcode::DCP(AddressingMode::ZeroPageIndexedX(a)) => Ok(vec![0xD7, a]),
+            Opcode::DCP(AddressingMode::AbsoluteIndexedY(a)) => Ok(opcode_with_u16(0xDB, a)),
+            Opcode::DCP(AddressingMode::AbsoluteIndexedX(a)) => Ok(opcode_with_u16(0xDF, a)),
+            Opcode::DCP(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for DCP"
+            ))),
+
+            Opcode::ISC(AddressingMode::IndexedIndirect(a)) => Ok(vec![0xE3, a]),
+            Opcode::ISC(AddressingMode::ZeroPage(a)) => Ok(vec![0xE7, a]),
+            Opcode::ISC(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0xEF, a)),
+            Opcode::ISC(AddressingMode::IndirectIndexed(a)) => Ok(vec![0xF3, a]),
+            Opcode::ISC(AddressingMode::ZeroPageIndexedX(a)) => Ok(vec![0xF7, a]),
+            Opcode::ISC(AddressingMode::AbsoluteIndexedY(a)) => Ok(opcode_with_u16(0xFB, a)),
+            Opcode::ISC(AddressingMode::AbsoluteIndexedX(a)) => Ok(opcode_with_u16(0xFF, a)),
+            Opcode::ISC(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for ISC"
+            ))),
+
+            Opcode::SLO(AddressingMode::IndexedIndirect(a)) => Ok(vec![0x03, a]),
+            Opcode::SLO(AddressingMode::ZeroPage(a)) => Ok(vec![0x07, a]),
+            Opcode::SLO(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0x0F, a)),
+            Opcode::SLO(AddressingMode::IndirectIndexed(a)) => Ok(vec![0x13, a]),
+            Opcode::SLO(AddressingMode::ZeroPageIndexedX(a)) => Ok(vec![0x17, a]),
+            Opcode::SLO(AddressingMode::AbsoluteIndexedY(a)) => Ok(opcode_with_u16(0x1B, a)),
+            Opcode::SLO(AddressingMode::AbsoluteIndexedX(a)) => Ok(opcode_with_u16(0x1F, a)),
+            Opcode::SLO(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for SLO"
+            ))),
+
+            Opcode::RLA(AddressingMode::IndexedIndirect(a)) => Ok(vec![0x23, a]),
+            Opcode::RLA(AddressingMode::ZeroPage(a)) => Ok(vec![0x27, a]),
+            Opcode::RLA(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0x2F, a)),
+            Opcode::RLA(AddressingMode::IndirectIndexed(a)) => Ok(vec![0x33, a]),
+            Opcode::RLA(AddressingMode::ZeroPageIndexedX(a)) => Ok(vec![0x37, a]),
+            Opcode::RLA(AddressingMode::AbsoluteIndexedY(a)) => Ok(opcode_with_u16(0x3B, a)),
+            Opcode::RLA(AddressingMode::AbsoluteIndexedX(a)) => Ok(opcode_with_u16(0x3F, a)),
+            Opcode::RLA(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for RLA"
+            ))),
+
+            Opcode::SRE(AddressingMode::IndexedIndirect(a)) => Ok(vec![0x43, a]),
+            Opcode::SRE(AddressingMode::ZeroPage(a)) => Ok(vec![0x47, a]),
+            Opcode::SRE(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0x4F, a)),
+            Opcode::SRE(AddressingMode::IndirectIndexed(a)) => Ok(vec![0x53, a]),
+            Opcode::SRE(AddressingMode::ZeroPageIndexedX(a)) => Ok(vec![0x57, a]),
+            Opcode::SRE(AddressingMode::AbsoluteIndexedY(a)) => Ok(opcode_with_u16(0x5B, a)),
+            Opcode::SRE(AddressingMode::AbsoluteIndexedX(a)) => Ok(opcode_with_u16(0x5F, a)),
+            Opcode::SRE(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for SRE"
+            ))),
+
+            Opcode::RRA(AddressingMode::IndexedIndirect(a)) => Ok(vec![0x63, a]),
+            Opcode::RRA(AddressingMode::ZeroPage(a)) => Ok(vec![0x67, a]),
+            Opcode::RRA(AddressingMode::Absolute(a)) => Ok(opcode_with_u16(0x6F, a)),
+            Opcode::RRA(AddressingMode::IndirectIndexed(a)) => Ok(vec![0x73, a]),
+            Opcode::RRA(AddressingMode::ZeroPageIndexedX(a)) => Ok(vec![0x77, a]),
+            Opcode::RRA(AddressingMode::AbsoluteIndexedY(a)) => Ok(opcode_with_u16(0x7B, a)),
+            Opcode::RRA(AddressingMode::AbsoluteIndexedX(a)) => Ok(opcode_with_u16(0x7F, a)),
+            Opcode::RRA(a) => Err(ParseError::InvalidAddressMode(format!(
+                "Invalid Addressing mode {a:?} for RRA"
+            ))),
+
+            Opcode::Illegal(opcode) => Ok(vec![opcode]),
         }
     }
 }
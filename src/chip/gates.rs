@@ -3,114 +3,342 @@ pub mod nand;
 pub mod nor;
 pub mod or;
 
-use std::time::Duration;
+use std::{collections::VecDeque, time::Duration};
 
 pub use and::*;
 pub use nand::*;
 pub use nor::*;
 pub use or::*;
 
-use crate::{generate_chip, State};
-
-use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
-
-/// # A chip with 6 bundled "NOT" gates
-///
-/// # Diagram
-/// ```
-///        ---__---
-///    A --|1   14|-- VCC
-///   !A --|2   13|-- D
-///    B --|3   12|-- !D
-///   !B --|4   11|-- E
-///    C --|5   10|-- !E
-///   !C --|6    9|-- F
-///  GND --|7    8|-- !F
-///        --------
-/// ```
+use crate::{define_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin};
+
+/// Voltage above which a gate input is considered high, when powered by a logic VCC
+const DEFAULT_THRESHOLD: f32 = 3.3;
+
+/// Delays the outputs of a gate chip, so that they only change once the delay has elapsed
 #[derive(Debug, Clone, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct NotGate {
-    pub vcc: Pin,
-    pub gnd: Pin,
-    pub a: Pin,
-    pub not_a: Pin,
-    pub b: Pin,
-    pub not_b: Pin,
-    pub c: Pin,
-    pub not_c: Pin,
-    pub d: Pin,
-    pub not_d: Pin,
-    pub e: Pin,
-    pub not_e: Pin,
-    pub f: Pin,
-    pub not_f: Pin,
+struct PropagationDelay {
+    delay: Duration,
+    time: Duration,
+    output: usize,
+    /// outputs waiting to be propagated, along with the time at which they will be
+    pending: VecDeque<(Duration, usize)>,
 }
+
+impl PropagationDelay {
+    /// Forget about the pending outputs
+    fn reset(&mut self) {
+        self.time = Duration::default();
+        self.output = 0;
+        self.pending.clear();
+    }
+
+    /// Feed the freshly computed outputs and get the ones that should be driven right now
+    fn propagate(&mut self, outputs: usize, tick_duration: Duration) -> usize {
+        if self.delay.is_zero() {
+            return outputs;
+        }
+        self.time += tick_duration;
+        let last = self.pending.back().map_or(self.output, |&(_, last)| last);
+        if outputs != last {
+            self.pending.push_back((self.time + self.delay, outputs));
+        }
+        while let Some(&(at, pending)) = self.pending.front() {
+            if at > self.time {
+                break;
+            }
+            self.output = pending;
+            self.pending.pop_front();
+        }
+        self.output
+    }
+}
+
+define_chip! {
+    /// # A chip with 6 bundled "NOT" gates
+    ///
+    /// # Diagram
+    /// ```txt
+    ///        ---__---
+    ///    A --|1   14|-- VCC
+    ///   !A --|2   13|-- D
+    ///    B --|3   12|-- !D
+    ///   !B --|4   11|-- E
+    ///    C --|5   10|-- !E
+    ///   !C --|6    9|-- F
+    ///  GND --|7    8|-- !F
+    ///        --------
+    /// ```
+    #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct NotGate => NotGatePin {
+        pins {
+            vcc: VCC = 14 => Input,
+            gnd: GND = 7 => Output,
+            a: A = 1 => Input,
+            not_a: NOT_A = 2 => Output,
+            b: B = 3 => Input,
+            not_b: NOT_B = 4 => Output,
+            c: C = 5 => Input,
+            not_c: NOT_C = 6 => Output,
+            d: D = 13 => Input,
+            not_d: NOT_D = 12 => Output,
+            e: E = 11 => Input,
+            not_e: NOT_E = 10 => Output,
+            f: F = 9 => Input,
+            not_f: NOT_F = 8 => Output,
+        }
+        state {
+            #[cfg_attr(feature = "serde", serde(default))]
+            threshold: Option<f32> = None,
+            #[cfg_attr(feature = "serde", serde(default))]
+            propagation: PropagationDelay = PropagationDelay::default(),
+        }
+    }
+}
+
 impl NotGate {
-    pub const VCC: PinId = 14;
-    pub const GND: PinId = 7;
-    pub const A: PinId = 1;
-    pub const NOT_A: PinId = 2;
-    pub const B: PinId = 3;
-    pub const NOT_B: PinId = 4;
-    pub const C: PinId = 5;
-    pub const NOT_C: PinId = 6;
-    pub const D: PinId = 13;
-    pub const NOT_D: PinId = 12;
-    pub const E: PinId = 11;
-    pub const NOT_E: PinId = 10;
-    pub const F: PinId = 9;
-    pub const NOT_F: PinId = 8;
+    /// Set the voltage above which an input is considered high, defaults to half of the VCC
+    /// voltage, or 3.3 when VCC is a logic state
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Delay the outputs by the given amount of simulated time, defaults to no delay
+    pub fn with_propagation_delay(mut self, delay: Duration) -> Self {
+        self.propagation.delay = delay;
+        self
+    }
 }
-impl ChipBuilder<ChipSet> for NotGate {
-    fn build() -> ChipSet {
-        ChipSet::NotGate(NotGate {
-            vcc: Pin::from(PinType::Input),
-            gnd: Pin::from(PinType::Output),
-            a: Pin::from(PinType::Input),
-            not_a: Pin::from(PinType::Output),
-            b: Pin::from(PinType::Input),
-            not_b: Pin::from(PinType::Output),
-            c: Pin::from(PinType::Input),
-            not_c: Pin::from(PinType::Output),
-            d: Pin::from(PinType::Input),
-            not_d: Pin::from(PinType::Output),
-            e: Pin::from(PinType::Input),
-            not_e: Pin::from(PinType::Output),
-            f: Pin::from(PinType::Input),
-            not_f: Pin::from(PinType::Output),
-        })
+
+impl ChipBuilder<NotGate> for NotGate {
+    fn build() -> NotGate {
+        NotGate::default()
     }
 }
 
-generate_chip!(
-    NotGate,
-    vcc: NotGate::VCC,
-    gnd: NotGate::GND,
-    a: NotGate::A,
-    not_a: NotGate::NOT_A,
-    b: NotGate::B,
-    not_b: NotGate::NOT_B,
-    c: NotGate::C,
-    not_c: NotGate::NOT_C,
-    d: NotGate::D,
-    not_d: NotGate::NOT_D,
-    e: NotGate::E,
-    not_e: NotGate::NOT_E,
-    f: NotGate::F,
-    not_f: NotGate::NOT_F
-);
+impl From<NotGate> for ChipSet {
+    fn from(value: NotGate) -> Self {
+        ChipSet::NotGate(value)
+    }
+}
 
 impl ChipRunner for NotGate {
-    fn run(&mut self, _: Duration) {
-        if self.vcc.state.as_logic(3.3) == State::High {
+    fn run(&mut self, tick_duration: Duration) {
+        let threshold = self
+            .threshold
+            .unwrap_or_else(|| self.vcc.state.rail_threshold(DEFAULT_THRESHOLD));
+        if self.vcc.state.as_logic(1.0) == State::High {
             self.gnd.state = State::Low;
-            self.not_a.state = State::from(!bool::from(self.a.state.as_logic(3.3)));
-            self.not_b.state = State::from(!bool::from(self.b.state.as_logic(3.3)));
-            self.not_c.state = State::from(!bool::from(self.c.state.as_logic(3.3)));
-            self.not_d.state = State::from(!bool::from(self.d.state.as_logic(3.3)));
-            self.not_e.state = State::from(!bool::from(self.e.state.as_logic(3.3)));
-            self.not_f.state = State::from(!bool::from(self.f.state.as_logic(3.3)));
+            self.not_a.state = State::from(!bool::from(self.a.state.as_logic(threshold)));
+            self.not_b.state = State::from(!bool::from(self.b.state.as_logic(threshold)));
+            self.not_c.state = State::from(!bool::from(self.c.state.as_logic(threshold)));
+            self.not_d.state = State::from(!bool::from(self.d.state.as_logic(threshold)));
+            self.not_e.state = State::from(!bool::from(self.e.state.as_logic(threshold)));
+            self.not_f.state = State::from(!bool::from(self.f.state.as_logic(threshold)));
+            let outputs = self.propagation.propagate(
+                Pin::read(&[
+                    &self.not_a,
+                    &self.not_b,
+                    &self.not_c,
+                    &self.not_d,
+                    &self.not_e,
+                    &self.not_f,
+                ]),
+                tick_duration,
+            );
+            Pin::write(
+                &mut [
+                    &mut self.not_a,
+                    &mut self.not_b,
+                    &mut self.not_c,
+                    &mut self.not_d,
+                    &mut self.not_e,
+                    &mut self.not_f,
+                ],
+                outputs,
+            );
+        }
+    }
+
+    fn reset(&mut self) {
+        self.propagation.reset();
+    }
+}
+
+#[cfg(test)]
+// the pins enum generated for the hand-written chip is named like the built-in ones
+#[allow(clippy::upper_case_acronyms)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{AndGate, NotGate};
+    use crate::{
+        chip::{Chip, ChipBuilder, ChipRunner, Pin, PinType},
+        generate_chip, State,
+    };
+
+    /// The NOT gate as it was written before `define_chip!`
+    #[derive(Debug, Clone)]
+    struct HandWrittenNotGate {
+        vcc: Pin,
+        gnd: Pin,
+        a: Pin,
+        not_a: Pin,
+        b: Pin,
+        not_b: Pin,
+        c: Pin,
+        not_c: Pin,
+        d: Pin,
+        not_d: Pin,
+        e: Pin,
+        not_e: Pin,
+        f: Pin,
+        not_f: Pin,
+    }
+
+    impl HandWrittenNotGate {
+        const VCC: usize = 14;
+        const GND: usize = 7;
+        const A: usize = 1;
+        const NOT_A: usize = 2;
+        const B: usize = 3;
+        const NOT_B: usize = 4;
+        const C: usize = 5;
+        const NOT_C: usize = 6;
+        const D: usize = 13;
+        const NOT_D: usize = 12;
+        const E: usize = 11;
+        const NOT_E: usize = 10;
+        const F: usize = 9;
+        const NOT_F: usize = 8;
+
+        fn build() -> Self {
+            HandWrittenNotGate {
+                vcc: Pin::from(PinType::Input),
+                gnd: Pin::from(PinType::Output),
+                a: Pin::from(PinType::Input),
+                not_a: Pin::from(PinType::Output),
+                b: Pin::from(PinType::Input),
+                not_b: Pin::from(PinType::Output),
+                c: Pin::from(PinType::Input),
+                not_c: Pin::from(PinType::Output),
+                d: Pin::from(PinType::Input),
+                not_d: Pin::from(PinType::Output),
+                e: Pin::from(PinType::Input),
+                not_e: Pin::from(PinType::Output),
+                f: Pin::from(PinType::Input),
+                not_f: Pin::from(PinType::Output),
+            }
+        }
+    }
+
+    generate_chip!(
+        HandWrittenNotGate => HandWrittenNotGatePin,
+        vcc: HandWrittenNotGate::VCC,
+        gnd: HandWrittenNotGate::GND,
+        a: HandWrittenNotGate::A,
+        not_a: HandWrittenNotGate::NOT_A,
+        b: HandWrittenNotGate::B,
+        not_b: HandWrittenNotGate::NOT_B,
+        c: HandWrittenNotGate::C,
+        not_c: HandWrittenNotGate::NOT_C,
+        d: HandWrittenNotGate::D,
+        not_d: HandWrittenNotGate::NOT_D,
+        e: HandWrittenNotGate::E,
+        not_e: HandWrittenNotGate::NOT_E,
+        f: HandWrittenNotGate::F,
+        not_f: HandWrittenNotGate::NOT_F
+    );
+
+    impl ChipRunner for HandWrittenNotGate {
+        fn run(&mut self, _: Duration) {}
+    }
+
+    /// Id, name and type of every pin, sorted by id
+    fn pin_map(chip: &impl Chip) -> Vec<(usize, Option<&'static str>, String)> {
+        let mut pins: Vec<_> = chip
+            .list_pins()
+            .into_iter()
+            .map(|(id, pin)| (id, chip.get_pin_name(id), format!("{:?}", pin.pin_type)))
+            .collect();
+        pins.sort_by_key(|(id, _, _)| *id);
+        pins
+    }
+
+    #[test]
+    fn define_chip_matches_generate_chip() {
+        let defined = NotGate::build();
+        let hand_written = HandWrittenNotGate::build();
+        assert_eq!(pin_map(&defined), pin_map(&hand_written));
+        assert_eq!(defined.pin_count(), hand_written.pin_count());
+        assert_eq!(usize::from(super::NotGatePin::NOT_D), 12);
+        assert_eq!(usize::from(HandWrittenNotGatePin::NOT_D), 12);
+    }
+
+    #[test]
+    fn analog_input_against_the_gate_threshold() {
+        let output = |mut gate: AndGate| {
+            gate.vcc.state = State::High;
+            gate.a.state = State::Analog(2.5);
+            gate.b.state = State::High;
+            gate.run(Duration::from_millis(1));
+            gate.ab.state
+        };
+        assert_eq!(output(AndGate::build()), State::Low);
+        assert_eq!(output(AndGate::build().with_threshold(2.0)), State::High);
+    }
+
+    #[test]
+    fn input_threshold_above_the_rail_keeps_the_gate_powered() {
+        let mut gate = AndGate::build().with_threshold(4.0);
+        gate.vcc.state = State::Analog(3.3);
+        gate.a.state = State::Analog(4.5);
+        gate.b.state = State::Analog(4.5);
+        gate.run(Duration::from_millis(1));
+        assert_eq!(gate.gnd.state, State::Low);
+        assert_eq!(gate.ab.state, State::High);
+
+        gate.b.state = State::Analog(3.3);
+        gate.run(Duration::from_millis(1));
+        assert_eq!(gate.ab.state, State::Low);
+    }
+
+    #[test]
+    fn delayed_inverter_lags_its_input() {
+        let mut gate = NotGate::build().with_propagation_delay(Duration::from_micros(300));
+        gate.vcc.state = State::High;
+        let step = Duration::from_micros(100);
+        let mut outputs = Vec::new();
+        for input in [
+            State::Low,
+            State::Low,
+            State::Low,
+            State::High,
+            State::High,
+            State::High,
+            State::High,
+        ] {
+            gate.a.state = input;
+            gate.run(step);
+            outputs.push(gate.not_a.state);
         }
+        // every change of the output shows up 3 ticks after the input that caused it
+        assert_eq!(
+            outputs,
+            [
+                State::Low,
+                State::Low,
+                State::Low,
+                State::High,
+                State::High,
+                State::High,
+                State::Low
+            ]
+        );
     }
 }
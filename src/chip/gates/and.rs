@@ -5,10 +5,12 @@ use crate::{
     generate_chip, State,
 };
 
+use super::{PropagationDelay, DEFAULT_THRESHOLD};
+
 /// # A chip with 4 bundled "AND" gates
 ///
 /// # Diagram
-/// ```
+/// ```txt
 ///        ---__---
 ///    A --|1   14|-- VCC
 ///    B --|2   13|-- E
@@ -19,9 +21,13 @@ use crate::{
 ///  GND --|7    8|-- G&H
 ///        --------
 /// ```
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AndGate {
+    #[cfg_attr(feature = "serde", serde(default))]
+    threshold: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    propagation: PropagationDelay,
     pub vcc: Pin,
     pub gnd: Pin,
     pub a: Pin,
@@ -53,11 +59,26 @@ impl AndGate {
     pub const G: PinId = 10;
     pub const H: PinId = 9;
     pub const GH: PinId = 8;
+
+    /// Set the voltage above which an input is considered high, defaults to half of the VCC
+    /// voltage, or 3.3 when VCC is a logic state
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Delay the outputs by the given amount of simulated time, defaults to no delay
+    pub fn with_propagation_delay(mut self, delay: Duration) -> Self {
+        self.propagation.delay = delay;
+        self
+    }
 }
 
-impl ChipBuilder<ChipSet> for AndGate {
-    fn build() -> ChipSet {
-        ChipSet::AndGate(AndGate {
+impl ChipBuilder<AndGate> for AndGate {
+    fn build() -> AndGate {
+        AndGate {
+            threshold: None,
+            propagation: PropagationDelay::default(),
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             a: Pin::from(PinType::Input),
@@ -72,12 +93,24 @@ impl ChipBuilder<ChipSet> for AndGate {
             g: Pin::from(PinType::Input),
             h: Pin::from(PinType::Input),
             gh: Pin::from(PinType::Output),
-        })
+        }
+    }
+}
+
+impl From<AndGate> for ChipSet {
+    fn from(value: AndGate) -> Self {
+        ChipSet::AndGate(value)
+    }
+}
+
+impl Default for AndGate {
+    fn default() -> Self {
+        Self::build()
     }
 }
 
 generate_chip!(
-    AndGate,
+    AndGate => AndGatePin,
     vcc: AndGate::VCC,
     gnd: AndGate::GND,
     a: AndGate::A,
@@ -95,25 +128,44 @@ generate_chip!(
 );
 
 impl ChipRunner for AndGate {
-    fn run(&mut self, _: Duration) {
-        if self.vcc.state.as_logic(3.3) == State::High {
+    fn run(&mut self, tick_duration: Duration) {
+        let threshold = self
+            .threshold
+            .unwrap_or_else(|| self.vcc.state.rail_threshold(DEFAULT_THRESHOLD));
+        if self.vcc.state.as_logic(1.0) == State::High {
             self.gnd.state = State::Low;
-            self.ab.state =
-                State::from(self.a.state.as_logic(3.3).into() && self.b.state.as_logic(3.3).into());
-            self.cd.state =
-                State::from(self.c.state.as_logic(3.3).into() && self.d.state.as_logic(3.3).into());
-            self.ef.state =
-                State::from(self.e.state.as_logic(3.3).into() && self.f.state.as_logic(3.3).into());
-            self.gh.state =
-                State::from(self.g.state.as_logic(3.3).into() && self.h.state.as_logic(3.3).into());
+            self.ab.state = State::from(
+                self.a.state.as_logic(threshold).into() && self.b.state.as_logic(threshold).into(),
+            );
+            self.cd.state = State::from(
+                self.c.state.as_logic(threshold).into() && self.d.state.as_logic(threshold).into(),
+            );
+            self.ef.state = State::from(
+                self.e.state.as_logic(threshold).into() && self.f.state.as_logic(threshold).into(),
+            );
+            self.gh.state = State::from(
+                self.g.state.as_logic(threshold).into() && self.h.state.as_logic(threshold).into(),
+            );
+            let outputs = self.propagation.propagate(
+                Pin::read(&[&self.ab, &self.cd, &self.ef, &self.gh]),
+                tick_duration,
+            );
+            Pin::write(
+                &mut [&mut self.ab, &mut self.cd, &mut self.ef, &mut self.gh],
+                outputs,
+            );
         }
     }
+
+    fn reset(&mut self) {
+        self.propagation.reset();
+    }
 }
 
 /// # A chip with 3 bundled "3-Input AND" gates
 ///
 /// # Diagram
-/// ```
+/// ```txt
 ///            ---__---
 ///        A --|1   14|-- VCC
 ///        B --|2   13|-- C
@@ -124,9 +176,13 @@ impl ChipRunner for AndGate {
 ///      GND --|7    8|-- G&H&I
 ///            --------
 /// ```
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ThreeInputAndGate {
+    #[cfg_attr(feature = "serde", serde(default))]
+    threshold: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    propagation: PropagationDelay,
     pub vcc: Pin,
     pub gnd: Pin,
     pub a: Pin,
@@ -158,11 +214,26 @@ impl ThreeInputAndGate {
     pub const H: PinId = 10;
     pub const I: PinId = 9;
     pub const GHI: PinId = 8;
+
+    /// Set the voltage above which an input is considered high, defaults to half of the VCC
+    /// voltage, or 3.3 when VCC is a logic state
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Delay the outputs by the given amount of simulated time, defaults to no delay
+    pub fn with_propagation_delay(mut self, delay: Duration) -> Self {
+        self.propagation.delay = delay;
+        self
+    }
 }
 
-impl ChipBuilder<ChipSet> for ThreeInputAndGate {
-    fn build() -> ChipSet {
-        ChipSet::ThreeInputAndGate(ThreeInputAndGate {
+impl ChipBuilder<ThreeInputAndGate> for ThreeInputAndGate {
+    fn build() -> ThreeInputAndGate {
+        ThreeInputAndGate {
+            threshold: None,
+            propagation: PropagationDelay::default(),
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             a: Pin::from(PinType::Input),
@@ -177,12 +248,24 @@ impl ChipBuilder<ChipSet> for ThreeInputAndGate {
             h: Pin::from(PinType::Input),
             i: Pin::from(PinType::Input),
             ghi: Pin::from(PinType::Output),
-        })
+        }
+    }
+}
+
+impl From<ThreeInputAndGate> for ChipSet {
+    fn from(value: ThreeInputAndGate) -> Self {
+        ChipSet::ThreeInputAndGate(value)
+    }
+}
+
+impl Default for ThreeInputAndGate {
+    fn default() -> Self {
+        Self::build()
     }
 }
 
 generate_chip!(
-    ThreeInputAndGate,
+    ThreeInputAndGate => ThreeInputAndGatePin,
     vcc: ThreeInputAndGate::VCC,
     gnd: ThreeInputAndGate::GND,
     a: ThreeInputAndGate::A,
@@ -200,24 +283,35 @@ generate_chip!(
 );
 
 impl ChipRunner for ThreeInputAndGate {
-    fn run(&mut self, _: Duration) {
-        if self.vcc.state.as_logic(3.3) == State::High {
+    fn run(&mut self, tick_duration: Duration) {
+        let threshold = self
+            .threshold
+            .unwrap_or_else(|| self.vcc.state.rail_threshold(DEFAULT_THRESHOLD));
+        if self.vcc.state.as_logic(1.0) == State::High {
             self.gnd.state = State::Low;
             self.abc.state = State::from(
-                self.a.state.as_logic(3.3).into()
-                    && self.b.state.as_logic(3.3).into()
-                    && self.c.state.as_logic(3.3).into(),
+                self.a.state.as_logic(threshold).into()
+                    && self.b.state.as_logic(threshold).into()
+                    && self.c.state.as_logic(threshold).into(),
             );
             self.def.state = State::from(
-                self.d.state.as_logic(3.3).into()
-                    && self.e.state.as_logic(3.3).into()
-                    && self.f.state.as_logic(3.3).into(),
+                self.d.state.as_logic(threshold).into()
+                    && self.e.state.as_logic(threshold).into()
+                    && self.f.state.as_logic(threshold).into(),
             );
             self.ghi.state = State::from(
-                self.g.state.as_logic(3.3).into()
-                    && self.h.state.as_logic(3.3).into()
-                    && self.i.state.as_logic(3.3).into(),
+                self.g.state.as_logic(threshold).into()
+                    && self.h.state.as_logic(threshold).into()
+                    && self.i.state.as_logic(threshold).into(),
             );
+            let outputs = self
+                .propagation
+                .propagate(Pin::read(&[&self.abc, &self.def, &self.ghi]), tick_duration);
+            Pin::write(&mut [&mut self.abc, &mut self.def, &mut self.ghi], outputs);
         }
     }
+
+    fn reset(&mut self) {
+        self.propagation.reset();
+    }
 }
@@ -8,6 +8,8 @@ use super::{Chip, ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Generator {
     state: State,
+    #[cfg_attr(feature = "serde", serde(default))]
+    disabled: bool,
     pub pin: Pin,
 }
 
@@ -19,12 +21,32 @@ impl Generator {
         self.pin.state = state;
         self
     }
+
+    /// Drive the given voltage instead of a logic state, to power chips from a custom rail
+    pub fn with_voltage(self, voltage: f32) -> Self {
+        self.with_state(State::Analog(voltage))
+    }
+
+    /// Switch the generator on or off, its output is left undefined while it's off
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.disabled = !enabled;
+        self.pin.state = if enabled {
+            self.state
+        } else {
+            State::Undefined
+        };
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled
+    }
 }
 
 impl ChipBuilder<Generator> for Generator {
     fn build() -> Generator {
         Generator {
             state: State::High,
+            disabled: false,
             pin: Pin {
                 pin_type: PinType::Output,
                 state: State::High,
@@ -51,10 +73,22 @@ impl Chip for Generator {
     fn get_pin_mut(&mut self, _pin: super::PinId) -> Option<&mut Pin> {
         Some(&mut self.pin)
     }
+
+    fn pin_count(&self) -> usize {
+        1
+    }
+
+    fn is_constant_source(&self) -> bool {
+        true
+    }
 }
 
 impl ChipRunner for Generator {
     fn run(&mut self, _: Duration) {
-        self.pin.state = self.state
+        self.pin.state = if self.disabled {
+            State::Undefined
+        } else {
+            self.state
+        }
     }
 }
@@ -1,3 +1,5 @@
+use std::{collections::VecDeque, time::Duration};
+
 use crate::{generate_chip, State};
 
 use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
@@ -6,8 +8,15 @@ use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
 /// Transmit the IN signal in the OUT pin when he is down
 /// you'll need to use `press()` and `release()` to change its state
 ///
+/// The contact can be made to bounce using `with_bounce`, in which case the OUT pin
+/// toggles a few times after each press or release before settling.
+///
+/// An active-low button, configured with `with_active_low`, shorts OUT to the ground instead:
+/// it drives it Low when down and leaves it floating otherwise, so that a pull-up on its trace
+/// sets the released level.
+///
 /// # Diagram
-/// ```
+/// ```txt
 ///        --------
 ///  IN  --|1    2|-- OUT
 ///        --------
@@ -16,6 +25,15 @@ use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Button {
     down: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    bounce: Duration,
+    #[cfg_attr(feature = "serde", serde(default))]
+    bounce_count: usize,
+    /// time elapsed since the last press or release, while the contact is bouncing
+    #[cfg_attr(feature = "serde", serde(default))]
+    bouncing: Option<Duration>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    active_low: bool,
     i: Pin,
     o: Pin,
 }
@@ -24,33 +42,467 @@ impl Button {
     pub const I: usize = 1;
     pub const O: usize = 2;
 
+    /// Make the contact bounce `bounce_count` times during the given window after each press
+    /// or release, defaults to a clean contact
+    pub fn with_bounce(mut self, bounce: Duration, bounce_count: usize) -> Self {
+        self.bounce = bounce;
+        self.bounce_count = bounce_count;
+        self
+    }
+
+    /// Drive OUT Low while the button is down instead of transmitting IN, defaults to false
+    pub fn with_active_low(mut self, active_low: bool) -> Self {
+        self.active_low = active_low;
+        self
+    }
+
     pub fn press(&mut self) {
-        self.down = true;
+        if !self.down {
+            self.down = true;
+            self.start_bouncing();
+        }
     }
 
     pub fn release(&mut self) {
-        self.down = false;
+        if self.down {
+            self.down = false;
+            self.start_bouncing();
+        }
+    }
+
+    fn start_bouncing(&mut self) {
+        if self.bounce_count > 0 && !self.bounce.is_zero() {
+            self.bouncing = Some(Duration::ZERO);
+        }
+    }
+
+    /// Get whether the contact is currently made, taking bouncing into account
+    fn contact(&mut self, tick_duration: Duration) -> bool {
+        match self.bouncing {
+            Some(elapsed) if elapsed < self.bounce => {
+                self.bouncing = Some(elapsed + tick_duration);
+                // the window is split in slots where the contact alternates between its new
+                // and previous position
+                let slot =
+                    elapsed.as_nanos() * (self.bounce_count as u128 * 2) / self.bounce.as_nanos();
+                (slot % 2 == 0) == self.down
+            }
+            _ => {
+                self.bouncing = None;
+                self.down
+            }
+        }
     }
 }
 
-generate_chip!(Button, i: Button::I, o: Button::O);
+generate_chip!(Button => ButtonPin, i: Button::I, o: Button::O);
 
-impl ChipBuilder<ChipSet> for Button {
-    fn build() -> ChipSet {
-        ChipSet::Button(Button {
+impl ChipBuilder<Button> for Button {
+    fn build() -> Button {
+        Button {
             down: false,
+            bounce: Duration::ZERO,
+            bounce_count: 0,
+            bouncing: None,
+            active_low: false,
             i: Pin::from(PinType::Input),
             o: Pin::from(PinType::Output),
-        })
+        }
+    }
+}
+
+impl From<Button> for ChipSet {
+    fn from(value: Button) -> Self {
+        ChipSet::Button(value)
     }
 }
 
 impl ChipRunner for Button {
-    fn run(&mut self, _: std::time::Duration) {
-        if self.down {
-            self.o.state = self.i.state;
+    fn run(&mut self, tick_duration: Duration) {
+        if self.contact(tick_duration) {
+            self.o.state = if self.active_low {
+                State::Low
+            } else {
+                self.i.state
+            };
         } else {
             self.o.state = State::Undefined
         }
     }
+
+    fn reset(&mut self) {
+        self.bouncing = None;
+    }
+}
+
+/// # A 4x4 matrix keypad
+/// Columns are driven high while powered, a pressed key pulls its column low
+/// when its row is driven low, allowing to scan the keypad row by row.
+/// you'll need to use `press(row, col)` and `release(row, col)` to change the keys state
+///
+/// # Diagram
+/// ```txt
+///        ---__---
+///   R0 --|1   10|-- VCC
+///   R1 --|2    9|-- GND
+///   R2 --|3    8|-- C3
+///   R3 --|4    7|-- C2
+///   C0 --|5    6|-- C1
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeypadMatrix4x4 {
+    keys: [[bool; 4]; 4],
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub r0: Pin,
+    pub r1: Pin,
+    pub r2: Pin,
+    pub r3: Pin,
+    pub c0: Pin,
+    pub c1: Pin,
+    pub c2: Pin,
+    pub c3: Pin,
+}
+
+impl KeypadMatrix4x4 {
+    pub const R0: usize = 1;
+    pub const R1: usize = 2;
+    pub const R2: usize = 3;
+    pub const R3: usize = 4;
+    pub const C0: usize = 5;
+    pub const C1: usize = 6;
+    pub const C2: usize = 7;
+    pub const C3: usize = 8;
+    pub const GND: usize = 9;
+    pub const VCC: usize = 10;
+
+    /// Press the key at the given row and column, out of range keys are ignored
+    pub fn press(&mut self, row: usize, col: usize) {
+        if let Some(key) = self.keys.get_mut(row).and_then(|r| r.get_mut(col)) {
+            *key = true;
+        }
+    }
+
+    /// Release the key at the given row and column, out of range keys are ignored
+    pub fn release(&mut self, row: usize, col: usize) {
+        if let Some(key) = self.keys.get_mut(row).and_then(|r| r.get_mut(col)) {
+            *key = false;
+        }
+    }
+}
+
+generate_chip!(
+    KeypadMatrix4x4 => KeypadMatrix4x4Pin,
+    vcc: KeypadMatrix4x4::VCC,
+    gnd: KeypadMatrix4x4::GND,
+    r0: KeypadMatrix4x4::R0,
+    r1: KeypadMatrix4x4::R1,
+    r2: KeypadMatrix4x4::R2,
+    r3: KeypadMatrix4x4::R3,
+    c0: KeypadMatrix4x4::C0,
+    c1: KeypadMatrix4x4::C1,
+    c2: KeypadMatrix4x4::C2,
+    c3: KeypadMatrix4x4::C3
+);
+
+impl ChipBuilder<KeypadMatrix4x4> for KeypadMatrix4x4 {
+    fn build() -> KeypadMatrix4x4 {
+        KeypadMatrix4x4 {
+            keys: [[false; 4]; 4],
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            r0: Pin::from(PinType::Input),
+            r1: Pin::from(PinType::Input),
+            r2: Pin::from(PinType::Input),
+            r3: Pin::from(PinType::Input),
+            c0: Pin::from(PinType::Output),
+            c1: Pin::from(PinType::Output),
+            c2: Pin::from(PinType::Output),
+            c3: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<KeypadMatrix4x4> for ChipSet {
+    fn from(value: KeypadMatrix4x4) -> Self {
+        ChipSet::KeypadMatrix4x4(value)
+    }
+}
+
+impl ChipRunner for KeypadMatrix4x4 {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            // a floating row isn't driven low
+            let selected = [&self.r0, &self.r1, &self.r2, &self.r3].map(|row| {
+                row.state != State::Undefined && row.state.as_logic(threshold) == State::Low
+            });
+            let mut columns = [State::High; 4];
+            for (row, keys) in self.keys.iter().enumerate() {
+                if selected[row] {
+                    for (col, pressed) in keys.iter().enumerate() {
+                        if *pressed {
+                            columns[col] = State::Low;
+                        }
+                    }
+                }
+            }
+            self.c0.state = columns[0];
+            self.c1.state = columns[1];
+            self.c2.state = columns[2];
+            self.c3.state = columns[3];
+        } else {
+            self.c0.state = State::Undefined;
+            self.c1.state = State::Undefined;
+            self.c2.state = State::Undefined;
+            self.c3.state = State::Undefined;
+        }
+    }
+}
+
+/// # A byte input bridge
+/// Hands bytes queued by the host program over to the circuit, one byte per rising edge of STB.
+/// This keeps the host I/O (stdin, keyboard...) out of the simulation: the program feeding the
+/// board reads its input and calls `queue_byte`, and the circuit strobes the bytes in.
+///
+/// AVAIL is high while bytes are waiting in the queue. On a rising edge of STB, the next byte
+/// is driven on D0-D7 and stays there until the next strobe, the outputs keep the last byte
+/// when the queue is empty.
+///
+/// # Diagram
+/// STB: Strobe
+/// AVAIL: Bytes available
+/// ```txt
+///         ---__---
+///    D0 --|1   14|-- VCC
+///    D1 --|2   13|-- STB
+///    D2 --|3   12|-- AVAIL
+///    D3 --|4   11|-- D7
+///    D4 --|5   10|-- D6
+///    D5 --|6    9|-- UNUSED
+///   GND --|7    8|-- UNUSED
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputBridge {
+    queue: VecDeque<u8>,
+    byte: u8,
+    strobe: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub stb: Pin,
+    pub avail: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+}
+
+impl InputBridge {
+    pub const D0: usize = 1;
+    pub const D1: usize = 2;
+    pub const D2: usize = 3;
+    pub const D3: usize = 4;
+    pub const D4: usize = 5;
+    pub const D5: usize = 6;
+    pub const GND: usize = 7;
+    pub const D6: usize = 10;
+    pub const D7: usize = 11;
+    pub const AVAIL: usize = 12;
+    pub const STB: usize = 13;
+    pub const VCC: usize = 14;
+
+    /// Add a byte at the end of the queue
+    pub fn queue_byte(&mut self, byte: u8) {
+        self.queue.push_back(byte);
+    }
+
+    /// Add several bytes at the end of the queue, like a line read from stdin
+    pub fn queue_bytes(&mut self, bytes: &[u8]) {
+        self.queue.extend(bytes);
+    }
+
+    /// Get the number of bytes waiting to be strobed in
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+generate_chip!(
+    InputBridge => InputBridgePin,
+    vcc: InputBridge::VCC,
+    gnd: InputBridge::GND,
+    stb: InputBridge::STB,
+    avail: InputBridge::AVAIL,
+    d0: InputBridge::D0,
+    d1: InputBridge::D1,
+    d2: InputBridge::D2,
+    d3: InputBridge::D3,
+    d4: InputBridge::D4,
+    d5: InputBridge::D5,
+    d6: InputBridge::D6,
+    d7: InputBridge::D7
+);
+
+impl ChipBuilder<InputBridge> for InputBridge {
+    fn build() -> InputBridge {
+        InputBridge {
+            queue: VecDeque::new(),
+            byte: 0,
+            strobe: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            stb: Pin::from(PinType::Input),
+            avail: Pin::from(PinType::Output),
+            d0: Pin::from(PinType::Output),
+            d1: Pin::from(PinType::Output),
+            d2: Pin::from(PinType::Output),
+            d3: Pin::from(PinType::Output),
+            d4: Pin::from(PinType::Output),
+            d5: Pin::from(PinType::Output),
+            d6: Pin::from(PinType::Output),
+            d7: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<InputBridge> for ChipSet {
+    fn from(value: InputBridge) -> Self {
+        ChipSet::InputBridge(value)
+    }
+}
+
+impl ChipRunner for InputBridge {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let strobe: bool = self.stb.state.as_logic(threshold).into();
+            if strobe && !self.strobe {
+                if let Some(byte) = self.queue.pop_front() {
+                    self.byte = byte;
+                }
+            }
+            self.strobe = strobe;
+
+            self.avail.state = State::from(!self.queue.is_empty());
+            Pin::write(
+                &mut [
+                    &mut self.d0,
+                    &mut self.d1,
+                    &mut self.d2,
+                    &mut self.d3,
+                    &mut self.d4,
+                    &mut self.d5,
+                    &mut self.d6,
+                    &mut self.d7,
+                ],
+                self.byte as usize,
+            );
+        }
+    }
+
+    fn reset(&mut self) {
+        self.byte = 0;
+        self.strobe = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin},
+        State,
+    };
+
+    use super::{Button, InputBridge, KeypadMatrix4x4};
+
+    #[test]
+    fn bouncing_button_settles_after_the_window() {
+        let mut button = Button::build().with_bounce(Duration::from_millis(1), 3);
+        button.i.state = State::High;
+        button.press();
+        let outputs: Vec<_> = (0..20)
+            .map(|_| {
+                button.run(Duration::from_micros(100));
+                button.o.state
+            })
+            .collect();
+
+        let (window, settled) = outputs.split_at(10);
+        let toggles = window.windows(2).filter(|pair| pair[0] != pair[1]).count();
+        assert!(toggles >= 4, "{window:?}");
+        assert!(settled.iter().all(|state| *state == State::High));
+    }
+
+    /// Drive the given row low and the others high, then read C0-C3
+    fn scan(keypad: &mut KeypadMatrix4x4, row: usize) -> usize {
+        for (index, pin) in [
+            &mut keypad.r0,
+            &mut keypad.r1,
+            &mut keypad.r2,
+            &mut keypad.r3,
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            pin.state = State::from(index != row);
+        }
+        keypad.run(Duration::from_millis(1));
+        Pin::read(&[&keypad.c0, &keypad.c1, &keypad.c2, &keypad.c3])
+    }
+
+    #[test]
+    fn pressed_key_pulls_its_column_low_on_its_row() {
+        let mut keypad = KeypadMatrix4x4::build();
+        keypad.vcc.state = State::High;
+        for row in 0..4 {
+            assert_eq!(scan(&mut keypad, row), 0b1111);
+        }
+
+        keypad.press(2, 1);
+        assert_eq!(scan(&mut keypad, 0), 0b1111);
+        assert_eq!(scan(&mut keypad, 1), 0b1111);
+        assert_eq!(scan(&mut keypad, 2), 0b1101);
+        assert_eq!(scan(&mut keypad, 3), 0b1111);
+
+        keypad.release(2, 1);
+        assert_eq!(scan(&mut keypad, 2), 0b1111);
+    }
+
+    #[test]
+    fn bridge_strobes_the_queued_bytes_in_order() {
+        let mut bridge = InputBridge::build();
+        bridge.vcc.state = State::High;
+        bridge.queue_byte(b'h');
+        bridge.queue_bytes(b"i!");
+        bridge.run(Duration::from_millis(1));
+        assert_eq!(bridge.avail.state, State::High);
+
+        let mut received = vec![];
+        while bridge.avail.state == State::High {
+            for strobe in [true, false] {
+                bridge.stb.state = State::from(strobe);
+                bridge.run(Duration::from_millis(1));
+            }
+            received.push(Pin::read(&[
+                &bridge.d0, &bridge.d1, &bridge.d2, &bridge.d3, &bridge.d4, &bridge.d5, &bridge.d6,
+                &bridge.d7,
+            ]) as u8);
+        }
+        assert_eq!(received, b"hi!");
+        assert_eq!(bridge.pending(), 0);
+    }
 }
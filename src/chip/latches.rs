@@ -0,0 +1,239 @@
+use std::time::Duration;
+
+use crate::{generate_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
+
+/// # A SR latch
+///
+/// Level-sensitive: Q goes high while S is high and low while R is high, and holds its value
+/// when both are low.
+/// Setting S and R high at the same time is forbidden, like a NOR latch both Q and !Q are then
+/// driven low, and Q keeps its previous value once they are released.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///     S --|1    6|-- VCC
+///     R --|2    5|-- Q
+///   GND --|3    4|-- !Q
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SrLatch {
+    value: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub s: Pin,
+    pub r: Pin,
+    pub q: Pin,
+    pub nq: Pin,
+}
+
+impl SrLatch {
+    pub const S: PinId = 1;
+    pub const R: PinId = 2;
+    pub const GND: PinId = 3;
+    pub const NQ: PinId = 4;
+    pub const Q: PinId = 5;
+    pub const VCC: PinId = 6;
+
+    /// Get the stored value
+    pub fn value(&self) -> bool {
+        self.value
+    }
+}
+
+impl ChipBuilder<SrLatch> for SrLatch {
+    fn build() -> SrLatch {
+        SrLatch {
+            value: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            s: Pin::from(PinType::Input),
+            r: Pin::from(PinType::Input),
+            q: Pin::from(PinType::Output),
+            nq: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<SrLatch> for ChipSet {
+    fn from(value: SrLatch) -> Self {
+        ChipSet::SrLatch(value)
+    }
+}
+
+generate_chip!(
+    SrLatch => SrLatchPin,
+    vcc: SrLatch::VCC,
+    gnd: SrLatch::GND,
+    s: SrLatch::S,
+    r: SrLatch::R,
+    q: SrLatch::Q,
+    nq: SrLatch::NQ
+);
+
+impl ChipRunner for SrLatch {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let set: bool = self.s.state.as_logic(threshold).into();
+            let reset: bool = self.r.state.as_logic(threshold).into();
+            match (set, reset) {
+                (true, true) => {
+                    self.q.state = State::Low;
+                    self.nq.state = State::Low;
+                    return;
+                }
+                (true, false) => self.value = true,
+                (false, true) => self.value = false,
+                (false, false) => {}
+            }
+            self.q.state = State::from(self.value);
+            self.nq.state = State::from(!self.value);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.value = false;
+    }
+}
+
+/// # A transparent D latch
+///
+/// Level-sensitive: Q follows D while EN is high, and holds the last value of D once EN goes low.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///     D --|1    6|-- VCC
+///    EN --|2    5|-- Q
+///   GND --|3    4|-- !Q
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DLatch {
+    value: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub d: Pin,
+    pub en: Pin,
+    pub q: Pin,
+    pub nq: Pin,
+}
+
+impl DLatch {
+    pub const D: PinId = 1;
+    pub const EN: PinId = 2;
+    pub const GND: PinId = 3;
+    pub const NQ: PinId = 4;
+    pub const Q: PinId = 5;
+    pub const VCC: PinId = 6;
+
+    /// Get the stored value
+    pub fn value(&self) -> bool {
+        self.value
+    }
+}
+
+impl ChipBuilder<DLatch> for DLatch {
+    fn build() -> DLatch {
+        DLatch {
+            value: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            d: Pin::from(PinType::Input),
+            en: Pin::from(PinType::Input),
+            q: Pin::from(PinType::Output),
+            nq: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<DLatch> for ChipSet {
+    fn from(value: DLatch) -> Self {
+        ChipSet::DLatch(value)
+    }
+}
+
+generate_chip!(
+    DLatch => DLatchPin,
+    vcc: DLatch::VCC,
+    gnd: DLatch::GND,
+    d: DLatch::D,
+    en: DLatch::EN,
+    q: DLatch::Q,
+    nq: DLatch::NQ
+);
+
+impl ChipRunner for DLatch {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            if self.en.state.as_logic(threshold) == State::High {
+                self.value = self.d.state.as_logic(threshold).into();
+            }
+            self.q.state = State::from(self.value);
+            self.nq.state = State::from(!self.value);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.value = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{DLatch, SrLatch};
+    use crate::{
+        chip::{ChipBuilder, ChipRunner},
+        State,
+    };
+
+    #[test]
+    fn sr_latch_set_reset_and_hold() {
+        let mut latch = SrLatch::build();
+        latch.vcc.state = State::High;
+        let mut apply = |set: bool, reset: bool| {
+            latch.s.state = State::from(set);
+            latch.r.state = State::from(reset);
+            latch.run(Duration::from_millis(1));
+            (latch.q.state, latch.nq.state)
+        };
+        assert_eq!(apply(true, false), (State::High, State::Low));
+        assert_eq!(apply(false, false), (State::High, State::Low));
+        assert_eq!(apply(false, true), (State::Low, State::High));
+        assert_eq!(apply(false, false), (State::Low, State::High));
+        // the forbidden state drives both outputs low, and the previous value comes back
+        apply(true, false);
+        assert_eq!(apply(true, true), (State::Low, State::Low));
+        assert_eq!(apply(false, false), (State::High, State::Low));
+    }
+
+    #[test]
+    fn d_latch_is_transparent_while_enabled() {
+        let mut latch = DLatch::build();
+        latch.vcc.state = State::High;
+        let mut apply = |enable: bool, data: bool| {
+            latch.en.state = State::from(enable);
+            latch.d.state = State::from(data);
+            latch.run(Duration::from_millis(1));
+            latch.q.state
+        };
+        assert_eq!(apply(true, true), State::High);
+        assert_eq!(apply(true, false), State::Low);
+        assert_eq!(apply(true, true), State::High);
+        // once disabled, D is ignored
+        assert_eq!(apply(false, false), State::High);
+        assert_eq!(apply(false, true), State::High);
+        assert_eq!(apply(true, false), State::Low);
+    }
+}
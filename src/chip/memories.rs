@@ -1,11 +1,201 @@
-use std::time::Duration;
+use std::{fmt, time::Duration};
 
-use rand::random;
+use rand::{rngs::StdRng, thread_rng, Rng, SeedableRng};
 
 use crate::{generate_chip, State};
 
 use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
 
+/// Content of a RAM chip when it gets powered up
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RamInit {
+    /// Fill the memory with zeroes
+    Zero,
+    /// Fill the memory with random values, like a real RAM chip would
+    #[default]
+    Random,
+    /// Fill the memory with the given byte
+    Pattern(u8),
+}
+
+impl RamInit {
+    fn fill(&self, ram: &mut [u8], seed: &mut Option<u64>) {
+        match self {
+            RamInit::Zero => ram.fill(0),
+            RamInit::Random => {
+                if let Some(seed) = seed {
+                    let mut rng = StdRng::seed_from_u64(*seed);
+                    rng.fill(ram);
+                    // chain the seed so that every power cycle is different but reproducible
+                    *seed = rng.gen();
+                } else {
+                    thread_rng().fill(ram);
+                }
+            }
+            RamInit::Pattern(pattern) => ram.fill(*pattern),
+        }
+    }
+}
+
+/// Returned when the content of a ROM doesn't match the expected checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChecksumMismatch {
+    pub expected: u32,
+    pub actual: u32,
+}
+
+/// Whether an active low control input is asserted, a floating input isn't
+fn active_low(state: State, threshold: f32) -> bool {
+    state != State::Undefined && state.as_logic(threshold) == State::Low
+}
+
+/// CRC-32 (IEEE 802.3) of the given bytes
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Reasons for which an Intel HEX image couldn't be loaded, along with the line at fault
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntelHexError {
+    /// The line isn't a well-formed record
+    InvalidRecord(usize),
+    /// The checksum of the record doesn't match its content
+    BadChecksum(usize),
+    /// The record writes outside of the memory
+    OutOfBounds(usize),
+}
+
+/// Dump a memory as Intel HEX data records of 16 bytes, followed by an end-of-file record
+fn to_intel_hex(data: &[u8]) -> String {
+    let mut hex = String::new();
+    for (index, chunk) in data.chunks(16).enumerate() {
+        let address = (index * 16) as u16;
+        let mut record = vec![chunk.len() as u8];
+        record.extend(address.to_be_bytes());
+        record.push(0x00);
+        record.extend(chunk);
+        let checksum = record
+            .iter()
+            .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+            .wrapping_neg();
+        record.push(checksum);
+        hex.push(':');
+        for byte in record {
+            hex.push_str(&format!("{byte:02X}"));
+        }
+        hex.push('\n');
+    }
+    hex.push_str(":00000001FF\n");
+    hex
+}
+
+/// Load the data records of an Intel HEX image into a memory, until the end-of-file record.
+/// Start address records are ignored.
+fn load_intel_hex(hex: &str, memory: &mut [u8]) -> Result<(), IntelHexError> {
+    let mut base = 0;
+    for (line_nb, line) in hex.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = line
+            .strip_prefix(':')
+            .filter(|record| record.len() >= 10 && record.len() % 2 == 0)
+            .and_then(|record| {
+                (0..record.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(record.get(i..i + 2)?, 16).ok())
+                    .collect::<Option<Vec<u8>>>()
+            })
+            .ok_or(IntelHexError::InvalidRecord(line_nb))?;
+        let data_len = record[0] as usize;
+        if record.len() != data_len + 5 {
+            return Err(IntelHexError::InvalidRecord(line_nb));
+        }
+        if record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) != 0 {
+            return Err(IntelHexError::BadChecksum(line_nb));
+        }
+        let address = u16::from_be_bytes([record[1], record[2]]) as usize;
+        let data = &record[4..4 + data_len];
+        match record[3] {
+            0x00 => {
+                let start = base + address;
+                memory
+                    .get_mut(start..start + data_len)
+                    .ok_or(IntelHexError::OutOfBounds(line_nb))?
+                    .copy_from_slice(data);
+            }
+            0x01 => break,
+            0x02 if data_len == 2 => base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 4,
+            0x04 if data_len == 2 => base = (u16::from_be_bytes([data[0], data[1]]) as usize) << 16,
+            0x03 | 0x05 => {}
+            _ => return Err(IntelHexError::InvalidRecord(line_nb)),
+        }
+    }
+    Ok(())
+}
+
+/// An access to a memory chip, recorded once when it begins
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemoryEvent {
+    /// The byte at the address was put on the IO pins
+    Read { address: usize, value: u8 },
+    /// The byte on the IO pins was stored at the address
+    Write { address: usize, value: u8 },
+}
+
+/// Accesses of a memory chip waiting to be drained, when recording is enabled
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct MemoryEventLog {
+    enabled: bool,
+    events: Vec<MemoryEvent>,
+    current: Option<MemoryEvent>,
+}
+
+impl MemoryEventLog {
+    /// Record the access happening during the current tick, an access lasting several ticks
+    /// is only recorded once, with the last value seen on the IO pins as the data bus settles
+    fn record(&mut self, access: Option<MemoryEvent>) {
+        if let (true, Some(access)) = (self.enabled, access) {
+            match (self.current, self.events.last_mut()) {
+                (Some(current), Some(last)) if *last == current && current.same_access(&access) => {
+                    *last = access;
+                }
+                _ if self.current != Some(access) => self.events.push(access),
+                _ => {}
+            }
+        }
+        self.current = access;
+    }
+}
+
+impl MemoryEvent {
+    /// Whether both events are the same kind of access at the same address
+    fn same_access(&self, other: &MemoryEvent) -> bool {
+        match (self, other) {
+            (MemoryEvent::Read { address: a, .. }, MemoryEvent::Read { address: b, .. })
+            | (MemoryEvent::Write { address: a, .. }, MemoryEvent::Write { address: b, .. }) => {
+                a == b
+            }
+            _ => false,
+        }
+    }
+}
+
 /// # A 256-bytes RAM chip
 ///
 /// # Diagram
@@ -14,7 +204,7 @@ use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
 /// OE: Output Enable (active low)
 /// A0-7: Addresses
 /// IO0-7: Input/Output
-/// ```
+/// ```txt
 ///        ---__---
 ///  !CS --|1   22|-- VCC
 ///  !WE --|2   21|-- UNUSED
@@ -33,7 +223,13 @@ use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ram256B {
     powered: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    init: RamInit,
+    #[cfg_attr(feature = "serde", serde(default))]
+    seed: Option<u64>,
     ram: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    events: MemoryEventLog,
     pub vcc: Pin,
     pub gnd: Pin,
     pub cs: Pin,
@@ -90,10 +286,38 @@ impl Ram256B {
         self.io6.pin_type = pin_type;
         self.io7.pin_type = pin_type;
     }
+
+    /// Choose how the memory gets initialized on power-up
+    pub fn with_init(mut self, init: RamInit) -> Self {
+        self.init = init;
+        self
+    }
+
+    /// Seed the random power-up content for reproducible simulations
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Record the reads and writes of the memory, to be drained with `take_events`
+    pub fn with_events(mut self, enabled: bool) -> Self {
+        self.events.enabled = enabled;
+        self
+    }
+
+    /// Get the accesses recorded since the last call, oldest first
+    pub fn take_events(&mut self) -> Vec<MemoryEvent> {
+        std::mem::take(&mut self.events.events)
+    }
+
+    /// Get the content of the memory
+    pub fn data(&self) -> &[u8] {
+        &self.ram
+    }
 }
 
 generate_chip!(
-    Ram256B,
+    Ram256B => Ram256BPin,
     cs: Ram256B::CS,
     we: Ram256B::WE,
     oe: Ram256B::OE,
@@ -117,11 +341,14 @@ generate_chip!(
     gnd: Ram256B::GND
 );
 
-impl ChipBuilder<ChipSet> for Ram256B {
-    fn build() -> ChipSet {
-        ChipSet::Ram256B(Ram256B {
+impl ChipBuilder<Ram256B> for Ram256B {
+    fn build() -> Ram256B {
+        Ram256B {
             powered: false,
+            init: RamInit::default(),
+            seed: None,
             ram: Vec::from([0; 256]),
+            events: MemoryEventLog::default(),
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             cs: Pin::from(PinType::Input),
@@ -143,17 +370,22 @@ impl ChipBuilder<ChipSet> for Ram256B {
             io5: Pin::from(PinType::Floating),
             io6: Pin::from(PinType::Floating),
             io7: Pin::from(PinType::Floating),
-        })
+        }
+    }
+}
+
+impl From<Ram256B> for ChipSet {
+    fn from(value: Ram256B) -> Self {
+        ChipSet::Ram256B(value)
     }
 }
 
 impl ChipRunner for Ram256B {
     fn run(&mut self, _: Duration) {
+        let threshold = self.vcc.state.rail_threshold(3.3);
         if self.vcc.state.as_logic(1.0) == State::High {
             if !self.powered {
-                for i in 0..256 {
-                    self.ram[i] = random::<u8>();
-                }
+                self.init.fill(&mut self.ram, &mut self.seed);
                 self.powered = true;
             }
             self.gnd.state = State::Low;
@@ -170,15 +402,20 @@ impl ChipRunner for Ram256B {
                             &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
                             &self.a7,
                         ],
-                        3.3,
+                        threshold,
                     );
-                    self.ram[addr] = Pin::read_threshold(
+                    let value = Pin::read_threshold(
                         &[
                             &self.io0, &self.io1, &self.io2, &self.io3, &self.io4, &self.io5,
                             &self.io6, &self.io7,
                         ],
-                        3.3,
+                        threshold,
                     ) as u8;
+                    self.ram[addr] = value;
+                    self.events.record(Some(MemoryEvent::Write {
+                        address: addr,
+                        value,
+                    }));
                 } else if self.oe.state == State::Low {
                     // IO = Output
                     self.set_io_type(PinType::Output);
@@ -189,7 +426,7 @@ impl ChipRunner for Ram256B {
                             &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
                             &self.a7,
                         ],
-                        3.3,
+                        threshold,
                     );
                     Pin::write(
                         &mut [
@@ -204,21 +441,32 @@ impl ChipRunner for Ram256B {
                         ],
                         self.ram[addr] as usize,
                     );
+                    self.events.record(Some(MemoryEvent::Read {
+                        address: addr,
+                        value: self.ram[addr],
+                    }));
                 } else {
                     self.set_io_type(PinType::Floating);
+                    self.events.record(None);
                 }
             } else {
                 self.set_io_type(PinType::Floating);
+                self.events.record(None);
             }
         } else if self.powered {
             self.set_io_type(PinType::Floating);
             self.powered = false;
         }
     }
+
+    fn reset(&mut self) {
+        self.init.fill(&mut self.ram, &mut self.seed);
+    }
 }
 
-impl ToString for Ram256B {
-    fn to_string(&self) -> std::string::String {
+impl fmt::Display for Ram256B {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let threshold = self.vcc.state.rail_threshold(3.3);
         let mut string = String::from(
             "ADR| 00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n---+------------------------------------------------",
         );
@@ -228,13 +476,13 @@ impl ToString for Ram256B {
             }
             string.push_str(&format!(
                 "{}{byte:02X}",
-                if self.cs.state.as_logic(3.3) == State::Low
+                if self.cs.state.as_logic(threshold) == State::Low
                     && Pin::read_threshold(
                         &[
                             &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
                             &self.a7
                         ],
-                        3.3
+                        threshold
                     ) == addr
                 {
                     ">"
@@ -244,7 +492,7 @@ impl ToString for Ram256B {
             ));
         }
         string.push('\n');
-        string
+        write!(f, "{string}")
     }
 }
 
@@ -256,28 +504,41 @@ impl ToString for Ram256B {
 /// OE: Output Enable (active low)
 /// A0-12: Addresses
 /// IO0-7: Input/Output
-/// ```
+/// WP: Write Protect (active low), writes are ignored while it's low.
+/// Leave it unconnected to keep the memory writable.
+/// ```txt
 ///        ---__---
-///  !CS --|1   26|-- VCC
-///  !WE --|2   25|-- IO7
-///  !OE --|3   24|-- IO6
-///   A0 --|4   23|-- IO5
-///   A1 --|5   22|-- IO4
-///   A2 --|6   21|-- IO3
-///   A3 --|7   20|-- IO2
-///   A4 --|8   19|-- IO1
-///   A5 --|9   18|-- IO0
-///   A6 --|10  17|-- A12
-///   A7 --|11  16|-- A11
-///   A8 --|12  15|-- A10
-///  GND --|13  14|-- A9
+///  !CS --|1   28|-- UNUSED
+///  !WE --|2   27|-- !WP
+///  !OE --|3   26|-- VCC
+///   A0 --|4   25|-- IO7
+///   A1 --|5   24|-- IO6
+///   A2 --|6   23|-- IO5
+///   A3 --|7   22|-- IO4
+///   A4 --|8   21|-- IO3
+///   A5 --|9   20|-- IO2
+///   A6 --|10  19|-- IO1
+///   A7 --|11  18|-- IO0
+///   A8 --|12  17|-- A12
+///  GND --|13  16|-- A11
+///   A9 --|14  15|-- A10
 ///        --------
 /// ```
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ram8KB {
     powered: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    init: RamInit,
+    #[cfg_attr(feature = "serde", serde(default))]
+    seed: Option<u64>,
     ram: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    events: MemoryEventLog,
+    #[cfg_attr(feature = "serde", serde(default))]
+    logic_threshold: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    address_mask: Option<usize>,
     pub vcc: Pin,
     pub gnd: Pin,
     pub cs: Pin,
@@ -304,6 +565,8 @@ pub struct Ram8KB {
     pub io5: Pin,
     pub io6: Pin,
     pub io7: Pin,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub wp: Pin,
 }
 
 impl Ram8KB {
@@ -333,6 +596,37 @@ impl Ram8KB {
     pub const IO7: usize = 25;
     pub const VCC: usize = 26;
     pub const GND: usize = 13;
+    pub const WP: usize = 27;
+
+    /// Set the voltage above which the address, data and control inputs are considered high,
+    /// defaults to half of the VCC voltage, or 3.3 when VCC is a logic state
+    pub fn with_logic_threshold(mut self, threshold: f32) -> Self {
+        self.logic_threshold = Some(threshold);
+        self
+    }
+
+    fn logic_threshold(&self) -> f32 {
+        self.logic_threshold
+            .unwrap_or_else(|| self.vcc.state.rail_threshold(3.3))
+    }
+
+    /// Mask the address before accessing the memory, so that its content gets mirrored.
+    /// For instance a mask of 0x00FF repeats the first 256 bytes across the whole address range.
+    pub fn with_address_mask(mut self, mask: usize) -> Self {
+        self.address_mask = Some(mask);
+        self
+    }
+
+    /// Address given on A0-12, masked by the address mask
+    fn address(&self, threshold: f32) -> usize {
+        Pin::read_threshold(
+            &[
+                &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6, &self.a7,
+                &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
+            ],
+            threshold,
+        ) & self.address_mask.unwrap_or(0x1FFF)
+    }
 
     fn set_io_type(&mut self, pin_type: PinType) {
         self.io0.pin_type = pin_type;
@@ -344,10 +638,38 @@ impl Ram8KB {
         self.io6.pin_type = pin_type;
         self.io7.pin_type = pin_type;
     }
+
+    /// Choose how the memory gets initialized on power-up
+    pub fn with_init(mut self, init: RamInit) -> Self {
+        self.init = init;
+        self
+    }
+
+    /// Seed the random power-up content for reproducible simulations
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Record the reads and writes of the memory, to be drained with `take_events`
+    pub fn with_events(mut self, enabled: bool) -> Self {
+        self.events.enabled = enabled;
+        self
+    }
+
+    /// Get the accesses recorded since the last call, oldest first
+    pub fn take_events(&mut self) -> Vec<MemoryEvent> {
+        std::mem::take(&mut self.events.events)
+    }
+
+    /// Dump the content of the memory in the Intel HEX format
+    pub fn to_intel_hex(&self) -> String {
+        to_intel_hex(&self.ram)
+    }
 }
 
 generate_chip!(
-    Ram8KB,
+    Ram8KB => Ram8KBPin,
     cs: Ram8KB::CS,
     we: Ram8KB::WE,
     oe: Ram8KB::OE,
@@ -372,15 +694,21 @@ generate_chip!(
     io5: Ram8KB::IO5,
     io6: Ram8KB::IO6,
     io7: Ram8KB::IO7,
+    wp: Ram8KB::WP,
     vcc: Ram8KB::VCC,
     gnd: Ram8KB::GND
 );
 
-impl ChipBuilder<ChipSet> for Ram8KB {
-    fn build() -> ChipSet {
-        ChipSet::Ram8KB(Ram8KB {
+impl ChipBuilder<Ram8KB> for Ram8KB {
+    fn build() -> Ram8KB {
+        Ram8KB {
             powered: false,
+            init: RamInit::default(),
+            seed: None,
             ram: Vec::from([0; 8192]),
+            events: MemoryEventLog::default(),
+            logic_threshold: None,
+            address_mask: None,
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             cs: Pin::from(PinType::Input),
@@ -407,54 +735,58 @@ impl ChipBuilder<ChipSet> for Ram8KB {
             io5: Pin::from(PinType::Floating),
             io6: Pin::from(PinType::Floating),
             io7: Pin::from(PinType::Floating),
-        })
+            wp: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<Ram8KB> for ChipSet {
+    fn from(value: Ram8KB) -> Self {
+        ChipSet::Ram8KB(value)
     }
 }
 
 impl ChipRunner for Ram8KB {
     fn run(&mut self, _: Duration) {
+        let threshold = self.logic_threshold();
         if self.vcc.state.as_logic(1.0) == State::High {
             if !self.powered {
-                for i in 0..256 {
-                    self.ram[i] = random::<u8>();
-                }
+                self.init.fill(&mut self.ram, &mut self.seed);
                 self.powered = true;
             }
             self.gnd.state = State::Low;
 
             // check Chip Select (active low)
-            if self.cs.state == State::Low {
+            if active_low(self.cs.state, threshold) {
                 // check Write Enable (active low)
-                if self.we.state == State::Low {
+                if active_low(self.we.state, threshold) {
                     // IO = Input
                     self.set_io_type(PinType::Input);
                     // read data on IO pins
-                    let addr = Pin::read_threshold(
-                        &[
-                            &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
-                            &self.a7, &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
-                        ],
-                        3.3,
-                    );
-                    self.ram[addr] = Pin::read_threshold(
+                    let addr = self.address(threshold);
+                    // check Write Protect (active low)
+                    if active_low(self.wp.state, threshold) {
+                        self.events.record(None);
+                        return;
+                    }
+                    let value = Pin::read_threshold(
                         &[
                             &self.io0, &self.io1, &self.io2, &self.io3, &self.io4, &self.io5,
                             &self.io6, &self.io7,
                         ],
-                        3.3,
+                        threshold,
                     ) as u8;
-                } else if self.oe.state == State::Low {
+                    self.ram[addr] = value;
+                    self.events.record(Some(MemoryEvent::Write {
+                        address: addr,
+                        value,
+                    }));
+                } else if active_low(self.oe.state, threshold) {
                     // IO = Output
                     self.set_io_type(PinType::Output);
 
                     // display data on IO pins
-                    let addr = Pin::read_threshold(
-                        &[
-                            &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
-                            &self.a7, &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
-                        ],
-                        3.3,
-                    );
+                    let addr = self.address(threshold);
                     Pin::write(
                         &mut [
                             &mut self.io0,
@@ -468,21 +800,32 @@ impl ChipRunner for Ram8KB {
                         ],
                         self.ram[addr] as usize,
                     );
+                    self.events.record(Some(MemoryEvent::Read {
+                        address: addr,
+                        value: self.ram[addr],
+                    }));
                 } else {
                     self.set_io_type(PinType::Floating);
+                    self.events.record(None);
                 }
             } else {
                 self.set_io_type(PinType::Floating);
+                self.events.record(None);
             }
         } else if self.powered {
             self.set_io_type(PinType::Floating);
             self.powered = false;
         }
     }
+
+    fn reset(&mut self) {
+        self.init.fill(&mut self.ram, &mut self.seed);
+    }
 }
 
-impl ToString for Ram8KB {
-    fn to_string(&self) -> std::string::String {
+impl fmt::Display for Ram8KB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let threshold = self.logic_threshold();
         let mut string = String::from(
             "  ADR| 00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n-----+------------------------------------------------",
         );
@@ -492,15 +835,7 @@ impl ToString for Ram8KB {
             }
             string.push_str(&format!(
                 "{}{byte:02X}",
-                if self.cs.state.as_logic(3.3) == State::Low
-                    && Pin::read_threshold(
-                        &[
-                            &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
-                            &self.a7, &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
-                        ],
-                        3.3
-                    ) == addr
-                {
+                if active_low(self.cs.state, threshold) && self.address(threshold) == addr {
                     ">"
                 } else {
                     " "
@@ -508,7 +843,7 @@ impl ToString for Ram8KB {
             ));
         }
         string.push('\n');
-        string
+        write!(f, "{string}")
     }
 }
 
@@ -519,7 +854,7 @@ impl ToString for Ram8KB {
 /// OE: Output Enable (active low)
 /// A0-7: Addresses
 /// IO0-7: Input/Output
-/// ```
+/// ```txt
 ///         ---__---
 ///   !CS --|1   22|-- VCC
 /// UNUSED--|2   21|-- UNUSED
@@ -599,10 +934,25 @@ impl Rom256B {
         self.rom.resize(256, 0);
         self
     }
+
+    /// Make sure that the loaded data matches the given checksum, see `checksum`
+    pub fn with_expected_checksum(self, expected: u32) -> Result<Self, ChecksumMismatch> {
+        let actual = self.checksum();
+        if actual == expected {
+            Ok(self)
+        } else {
+            Err(ChecksumMismatch { expected, actual })
+        }
+    }
+
+    /// Compute the CRC32 of the whole memory, including the zeroes padding the loaded data
+    pub fn checksum(&self) -> u32 {
+        crc32(&self.rom)
+    }
 }
 
 generate_chip!(
-    Rom256B,
+    Rom256B => Rom256BPin,
     cs: Rom256B::CS,
     oe: Rom256B::OE,
     a0: Rom256B::A0,
@@ -662,6 +1012,7 @@ impl From<Rom256B> for ChipSet {
 
 impl ChipRunner for Rom256B {
     fn run(&mut self, _: Duration) {
+        let threshold = self.vcc.state.rail_threshold(3.3);
         if self.vcc.state.as_logic(1.0) == State::High {
             if !self.powered {
                 self.powered = true;
@@ -681,7 +1032,7 @@ impl ChipRunner for Rom256B {
                             &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
                             &self.a7,
                         ],
-                        3.3,
+                        threshold,
                     );
                     Pin::write(
                         &mut [
@@ -709,8 +1060,9 @@ impl ChipRunner for Rom256B {
     }
 }
 
-impl ToString for Rom256B {
-    fn to_string(&self) -> std::string::String {
+impl fmt::Display for Rom256B {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let threshold = self.vcc.state.rail_threshold(3.3);
         let mut string = String::from(
             "ADR| 00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n---+------------------------------------------------",
         );
@@ -720,13 +1072,13 @@ impl ToString for Rom256B {
             }
             string.push_str(&format!(
                 "{}{byte:02X}",
-                if self.cs.state.as_logic(3.3) == State::Low
+                if self.cs.state.as_logic(threshold) == State::Low
                     && Pin::read_threshold(
                         &[
                             &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
                             &self.a7
                         ],
-                        3.3
+                        threshold
                     ) > 0
                 {
                     ">"
@@ -736,7 +1088,7 @@ impl ToString for Rom256B {
             ));
         }
         string.push('\n');
-        string
+        write!(f, "{string}")
     }
 }
 
@@ -748,7 +1100,7 @@ impl ToString for Rom256B {
 /// OE: Output Enable (active low)
 /// A0-12: Addresses
 /// IO0-7: Input/Output
-/// ```
+/// ```txt
 ///         ---__---
 ///   !CS --|1   26|-- VCC
 /// UNUSED--|2   25|-- IO7
@@ -770,6 +1122,10 @@ impl ToString for Rom256B {
 pub struct Rom8KB {
     powered: bool,
     rom: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    logic_threshold: Option<f32>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    address_mask: Option<usize>,
     pub vcc: Pin,
     pub gnd: Pin,
     pub cs: Pin,
@@ -824,6 +1180,36 @@ impl Rom8KB {
     pub const VCC: usize = 26;
     pub const GND: usize = 13;
 
+    /// Set the voltage above which the address, data and control inputs are considered high,
+    /// defaults to half of the VCC voltage, or 3.3 when VCC is a logic state
+    pub fn with_logic_threshold(mut self, threshold: f32) -> Self {
+        self.logic_threshold = Some(threshold);
+        self
+    }
+
+    fn logic_threshold(&self) -> f32 {
+        self.logic_threshold
+            .unwrap_or_else(|| self.vcc.state.rail_threshold(3.3))
+    }
+
+    /// Mask the address before accessing the memory, so that its content gets mirrored.
+    /// For instance a mask of 0x00FF repeats the first 256 bytes across the whole address range.
+    pub fn with_address_mask(mut self, mask: usize) -> Self {
+        self.address_mask = Some(mask);
+        self
+    }
+
+    /// Address given on A0-12, masked by the address mask
+    fn address(&self, threshold: f32) -> usize {
+        Pin::read_threshold(
+            &[
+                &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6, &self.a7,
+                &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
+            ],
+            threshold,
+        ) & self.address_mask.unwrap_or(0x1FFF)
+    }
+
     fn set_io_type(&mut self, pin_type: PinType) {
         self.io0.pin_type = pin_type;
         self.io1.pin_type = pin_type;
@@ -840,10 +1226,37 @@ impl Rom8KB {
         self.rom.resize(8192, 0);
         self
     }
+
+    /// Make sure that the loaded data matches the given checksum, see `checksum`
+    pub fn with_expected_checksum(self, expected: u32) -> Result<Self, ChecksumMismatch> {
+        let actual = self.checksum();
+        if actual == expected {
+            Ok(self)
+        } else {
+            Err(ChecksumMismatch { expected, actual })
+        }
+    }
+
+    /// Compute the CRC32 of the whole memory, including the zeroes padding the loaded data
+    pub fn checksum(&self) -> u32 {
+        crc32(&self.rom)
+    }
+
+    /// Dump the content of the memory in the Intel HEX format
+    pub fn to_intel_hex(&self) -> String {
+        to_intel_hex(&self.rom)
+    }
+
+    /// Build a ROM loaded with an Intel HEX image, the bytes it doesn't cover are left to zero
+    pub fn from_intel_hex(hex: &str) -> Result<Self, IntelHexError> {
+        let mut rom = Self::build();
+        load_intel_hex(hex, &mut rom.rom)?;
+        Ok(rom)
+    }
 }
 
 generate_chip!(
-    Rom8KB,
+    Rom8KB => Rom8KBPin,
     cs: Rom8KB::CS,
     oe: Rom8KB::OE,
     a0: Rom8KB::A0,
@@ -876,6 +1289,8 @@ impl ChipBuilder<Rom8KB> for Rom8KB {
         Rom8KB {
             powered: false,
             rom: Vec::from([0; 8192]),
+            logic_threshold: None,
+            address_mask: None,
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             cs: Pin::from(PinType::Input),
@@ -913,6 +1328,7 @@ impl From<Rom8KB> for ChipSet {
 
 impl ChipRunner for Rom8KB {
     fn run(&mut self, _: Duration) {
+        let threshold = self.logic_threshold();
         if self.vcc.state.as_logic(1.0) == State::High {
             if !self.powered {
                 self.powered = true;
@@ -920,20 +1336,14 @@ impl ChipRunner for Rom8KB {
             self.gnd.state = State::Low;
 
             // check Chip Select (active low)
-            if self.cs.state == State::Low {
+            if active_low(self.cs.state, threshold) {
                 // check Output Enable (active low)
-                if self.oe.state == State::Low {
+                if active_low(self.oe.state, threshold) {
                     // IO = Output
                     self.set_io_type(PinType::Output);
 
                     // display data on IO pins
-                    let addr = Pin::read_threshold(
-                        &[
-                            &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
-                            &self.a7, &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
-                        ],
-                        3.3,
-                    );
+                    let addr = self.address(threshold);
                     Pin::write(
                         &mut [
                             &mut self.io0,
@@ -960,8 +1370,9 @@ impl ChipRunner for Rom8KB {
     }
 }
 
-impl ToString for Rom8KB {
-    fn to_string(&self) -> std::string::String {
+impl fmt::Display for Rom8KB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let threshold = self.logic_threshold();
         let mut string = String::from(
             "  ADR| 00 01 02 03 04 05 06 07 08 09 0A 0B 0C 0D 0E 0F\n-----+------------------------------------------------",
         );
@@ -971,15 +1382,7 @@ impl ToString for Rom8KB {
             }
             string.push_str(&format!(
                 "{}{byte:02X}",
-                if self.cs.state.as_logic(3.3) == State::Low
-                    && Pin::read_threshold(
-                        &[
-                            &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
-                            &self.a7, &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
-                        ],
-                        3.3
-                    ) == addr
-                {
+                if active_low(self.cs.state, threshold) && self.address(threshold) == addr {
                     ">"
                 } else {
                     " "
@@ -987,6 +1390,587 @@ impl ToString for Rom8KB {
             ));
         }
         string.push('\n');
-        string
+        write!(f, "{string}")
+    }
+}
+
+/// # A banked ROM chip
+///
+/// A ROM bigger than the address space it's mapped in, seen through an 8KB window.
+/// Like a simple cartridge mapper, writing a byte anywhere in the window selects the bank
+/// visible in it, the bank number wrapping around the amount of banks.
+///
+/// # Diagram
+/// CS: Chip Select (active low)
+/// WE: Write Enable (active low), selects the bank given on IO0-7
+/// OE: Output Enable (active low)
+/// A0-12: Addresses
+/// IO0-7: Input/Output
+/// ```txt
+///        ---__---
+///  !CS --|1   26|-- VCC
+///  !WE --|2   25|-- IO7
+///  !OE --|3   24|-- IO6
+///   A0 --|4   23|-- IO5
+///   A1 --|5   22|-- IO4
+///   A2 --|6   21|-- IO3
+///   A3 --|7   20|-- IO2
+///   A4 --|8   19|-- IO1
+///   A5 --|9   18|-- IO0
+///   A6 --|10  17|-- A12
+///   A7 --|11  16|-- A11
+///   A8 --|12  15|-- A10
+///  GND --|13  14|-- A9
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BankedRom {
+    bank: usize,
+    rom: Vec<u8>,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub cs: Pin,
+    pub we: Pin,
+    pub oe: Pin,
+    pub a0: Pin,
+    pub a1: Pin,
+    pub a2: Pin,
+    pub a3: Pin,
+    pub a4: Pin,
+    pub a5: Pin,
+    pub a6: Pin,
+    pub a7: Pin,
+    pub a8: Pin,
+    pub a9: Pin,
+    pub a10: Pin,
+    pub a11: Pin,
+    pub a12: Pin,
+    pub io0: Pin,
+    pub io1: Pin,
+    pub io2: Pin,
+    pub io3: Pin,
+    pub io4: Pin,
+    pub io5: Pin,
+    pub io6: Pin,
+    pub io7: Pin,
+}
+
+impl BankedRom {
+    pub const CS: usize = 1;
+    pub const WE: usize = 2;
+    pub const OE: usize = 3;
+    pub const A0: usize = 4;
+    pub const A1: usize = 5;
+    pub const A2: usize = 6;
+    pub const A3: usize = 7;
+    pub const A4: usize = 8;
+    pub const A5: usize = 9;
+    pub const A6: usize = 10;
+    pub const A7: usize = 11;
+    pub const A8: usize = 12;
+    pub const A9: usize = 14;
+    pub const A10: usize = 15;
+    pub const A11: usize = 16;
+    pub const A12: usize = 17;
+    pub const IO0: usize = 18;
+    pub const IO1: usize = 19;
+    pub const IO2: usize = 20;
+    pub const IO3: usize = 21;
+    pub const IO4: usize = 22;
+    pub const IO5: usize = 23;
+    pub const IO6: usize = 24;
+    pub const IO7: usize = 25;
+    pub const VCC: usize = 26;
+    pub const GND: usize = 13;
+
+    /// Size of a bank, and of the window it's seen through
+    pub const BANK_SIZE: usize = 8192;
+
+    fn set_io_type(&mut self, pin_type: PinType) {
+        self.io0.pin_type = pin_type;
+        self.io1.pin_type = pin_type;
+        self.io2.pin_type = pin_type;
+        self.io3.pin_type = pin_type;
+        self.io4.pin_type = pin_type;
+        self.io5.pin_type = pin_type;
+        self.io6.pin_type = pin_type;
+        self.io7.pin_type = pin_type;
+    }
+
+    /// Load the content of the ROM, padded with zeroes up to a whole amount of banks
+    pub fn set_data(mut self, data: &[u8]) -> Self {
+        self.rom = Vec::from(data);
+        let banks = ((data.len() + Self::BANK_SIZE - 1) / Self::BANK_SIZE).max(1);
+        self.rom.resize(banks * Self::BANK_SIZE, 0);
+        self
+    }
+
+    /// Get the amount of banks
+    pub fn bank_count(&self) -> usize {
+        self.rom.len() / Self::BANK_SIZE
+    }
+
+    /// Get the bank currently visible in the window
+    pub fn bank(&self) -> usize {
+        self.bank
+    }
+}
+
+impl ChipBuilder<BankedRom> for BankedRom {
+    fn build() -> BankedRom {
+        BankedRom {
+            bank: 0,
+            rom: Vec::from([0; Self::BANK_SIZE]),
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            cs: Pin::from(PinType::Input),
+            we: Pin::from(PinType::Input),
+            oe: Pin::from(PinType::Input),
+            a0: Pin::from(PinType::Input),
+            a1: Pin::from(PinType::Input),
+            a2: Pin::from(PinType::Input),
+            a3: Pin::from(PinType::Input),
+            a4: Pin::from(PinType::Input),
+            a5: Pin::from(PinType::Input),
+            a6: Pin::from(PinType::Input),
+            a7: Pin::from(PinType::Input),
+            a8: Pin::from(PinType::Input),
+            a9: Pin::from(PinType::Input),
+            a10: Pin::from(PinType::Input),
+            a11: Pin::from(PinType::Input),
+            a12: Pin::from(PinType::Input),
+            io0: Pin::from(PinType::Floating),
+            io1: Pin::from(PinType::Floating),
+            io2: Pin::from(PinType::Floating),
+            io3: Pin::from(PinType::Floating),
+            io4: Pin::from(PinType::Floating),
+            io5: Pin::from(PinType::Floating),
+            io6: Pin::from(PinType::Floating),
+            io7: Pin::from(PinType::Floating),
+        }
+    }
+}
+
+impl From<BankedRom> for ChipSet {
+    fn from(value: BankedRom) -> Self {
+        ChipSet::BankedRom(value)
+    }
+}
+
+generate_chip!(
+    BankedRom => BankedRomPin,
+    cs: BankedRom::CS,
+    we: BankedRom::WE,
+    oe: BankedRom::OE,
+    a0: BankedRom::A0,
+    a1: BankedRom::A1,
+    a2: BankedRom::A2,
+    a3: BankedRom::A3,
+    a4: BankedRom::A4,
+    a5: BankedRom::A5,
+    a6: BankedRom::A6,
+    a7: BankedRom::A7,
+    a8: BankedRom::A8,
+    a9: BankedRom::A9,
+    a10: BankedRom::A10,
+    a11: BankedRom::A11,
+    a12: BankedRom::A12,
+    io0: BankedRom::IO0,
+    io1: BankedRom::IO1,
+    io2: BankedRom::IO2,
+    io3: BankedRom::IO3,
+    io4: BankedRom::IO4,
+    io5: BankedRom::IO5,
+    io6: BankedRom::IO6,
+    io7: BankedRom::IO7,
+    vcc: BankedRom::VCC,
+    gnd: BankedRom::GND
+);
+
+impl ChipRunner for BankedRom {
+    fn run(&mut self, _: Duration) {
+        let threshold = self.vcc.state.rail_threshold(3.3);
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+
+            // check Chip Select (active low)
+            if self.cs.state == State::Low {
+                // check Write Enable (active low)
+                if self.we.state == State::Low {
+                    // IO = Input
+                    self.set_io_type(PinType::Input);
+                    // select the bank given on IO pins
+                    self.bank = Pin::read_threshold(
+                        &[
+                            &self.io0, &self.io1, &self.io2, &self.io3, &self.io4, &self.io5,
+                            &self.io6, &self.io7,
+                        ],
+                        threshold,
+                    ) % self.bank_count();
+                } else if self.oe.state == State::Low {
+                    // IO = Output
+                    self.set_io_type(PinType::Output);
+
+                    // display data on IO pins
+                    let addr = Pin::read_threshold(
+                        &[
+                            &self.a0, &self.a1, &self.a2, &self.a3, &self.a4, &self.a5, &self.a6,
+                            &self.a7, &self.a8, &self.a9, &self.a10, &self.a11, &self.a12,
+                        ],
+                        threshold,
+                    );
+                    Pin::write(
+                        &mut [
+                            &mut self.io0,
+                            &mut self.io1,
+                            &mut self.io2,
+                            &mut self.io3,
+                            &mut self.io4,
+                            &mut self.io5,
+                            &mut self.io6,
+                            &mut self.io7,
+                        ],
+                        self.rom[self.bank * Self::BANK_SIZE + addr] as usize,
+                    );
+                } else {
+                    self.set_io_type(PinType::Floating);
+                }
+            } else {
+                self.set_io_type(PinType::Floating);
+            }
+        } else {
+            self.set_io_type(PinType::Floating);
+            self.bank = 0;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.bank = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_address(ram: &mut Ram8KB, addr: usize) {
+        Pin::write(
+            &mut [
+                &mut ram.a0,
+                &mut ram.a1,
+                &mut ram.a2,
+                &mut ram.a3,
+                &mut ram.a4,
+                &mut ram.a5,
+                &mut ram.a6,
+                &mut ram.a7,
+                &mut ram.a8,
+                &mut ram.a9,
+                &mut ram.a10,
+                &mut ram.a11,
+                &mut ram.a12,
+            ],
+            addr,
+        );
+    }
+
+    fn write(ram: &mut Ram8KB, addr: usize, value: u8) {
+        set_address(ram, addr);
+        ram.we.state = State::Low;
+        ram.oe.state = State::High;
+        Pin::write(
+            &mut [
+                &mut ram.io0,
+                &mut ram.io1,
+                &mut ram.io2,
+                &mut ram.io3,
+                &mut ram.io4,
+                &mut ram.io5,
+                &mut ram.io6,
+                &mut ram.io7,
+            ],
+            value as usize,
+        );
+        ram.run(Duration::from_millis(1));
+    }
+
+    fn read(ram: &mut Ram8KB, addr: usize) -> u8 {
+        set_address(ram, addr);
+        ram.we.state = State::High;
+        ram.oe.state = State::Low;
+        ram.run(Duration::from_millis(1));
+        Pin::read(&[
+            &ram.io0, &ram.io1, &ram.io2, &ram.io3, &ram.io4, &ram.io5, &ram.io6, &ram.io7,
+        ]) as u8
+    }
+
+    fn powered_ram(ram: Ram8KB) -> Ram8KB {
+        let mut ram = ram.with_init(RamInit::Zero);
+        ram.vcc.state = State::High;
+        ram.cs.state = State::Low;
+        ram.wp.state = State::High;
+        ram
+    }
+
+    fn power_up(ram: &mut Ram256B) {
+        ram.vcc.state = State::High;
+        ram.cs.state = State::High;
+        ram.run(Duration::from_millis(1));
+    }
+
+    #[test]
+    fn ram_init_fills_the_memory_on_power_up() {
+        let mut ram = Ram256B::build().with_init(RamInit::Zero);
+        power_up(&mut ram);
+        assert!(ram.data().iter().all(|byte| *byte == 0));
+
+        let mut ram = Ram256B::build().with_init(RamInit::Pattern(0xA5));
+        power_up(&mut ram);
+        assert!(ram.data().iter().all(|byte| *byte == 0xA5));
+    }
+
+    #[test]
+    fn ram_is_only_initialized_once_powered() {
+        let mut ram = Ram256B::build().with_init(RamInit::Random);
+        power_up(&mut ram);
+        let content = ram.data().to_vec();
+        for _ in 0..10 {
+            ram.run(Duration::from_millis(1));
+        }
+        assert_eq!(ram.data(), content);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_power_up_content() {
+        let mut a = Ram256B::build().with_seed(42);
+        let mut b = Ram256B::build().with_seed(42);
+        let mut c = Ram256B::build().with_seed(43);
+        power_up(&mut a);
+        power_up(&mut b);
+        power_up(&mut c);
+        assert_eq!(a.data(), b.data());
+        assert_ne!(a.data(), c.data());
+
+        // every power cycle gets new content, the same for both chips
+        let first = a.data().to_vec();
+        for ram in [&mut a, &mut b] {
+            ram.vcc.state = State::Low;
+            ram.run(Duration::from_millis(1));
+            power_up(ram);
+        }
+        assert_eq!(a.data(), b.data());
+        assert_ne!(a.data(), first);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn ram_saved_without_init_or_seed_still_loads() {
+        // RAM chips saved before `init` and `seed` existed don't have them
+        fn without_init(chip: impl serde::Serialize) -> String {
+            let mut value = serde_json::to_value(chip).unwrap();
+            let fields = value.as_object_mut().unwrap();
+            assert!(fields.remove("init").is_some() && fields.remove("seed").is_some());
+            value.to_string()
+        }
+        let ram: Ram256B = serde_json::from_str(&without_init(
+            Ram256B::build().with_init(RamInit::Zero).with_seed(42),
+        ))
+        .unwrap();
+        assert_eq!((ram.init, ram.seed), (RamInit::default(), None));
+        let ram: Ram8KB = serde_json::from_str(&without_init(
+            Ram8KB::build().with_init(RamInit::Zero).with_seed(42),
+        ))
+        .unwrap();
+        assert_eq!((ram.init, ram.seed), (RamInit::default(), None));
+    }
+
+    #[test]
+    fn address_mask_mirrors_the_memory() {
+        let mut ram = powered_ram(Ram8KB::build().with_address_mask(0xFF));
+        write(&mut ram, 0x100, 0x42);
+        assert_eq!(read(&mut ram, 0x000), 0x42);
+        write(&mut ram, 0x0FF, 0x24);
+        assert_eq!(read(&mut ram, 0x1FFF), 0x24);
+    }
+
+    #[test]
+    fn write_protect_blocks_writes() {
+        let mut ram = powered_ram(Ram8KB::build());
+        ram.wp.state = State::Low;
+        write(&mut ram, 0x10, 0x42);
+        assert_eq!(read(&mut ram, 0x10), 0x00);
+
+        ram.wp.state = State::High;
+        write(&mut ram, 0x10, 0x42);
+        assert_eq!(read(&mut ram, 0x10), 0x42);
+
+        // left unconnected
+        ram.wp.state = State::Undefined;
+        write(&mut ram, 0x11, 0x24);
+        assert_eq!(read(&mut ram, 0x11), 0x24);
+    }
+
+    #[test]
+    fn without_mask_every_address_is_distinct() {
+        let mut ram = powered_ram(Ram8KB::build());
+        write(&mut ram, 0x100, 0x42);
+        assert_eq!(read(&mut ram, 0x000), 0x00);
+        assert_eq!(read(&mut ram, 0x100), 0x42);
+    }
+
+    #[test]
+    fn rom_checksums_match_the_crc32_of_their_content() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        // the unused space is padded with zeroes and taken into account
+        let rom = Rom256B::build().set_data(b"123456789");
+        assert_eq!(rom.checksum(), 0x5E2D_328C);
+        let rom = Rom8KB::build().set_data(b"123456789");
+        assert_eq!(rom.checksum(), 0x6C3E_FBF8);
+
+        assert!(Rom8KB::build()
+            .set_data(b"123456789")
+            .with_expected_checksum(0x6C3E_FBF8)
+            .is_ok());
+        assert_eq!(
+            Rom256B::build()
+                .set_data(b"123456789")
+                .with_expected_checksum(0xCBF4_3926)
+                .unwrap_err(),
+            ChecksumMismatch {
+                expected: 0xCBF4_3926,
+                actual: 0x5E2D_328C,
+            }
+        );
+    }
+
+    #[test]
+    fn intel_hex_round_trip() {
+        let mut ram = powered_ram(Ram8KB::build())
+            .with_init(RamInit::Random)
+            .with_seed(42);
+        write(&mut ram, 0x0000, 0x12);
+        write(&mut ram, 0x1FFF, 0x34);
+
+        let hex = ram.to_intel_hex();
+        assert!(hex.starts_with(":1000000012"));
+        assert!(hex.ends_with(":00000001FF\n"));
+        let rom = Rom8KB::from_intel_hex(&hex).unwrap();
+        assert_eq!(rom.rom, ram.ram);
+        assert_eq!(rom.to_intel_hex(), hex);
+
+        assert!(matches!(
+            Rom8KB::from_intel_hex(":0100000012EE\n"),
+            Err(IntelHexError::BadChecksum(0))
+        ));
+        assert!(matches!(
+            Rom8KB::from_intel_hex(":0100000012ED\n:01200000AB34\n"),
+            Err(IntelHexError::OutOfBounds(1))
+        ));
+    }
+
+    #[test]
+    fn banked_rom_reads_the_selected_bank() {
+        let mut data = vec![0; BankedRom::BANK_SIZE * 3];
+        for bank in 0..3 {
+            data[bank * BankedRom::BANK_SIZE + 0x123] = 0x10 + bank as u8;
+        }
+        let mut rom = BankedRom::build().set_data(&data);
+        rom.vcc.state = State::High;
+        rom.cs.state = State::Low;
+        assert_eq!(rom.bank_count(), 3);
+
+        fn io(rom: &mut BankedRom) -> [&mut Pin; 8] {
+            [
+                &mut rom.io0,
+                &mut rom.io1,
+                &mut rom.io2,
+                &mut rom.io3,
+                &mut rom.io4,
+                &mut rom.io5,
+                &mut rom.io6,
+                &mut rom.io7,
+            ]
+        }
+        let select = |rom: &mut BankedRom, bank: usize| {
+            rom.we.state = State::Low;
+            rom.oe.state = State::High;
+            Pin::write(&mut io(rom), bank);
+            rom.run(Duration::from_millis(1));
+        };
+        let read = |rom: &mut BankedRom, addr: usize| {
+            rom.we.state = State::High;
+            rom.oe.state = State::Low;
+            Pin::write(
+                &mut [
+                    &mut rom.a0,
+                    &mut rom.a1,
+                    &mut rom.a2,
+                    &mut rom.a3,
+                    &mut rom.a4,
+                    &mut rom.a5,
+                    &mut rom.a6,
+                    &mut rom.a7,
+                    &mut rom.a8,
+                    &mut rom.a9,
+                    &mut rom.a10,
+                    &mut rom.a11,
+                    &mut rom.a12,
+                ],
+                addr,
+            );
+            rom.run(Duration::from_millis(1));
+            Pin::read(&io(rom).map(|pin| &*pin)) as u8
+        };
+
+        assert_eq!(read(&mut rom, 0x123), 0x10);
+        select(&mut rom, 2);
+        assert_eq!(read(&mut rom, 0x123), 0x12);
+        select(&mut rom, 1);
+        assert_eq!(read(&mut rom, 0x123), 0x11);
+        // the bank number wraps around the amount of banks
+        select(&mut rom, 5);
+        assert_eq!(rom.bank(), 2);
+        assert_eq!(read(&mut rom, 0x123), 0x12);
+    }
+
+    #[test]
+    fn logic_threshold_decides_how_addresses_are_read() {
+        let mut data = vec![0; 8192];
+        data[0x0155] = 0xAB;
+        let read_at_2v = |rom: Rom8KB| {
+            let mut rom = rom.set_data(&data);
+            rom.vcc.state = State::High;
+            rom.cs.state = State::Low;
+            rom.oe.state = State::Low;
+            let address = [
+                &mut rom.a0,
+                &mut rom.a1,
+                &mut rom.a2,
+                &mut rom.a3,
+                &mut rom.a4,
+                &mut rom.a5,
+                &mut rom.a6,
+                &mut rom.a7,
+                &mut rom.a8,
+                &mut rom.a9,
+                &mut rom.a10,
+                &mut rom.a11,
+                &mut rom.a12,
+            ];
+            for (bit, pin) in address.into_iter().enumerate() {
+                let voltage = if 0x0155 & (1 << bit) != 0 { 2.0 } else { 0.0 };
+                pin.state = State::Analog(voltage);
+            }
+            rom.run(Duration::from_millis(1));
+            Pin::read(&[
+                &rom.io0, &rom.io1, &rom.io2, &rom.io3, &rom.io4, &rom.io5, &rom.io6, &rom.io7,
+            ]) as u8
+        };
+
+        assert_eq!(read_at_2v(Rom8KB::build().with_logic_threshold(1.5)), 0xAB);
+        // at 3.3V, the 2V address lines all read as low
+        assert_eq!(read_at_2v(Rom8KB::build().with_logic_threshold(3.3)), 0x00);
     }
 }
@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use crate::{generate_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
+
+/// # A 1-to-8 demultiplexer
+///
+/// Routes D to the output selected by S0-S2, the other outputs are held low.
+/// Every output is held low while !EN is high.
+///
+/// # Diagram
+/// EN: Enable (active low)
+/// ```txt
+///          ---__---
+///     S0 --|1   16|-- VCC
+///     S1 --|2   15|-- Y0
+///     S2 --|3   14|-- Y1
+///    !EN --|4   13|-- Y2
+/// UNUSED --|5   12|-- Y3
+///      D --|6   11|-- Y4
+///     Y7 --|7   10|-- Y5
+///    GND --|8    9|-- Y6
+///          --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Demux1to8 {
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub s0: Pin,
+    pub s1: Pin,
+    pub s2: Pin,
+    pub en: Pin,
+    pub d: Pin,
+    pub y0: Pin,
+    pub y1: Pin,
+    pub y2: Pin,
+    pub y3: Pin,
+    pub y4: Pin,
+    pub y5: Pin,
+    pub y6: Pin,
+    pub y7: Pin,
+}
+
+impl Demux1to8 {
+    pub const S0: PinId = 1;
+    pub const S1: PinId = 2;
+    pub const S2: PinId = 3;
+    pub const EN: PinId = 4;
+    pub const D: PinId = 6;
+    pub const Y7: PinId = 7;
+    pub const GND: PinId = 8;
+    pub const Y6: PinId = 9;
+    pub const Y5: PinId = 10;
+    pub const Y4: PinId = 11;
+    pub const Y3: PinId = 12;
+    pub const Y2: PinId = 13;
+    pub const Y1: PinId = 14;
+    pub const Y0: PinId = 15;
+    pub const VCC: PinId = 16;
+}
+
+impl ChipBuilder<Demux1to8> for Demux1to8 {
+    fn build() -> Demux1to8 {
+        Demux1to8 {
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            s0: Pin::from(PinType::Input),
+            s1: Pin::from(PinType::Input),
+            s2: Pin::from(PinType::Input),
+            en: Pin::from(PinType::Input),
+            d: Pin::from(PinType::Input),
+            y0: Pin::from(PinType::Output),
+            y1: Pin::from(PinType::Output),
+            y2: Pin::from(PinType::Output),
+            y3: Pin::from(PinType::Output),
+            y4: Pin::from(PinType::Output),
+            y5: Pin::from(PinType::Output),
+            y6: Pin::from(PinType::Output),
+            y7: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<Demux1to8> for ChipSet {
+    fn from(value: Demux1to8) -> Self {
+        ChipSet::Demux1to8(value)
+    }
+}
+
+generate_chip!(
+    Demux1to8 => Demux1to8Pin,
+    vcc: Demux1to8::VCC,
+    gnd: Demux1to8::GND,
+    s0: Demux1to8::S0,
+    s1: Demux1to8::S1,
+    s2: Demux1to8::S2,
+    en: Demux1to8::EN,
+    d: Demux1to8::D,
+    y0: Demux1to8::Y0,
+    y1: Demux1to8::Y1,
+    y2: Demux1to8::Y2,
+    y3: Demux1to8::Y3,
+    y4: Demux1to8::Y4,
+    y5: Demux1to8::Y5,
+    y6: Demux1to8::Y6,
+    y7: Demux1to8::Y7
+);
+
+impl ChipRunner for Demux1to8 {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let select = Pin::read_threshold(&[&self.s0, &self.s1, &self.s2], threshold);
+            let enabled = self.en.state.as_logic(threshold) == State::Low;
+            let data = enabled && self.d.state.as_logic(threshold) == State::High;
+            Pin::write(
+                &mut [
+                    &mut self.y0,
+                    &mut self.y1,
+                    &mut self.y2,
+                    &mut self.y3,
+                    &mut self.y4,
+                    &mut self.y5,
+                    &mut self.y6,
+                    &mut self.y7,
+                ],
+                usize::from(data) << select,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Demux1to8;
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin},
+        State,
+    };
+
+    #[test]
+    fn data_only_reaches_the_selected_output() {
+        let mut demux = Demux1to8::build();
+        demux.vcc.state = State::High;
+        demux.en.state = State::Low;
+        demux.d.state = State::High;
+        let outputs = |demux: &Demux1to8| {
+            Pin::read(&[
+                &demux.y0, &demux.y1, &demux.y2, &demux.y3, &demux.y4, &demux.y5, &demux.y6,
+                &demux.y7,
+            ])
+        };
+
+        for select in 0..8 {
+            Pin::write(&mut [&mut demux.s0, &mut demux.s1, &mut demux.s2], select);
+            demux.run(Duration::from_millis(1));
+            assert_eq!(outputs(&demux), 1 << select, "Y{select}");
+
+            demux.d.state = State::Low;
+            demux.run(Duration::from_millis(1));
+            assert_eq!(outputs(&demux), 0, "Y{select}");
+            demux.d.state = State::High;
+        }
+
+        // every output stays low while disabled
+        demux.en.state = State::High;
+        demux.run(Duration::from_millis(1));
+        assert_eq!(outputs(&demux), 0);
+    }
+}
@@ -1,6 +1,12 @@
+pub mod analyzer;
 pub mod helpers;
+pub mod lcd;
 
+use std::fmt;
+
+pub use analyzer::*;
 pub use helpers::*;
+pub use lcd::*;
 
 use crate::{generate_chip, State};
 
@@ -16,7 +22,7 @@ use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
 ///  c -|3  |  |   |
 ///  d -|4   ──    |
 ///  e -|5  |  |   |
-///  f -|6   ──    |
+///  f -|6   ── .10|- DP
 ///  g -|7        8|- GND
 ///     ------------
 /// ```
@@ -28,8 +34,8 @@ use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
 /// f|   |b
 ///   ─g─
 /// e|   |c
-///   ───
-///    d
+///   ───  .
+///    d   DP
 /// ```
 ///
 #[derive(Debug, Clone)]
@@ -44,6 +50,14 @@ pub struct SegmentDisplay {
     pub e: Pin,
     pub f: Pin,
     pub g: Pin,
+    /// missing from the displays saved before it existed, it's then left unconnected
+    #[cfg_attr(feature = "serde", serde(default = "decimal_point_pin"))]
+    pub dp: Pin,
+}
+
+#[cfg(feature = "serde")]
+fn decimal_point_pin() -> Pin {
+    Pin::from(PinType::Input)
 }
 
 impl SegmentDisplay {
@@ -56,10 +70,11 @@ impl SegmentDisplay {
     pub const E: usize = 5;
     pub const F: usize = 6;
     pub const G: usize = 7;
+    pub const DP: usize = 10;
 }
 
 generate_chip!(
-    SegmentDisplay,
+    SegmentDisplay => SegmentDisplayPin,
     vcc: SegmentDisplay::VCC,
     gnd: SegmentDisplay::GND,
     a: SegmentDisplay::A,
@@ -68,12 +83,13 @@ generate_chip!(
     d: SegmentDisplay::D,
     e: SegmentDisplay::E,
     f: SegmentDisplay::F,
-    g: SegmentDisplay::G
+    g: SegmentDisplay::G,
+    dp: SegmentDisplay::DP
 );
 
-impl ChipBuilder<ChipSet> for SegmentDisplay {
-    fn build() -> ChipSet {
-        ChipSet::SegmentDisplay(SegmentDisplay {
+impl ChipBuilder<SegmentDisplay> for SegmentDisplay {
+    fn build() -> SegmentDisplay {
+        SegmentDisplay {
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             a: Pin::from(PinType::Input),
@@ -83,7 +99,14 @@ impl ChipBuilder<ChipSet> for SegmentDisplay {
             e: Pin::from(PinType::Input),
             f: Pin::from(PinType::Input),
             g: Pin::from(PinType::Input),
-        })
+            dp: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<SegmentDisplay> for ChipSet {
+    fn from(value: SegmentDisplay) -> Self {
+        ChipSet::SegmentDisplay(value)
     }
 }
 
@@ -95,26 +118,55 @@ impl ChipRunner for SegmentDisplay {
     }
 }
 
-impl ToString for SegmentDisplay {
-    fn to_string(&self) -> String {
+impl fmt::Display for SegmentDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.vcc.state.into() {
-            format!(
-                " {} \n{}  {}\n {} \n{}  {}\n {} ",
+            write!(
+                f,
+                " {} \n{}  {}\n {} \n{}  {}\n {}{}",
                 if self.a.state.into() { "──" } else { "  " },
                 if self.f.state.into() { "|" } else { " " },
                 if self.b.state.into() { "|" } else { " " },
                 if self.g.state.into() { "──" } else { "  " },
                 if self.e.state.into() { "|" } else { " " },
                 if self.c.state.into() { "|" } else { " " },
-                if self.d.state.into() { "──" } else { "  " }
+                if self.d.state.into() { "──" } else { "  " },
+                if self.dp.state.into() { "." } else { " " }
             )
         } else {
-            String::from("    \n    \n    \n    \n    ")
+            write!(f, "    \n    \n    \n    \n    ")
         }
     }
 }
 
 impl SegmentDisplay {
+    /// Render the display like its `Display` implementation, but showing the segments that
+    /// aren't clearly lit: `Undefined` segments are drawn with `?`, and analog segments below the
+    /// logic threshold but not off are drawn dimmed, which helps spotting floating drivers
+    pub fn render_states(&self) -> String {
+        if !bool::from(self.vcc.state) {
+            return String::from("    \n    \n    \n    \n    ");
+        }
+        let threshold = self.vcc.state.rail_threshold(3.3);
+        let horizontal = |pin: &Pin| segment_glyph(pin.state, threshold, "──", "--", "??", "  ");
+        let vertical = |pin: &Pin| segment_glyph(pin.state, threshold, "|", ":", "?", " ");
+        format!(
+            " {} \n{}  {}\n {} \n{}  {}\n {}{}",
+            horizontal(&self.a),
+            vertical(&self.f),
+            vertical(&self.b),
+            horizontal(&self.g),
+            vertical(&self.e),
+            vertical(&self.c),
+            horizontal(&self.d),
+            segment_glyph(self.dp.state, threshold, ".", ",", "?", " ")
+        )
+    }
+
+    pub fn decimal_point_lit(&self) -> bool {
+        bool::from(self.vcc.state) && bool::from(self.dp.state)
+    }
+
     pub fn as_char(&self) -> char {
         if self.vcc.state.into() {
             let segments = Pin::read(&[
@@ -164,3 +216,110 @@ impl SegmentDisplay {
         }
     }
 }
+
+/// Pick the glyph of a segment depending on how much it is lit
+fn segment_glyph(
+    state: State,
+    threshold: f32,
+    lit: &'static str,
+    dim: &'static str,
+    undefined: &'static str,
+    off: &'static str,
+) -> &'static str {
+    match state {
+        State::Undefined => undefined,
+        State::Low => off,
+        State::High => lit,
+        State::Analog(v) if v >= threshold => lit,
+        State::Analog(v) if v > 0.0 => dim,
+        State::Analog(_) => off,
+    }
+}
+
+/// Read a panel made of several displays, from left to right, as a string
+pub fn read_display_panel(chips: &[&SegmentDisplay]) -> String {
+    chips.iter().map(|chip| chip.as_char()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin},
+        State,
+    };
+    use std::time::Duration;
+
+    use super::{read_display_panel, SegmentDisplay};
+
+    /// A powered display showing the given segments, `a` being the lowest bit
+    fn display(segments: u8) -> SegmentDisplay {
+        let mut display = SegmentDisplay::build();
+        display.vcc.state = State::High;
+        Pin::write(
+            &mut [
+                &mut display.a,
+                &mut display.b,
+                &mut display.c,
+                &mut display.d,
+                &mut display.e,
+                &mut display.f,
+                &mut display.g,
+            ],
+            segments as usize,
+        );
+        display.run(Duration::from_millis(1));
+        display
+    }
+
+    #[test]
+    fn decimal_point_renders_when_driven() {
+        // a "1", lighting b and c
+        let mut display = display(0b0000110);
+        assert!(!display.decimal_point_lit());
+        assert!(display.to_string().ends_with(' '));
+
+        display.dp.state = State::High;
+        assert!(display.decimal_point_lit());
+        assert!(display.to_string().ends_with('.'));
+        assert_eq!(display.as_char(), '1');
+
+        // an unpowered display shows nothing
+        display.vcc.state = State::Low;
+        assert!(!display.decimal_point_lit());
+    }
+
+    #[test]
+    fn undefined_and_dimmed_segments_render_distinctly() {
+        let mut display = display(0b0000110);
+        display.g.state = State::Undefined;
+        display.dp.state = State::Analog(1.0);
+
+        assert_eq!(display.render_states(), "    \n   |\n ?? \n   |\n   ,");
+        // the other views still only show the lit segments
+        assert_eq!(display.as_char(), '1');
+        assert!(!display.to_string().contains('?'));
+    }
+
+    #[test]
+    fn panel_reads_its_displays_in_order() {
+        let digits = [0b0000110, 0b1011011, 0b1001111, 0b1100110].map(display);
+        let panel: Vec<_> = digits.iter().collect();
+        assert_eq!(read_display_panel(&panel), "1234");
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn display_saved_without_decimal_point_still_loads() {
+        use crate::chip::PinType;
+
+        let pin = |pin_type| format!(r#"{{"pin_type":"{pin_type}","state":"Undefined"}}"#);
+        let (input, output) = (pin("Input"), pin("Output"));
+        let saved = format!(
+            r#"{{"vcc":{input},"gnd":{output},"a":{input},"b":{input},"c":{input},"d":{input},"e":{input},"f":{input},"g":{input}}}"#
+        );
+
+        let display: SegmentDisplay = serde_json::from_str(&saved).unwrap();
+        assert!(matches!(display.dp.pin_type, PinType::Input));
+        assert_eq!(display.dp.state, State::Undefined);
+    }
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use crate::{
+    chip::{ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType},
+    generate_chip, State,
+};
+
+/// # An 8-channel logic analyzer
+///
+/// Records the value of D0-D7 on each rising edge of CLK, D0 being the lowest bit.
+/// Recording stops once `depth` samples have been captured, defaults to 4096.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///   CLK --|1   12|-- VCC
+///    D0 --|2   11|-- D7
+///    D1 --|3   10|-- D6
+///    D2 --|4    9|-- D5
+///    D3 --|5    8|-- D4
+///   GND --|6    7|-- UNUSED
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogicAnalyzer {
+    depth: usize,
+    clock: bool,
+    samples: Vec<usize>,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub clk: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+}
+
+impl LogicAnalyzer {
+    pub const CLK: PinId = 1;
+    pub const D0: PinId = 2;
+    pub const D1: PinId = 3;
+    pub const D2: PinId = 4;
+    pub const D3: PinId = 5;
+    pub const GND: PinId = 6;
+    pub const D4: PinId = 8;
+    pub const D5: PinId = 9;
+    pub const D6: PinId = 10;
+    pub const D7: PinId = 11;
+    pub const VCC: PinId = 12;
+
+    /// Set the maximum amount of samples to record
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Get the recorded samples, oldest first
+    pub fn samples(&self) -> &[usize] {
+        &self.samples
+    }
+
+    /// Discard the recorded samples to start a new capture
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+}
+
+impl ChipBuilder<LogicAnalyzer> for LogicAnalyzer {
+    fn build() -> LogicAnalyzer {
+        LogicAnalyzer {
+            depth: 4096,
+            clock: false,
+            samples: vec![],
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            clk: Pin::from(PinType::Input),
+            d0: Pin::from(PinType::Input),
+            d1: Pin::from(PinType::Input),
+            d2: Pin::from(PinType::Input),
+            d3: Pin::from(PinType::Input),
+            d4: Pin::from(PinType::Input),
+            d5: Pin::from(PinType::Input),
+            d6: Pin::from(PinType::Input),
+            d7: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<LogicAnalyzer> for ChipSet {
+    fn from(value: LogicAnalyzer) -> Self {
+        ChipSet::LogicAnalyzer(value)
+    }
+}
+
+generate_chip!(
+    LogicAnalyzer => LogicAnalyzerPin,
+    vcc: LogicAnalyzer::VCC,
+    gnd: LogicAnalyzer::GND,
+    clk: LogicAnalyzer::CLK,
+    d0: LogicAnalyzer::D0,
+    d1: LogicAnalyzer::D1,
+    d2: LogicAnalyzer::D2,
+    d3: LogicAnalyzer::D3,
+    d4: LogicAnalyzer::D4,
+    d5: LogicAnalyzer::D5,
+    d6: LogicAnalyzer::D6,
+    d7: LogicAnalyzer::D7
+);
+
+impl ChipRunner for LogicAnalyzer {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let clock: bool = self.clk.state.as_logic(threshold).into();
+            if clock && !self.clock && self.samples.len() < self.depth {
+                self.samples.push(Pin::read_threshold(
+                    &[
+                        &self.d0, &self.d1, &self.d2, &self.d3, &self.d4, &self.d5, &self.d6,
+                        &self.d7,
+                    ],
+                    threshold,
+                ));
+            }
+            self.clock = clock;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = false;
+        self.samples.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::LogicAnalyzer;
+    use crate::{
+        chip::{counters::UpDownCounter4Bit, ChipBuilder, ChipRunner},
+        State,
+    };
+
+    #[test]
+    fn samples_a_counting_bus() {
+        let mut counter = UpDownCounter4Bit::build();
+        let mut analyzer = LogicAnalyzer::build().with_depth(20);
+        counter.vcc.state = State::High;
+        analyzer.vcc.state = State::High;
+        for _ in 0..24 {
+            // the counter counts on the rising edge, the analyzer samples on the falling one
+            for clock in [true, false] {
+                counter.clk.state = State::from(clock);
+                counter.run(Duration::from_micros(1));
+                analyzer.d0.state = counter.q0.state;
+                analyzer.d1.state = counter.q1.state;
+                analyzer.d2.state = counter.q2.state;
+                analyzer.d3.state = counter.q3.state;
+                analyzer.clk.state = State::from(!clock);
+                analyzer.run(Duration::from_micros(1));
+            }
+        }
+
+        assert_eq!(analyzer.samples().len(), 20);
+        assert!(analyzer
+            .samples()
+            .iter()
+            .enumerate()
+            .all(|(i, sample)| *sample == (i + 1) % 16));
+    }
+}
@@ -58,7 +58,7 @@ impl SevenSegmentsDecoder {
 }
 
 generate_chip!(
-    SevenSegmentsDecoder,
+    SevenSegmentsDecoder => SevenSegmentsDecoderPin,
     vcc: SevenSegmentsDecoder::VCC,
     gnd: SevenSegmentsDecoder::GND,
     bi: SevenSegmentsDecoder::BI,
@@ -75,9 +75,9 @@ generate_chip!(
     og: SevenSegmentsDecoder::OG
 );
 
-impl ChipBuilder<ChipSet> for SevenSegmentsDecoder {
-    fn build() -> ChipSet {
-        ChipSet::SevenSegmentDecoder(SevenSegmentsDecoder {
+impl ChipBuilder<SevenSegmentsDecoder> for SevenSegmentsDecoder {
+    fn build() -> SevenSegmentsDecoder {
+        SevenSegmentsDecoder {
             vcc: Pin::from(PinType::Input),
             gnd: Pin::from(PinType::Output),
             bi: Pin::from(PinType::Input),
@@ -92,7 +92,13 @@ impl ChipBuilder<ChipSet> for SevenSegmentsDecoder {
             oe: Pin::from(PinType::Output),
             of: Pin::from(PinType::Output),
             og: Pin::from(PinType::Output),
-        })
+        }
+    }
+}
+
+impl From<SevenSegmentsDecoder> for ChipSet {
+    fn from(value: SevenSegmentsDecoder) -> Self {
+        ChipSet::SevenSegmentDecoder(value)
     }
 }
 
@@ -0,0 +1,276 @@
+use crate::{
+    chip::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType},
+    generate_chip, State,
+};
+
+/// Amount of characters stored per line in the display RAM
+const LINE_LENGTH: usize = 40;
+/// Amount of characters visible per line
+const VISIBLE_LENGTH: usize = 16;
+/// DDRAM address of the first character of the second line
+const SECOND_LINE_ADDR: u8 = 0x40;
+
+/// # 16x2 character LCD with a HD44780 parallel interface
+///
+/// RS: Register Select (low = command, high = character)
+/// RW: Read/Write (low = write), reading is not supported
+/// E: Enable, the data is latched on its falling edge
+///
+/// Supported commands: clear display, return home and set DDRAM address.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///    GND --|1   16|-- UNUSED
+///    VCC --|2   15|-- UNUSED
+/// UNUSED --|3   14|-- D7
+///     RS --|4   13|-- D6
+///     RW --|5   12|-- D5
+///      E --|6   11|-- D4
+///     D0 --|7   10|-- D3
+///     D1 --|8    9|-- D2
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LcdHd44780 {
+    enable: bool,
+    cursor: u8,
+    ddram: Vec<u8>,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub rs: Pin,
+    pub rw: Pin,
+    pub e: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+}
+
+impl LcdHd44780 {
+    pub const GND: usize = 1;
+    pub const VCC: usize = 2;
+    pub const RS: usize = 4;
+    pub const RW: usize = 5;
+    pub const E: usize = 6;
+    pub const D0: usize = 7;
+    pub const D1: usize = 8;
+    pub const D2: usize = 9;
+    pub const D3: usize = 10;
+    pub const D4: usize = 11;
+    pub const D5: usize = 12;
+    pub const D6: usize = 13;
+    pub const D7: usize = 14;
+
+    /// Get the visible content of both lines
+    pub fn lines(&self) -> [String; 2] {
+        [
+            self.ddram[..VISIBLE_LENGTH]
+                .iter()
+                .map(|&c| c as char)
+                .collect(),
+            self.ddram[LINE_LENGTH..LINE_LENGTH + VISIBLE_LENGTH]
+                .iter()
+                .map(|&c| c as char)
+                .collect(),
+        ]
+    }
+
+    fn ddram_index(address: u8) -> usize {
+        if address >= SECOND_LINE_ADDR {
+            LINE_LENGTH + (address - SECOND_LINE_ADDR) as usize % LINE_LENGTH
+        } else {
+            address as usize % LINE_LENGTH
+        }
+    }
+
+    /// Run a command, decoded from its highest set bit like the HD44780 does
+    fn execute(&mut self, command: u8) {
+        match command.leading_zeros() {
+            0 => {
+                // set DDRAM address
+                self.cursor = command & 0x7F;
+            }
+            6 => {
+                // return home
+                self.cursor = 0;
+            }
+            7 => {
+                // clear display
+                self.ddram.fill(b' ');
+                self.cursor = 0;
+            }
+            // set CGRAM address, function set, shift, display control and entry mode are
+            // not supported
+            _ => {}
+        }
+    }
+
+    fn write_char(&mut self, character: u8) {
+        self.ddram[Self::ddram_index(self.cursor)] = character;
+        self.cursor = match self.cursor + 1 {
+            end if end == LINE_LENGTH as u8 => SECOND_LINE_ADDR,
+            end if end >= SECOND_LINE_ADDR + LINE_LENGTH as u8 => 0,
+            next => next,
+        };
+    }
+}
+
+generate_chip!(
+    LcdHd44780 => LcdHd44780Pin,
+    vcc: LcdHd44780::VCC,
+    gnd: LcdHd44780::GND,
+    rs: LcdHd44780::RS,
+    rw: LcdHd44780::RW,
+    e: LcdHd44780::E,
+    d0: LcdHd44780::D0,
+    d1: LcdHd44780::D1,
+    d2: LcdHd44780::D2,
+    d3: LcdHd44780::D3,
+    d4: LcdHd44780::D4,
+    d5: LcdHd44780::D5,
+    d6: LcdHd44780::D6,
+    d7: LcdHd44780::D7
+);
+
+impl ChipBuilder<LcdHd44780> for LcdHd44780 {
+    fn build() -> LcdHd44780 {
+        LcdHd44780 {
+            enable: false,
+            cursor: 0,
+            ddram: vec![b' '; LINE_LENGTH * 2],
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            rs: Pin::from(PinType::Input),
+            rw: Pin::from(PinType::Input),
+            e: Pin::from(PinType::Input),
+            d0: Pin::from(PinType::Input),
+            d1: Pin::from(PinType::Input),
+            d2: Pin::from(PinType::Input),
+            d3: Pin::from(PinType::Input),
+            d4: Pin::from(PinType::Input),
+            d5: Pin::from(PinType::Input),
+            d6: Pin::from(PinType::Input),
+            d7: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<LcdHd44780> for ChipSet {
+    fn from(value: LcdHd44780) -> Self {
+        ChipSet::LcdHd44780(value)
+    }
+}
+
+impl ChipRunner for LcdHd44780 {
+    fn run(&mut self, _: std::time::Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let enable: bool = self.e.state.as_logic(threshold).into();
+            // data is latched on the falling edge of E
+            if self.enable && !enable && self.rw.state.as_logic(threshold) == State::Low {
+                let data = Pin::read_threshold(
+                    &[
+                        &self.d0, &self.d1, &self.d2, &self.d3, &self.d4, &self.d5, &self.d6,
+                        &self.d7,
+                    ],
+                    threshold,
+                ) as u8;
+                if self.rs.state.as_logic(threshold).into() {
+                    self.write_char(data);
+                } else {
+                    self.execute(data);
+                }
+            }
+            self.enable = enable;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.enable = false;
+        self.cursor = 0;
+        self.ddram.fill(b' ');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chip::{ChipBuilder, ChipRunner, Pin};
+    use crate::State;
+    use std::time::Duration;
+
+    use super::LcdHd44780;
+
+    fn send(lcd: &mut LcdHd44780, character: bool, data: u8) {
+        lcd.rs.state = State::from(character);
+        lcd.rw.state = State::Low;
+        Pin::write(
+            &mut [
+                &mut lcd.d0,
+                &mut lcd.d1,
+                &mut lcd.d2,
+                &mut lcd.d3,
+                &mut lcd.d4,
+                &mut lcd.d5,
+                &mut lcd.d6,
+                &mut lcd.d7,
+            ],
+            data as usize,
+        );
+        lcd.e.state = State::High;
+        lcd.run(Duration::from_micros(1));
+        lcd.e.state = State::Low;
+        lcd.run(Duration::from_micros(1));
+    }
+
+    #[test]
+    fn writes_a_string_after_the_init_sequence() {
+        let mut lcd = LcdHd44780::build();
+        lcd.vcc.state = State::High;
+        // function set, display on with cursor and blink, entry mode, clear
+        for command in [0x38, 0x0F, 0x0D, 0x06, 0x01] {
+            send(&mut lcd, false, command);
+        }
+        for &character in b"Hello" {
+            send(&mut lcd, true, character);
+        }
+        // none of these may move the cursor or clear the display
+        for command in [0x06, 0x0E, 0x0D, 0x41] {
+            send(&mut lcd, false, command);
+        }
+        send(&mut lcd, true, b'!');
+
+        assert_eq!(lcd.lines()[0], "Hello!          ");
+        assert_eq!(lcd.lines()[1].trim(), "");
+    }
+
+    #[test]
+    fn set_ddram_address_moves_to_the_second_line() {
+        let mut lcd = LcdHd44780::build();
+        lcd.vcc.state = State::High;
+        send(&mut lcd, false, 0x80 | 0x40);
+        for &character in b"Hi" {
+            send(&mut lcd, true, character);
+        }
+        send(&mut lcd, false, 0x02);
+        send(&mut lcd, true, b'A');
+
+        assert_eq!(lcd.lines()[0].trim(), "A");
+        assert_eq!(lcd.lines()[1].trim(), "Hi");
+    }
+
+    #[test]
+    fn runs_from_a_low_voltage_rail() {
+        let mut lcd = LcdHd44780::build();
+        lcd.vcc.state = State::Analog(2.0);
+        send(&mut lcd, true, b'A');
+        assert_eq!(lcd.lines()[0].trim(), "A");
+    }
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use crate::{generate_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
+
+/// # An 8-bit bidirectional GPIO port
+///
+/// Each pin is configured as an input or an output by the direction register, set using
+/// `set_direction` (a bit set to 1 makes the pin an output).
+/// Output pins drive the value given to `write`, input pins are sampled on each run
+/// and can be read back using `read`.
+///
+/// # Diagram
+/// ```txt
+///        ---__---
+///   P0 --|1   10|-- VCC
+///   P1 --|2    9|-- P7
+///   P2 --|3    8|-- P6
+///   P3 --|4    7|-- P5
+///  GND --|5    6|-- P4
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpioPort8 {
+    direction: u8,
+    output: u8,
+    input: u8,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub p0: Pin,
+    pub p1: Pin,
+    pub p2: Pin,
+    pub p3: Pin,
+    pub p4: Pin,
+    pub p5: Pin,
+    pub p6: Pin,
+    pub p7: Pin,
+}
+
+impl GpioPort8 {
+    pub const P0: usize = 1;
+    pub const P1: usize = 2;
+    pub const P2: usize = 3;
+    pub const P3: usize = 4;
+    pub const GND: usize = 5;
+    pub const P4: usize = 6;
+    pub const P5: usize = 7;
+    pub const P6: usize = 8;
+    pub const P7: usize = 9;
+    pub const VCC: usize = 10;
+
+    /// Configure the pins direction, a bit set to 1 makes the corresponding pin an output
+    pub fn set_direction(&mut self, mask: u8) {
+        self.direction = mask;
+    }
+
+    /// Set the value driven on the output pins, bits of input pins are ignored
+    pub fn write(&mut self, value: u8) {
+        self.output = value;
+    }
+
+    /// Read the port, input pins give their last sampled state and output pins
+    /// the value they're driving
+    pub fn read(&self) -> u8 {
+        (self.input & !self.direction) | (self.output & self.direction)
+    }
+
+    fn pins_mut(&mut self) -> [&mut Pin; 8] {
+        [
+            &mut self.p0,
+            &mut self.p1,
+            &mut self.p2,
+            &mut self.p3,
+            &mut self.p4,
+            &mut self.p5,
+            &mut self.p6,
+            &mut self.p7,
+        ]
+    }
+}
+
+impl ChipBuilder<GpioPort8> for GpioPort8 {
+    fn build() -> GpioPort8 {
+        GpioPort8 {
+            direction: 0,
+            output: 0,
+            input: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            p0: Pin::from(PinType::Input),
+            p1: Pin::from(PinType::Input),
+            p2: Pin::from(PinType::Input),
+            p3: Pin::from(PinType::Input),
+            p4: Pin::from(PinType::Input),
+            p5: Pin::from(PinType::Input),
+            p6: Pin::from(PinType::Input),
+            p7: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<GpioPort8> for ChipSet {
+    fn from(value: GpioPort8) -> Self {
+        ChipSet::GpioPort8(value)
+    }
+}
+
+generate_chip!(
+    GpioPort8 => GpioPort8Pin,
+    vcc: GpioPort8::VCC,
+    gnd: GpioPort8::GND,
+    p0: GpioPort8::P0,
+    p1: GpioPort8::P1,
+    p2: GpioPort8::P2,
+    p3: GpioPort8::P3,
+    p4: GpioPort8::P4,
+    p5: GpioPort8::P5,
+    p6: GpioPort8::P6,
+    p7: GpioPort8::P7
+);
+
+impl ChipRunner for GpioPort8 {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let (direction, output) = (self.direction, self.output);
+            let mut input = 0;
+            for (i, pin) in self.pins_mut().into_iter().enumerate() {
+                if direction & (1 << i) != 0 {
+                    pin.pin_type = PinType::Output;
+                    pin.state = State::from_bit(output, i as u8);
+                } else {
+                    pin.pin_type = PinType::Input;
+                    if pin.state.as_logic(threshold) == State::High {
+                        input |= 1 << i;
+                    }
+                }
+            }
+            self.input = input;
+        } else {
+            for pin in self.pins_mut() {
+                pin.pin_type = PinType::Input;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.direction = 0;
+        self.output = 0;
+        self.input = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, PinType},
+        State,
+    };
+
+    use super::GpioPort8;
+
+    #[test]
+    fn pins_follow_the_direction_register() {
+        let mut port = GpioPort8::build();
+        port.vcc.state = State::High;
+        // P0-3 are outputs, P4-7 inputs
+        port.set_direction(0x0F);
+        port.write(0xA5);
+        for pin in [&mut port.p0, &mut port.p4, &mut port.p6] {
+            pin.state = State::High;
+        }
+        port.run(Duration::from_millis(1));
+
+        assert!(matches!(port.p0.pin_type, PinType::Output));
+        assert!(matches!(port.p4.pin_type, PinType::Input));
+        // the outputs drive the low nibble of the written value, ignoring the high one
+        assert_eq!(
+            [port.p0.state, port.p1.state, port.p2.state, port.p3.state],
+            [State::High, State::Low, State::High, State::Low]
+        );
+        // the inputs are sampled, the outputs read back what they drive
+        assert_eq!(port.read(), 0x55);
+    }
+}
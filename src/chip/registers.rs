@@ -0,0 +1,560 @@
+use std::time::Duration;
+
+use crate::{generate_chip, State};
+
+use super::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType};
+
+/// # An 8-bit register
+///
+/// On the rising edge of CLK, the D0-7 inputs are captured if LOAD is high.
+/// The captured value is output on Q0-7 while OE is low, otherwise they're floating.
+///
+/// # Diagram
+/// OE: Output Enable (active low)
+/// LOAD: Load Enable (active high)
+/// ```txt
+///         ---__---
+///    !OE --|1   22|-- VCC
+///   LOAD --|2   21|-- CLK
+///     D0 --|3   20|-- Q0
+///     D1 --|4   19|-- Q1
+///     D2 --|5   18|-- Q2
+///     D3 --|6   17|-- Q3
+///     D4 --|7   16|-- Q4
+///     D5 --|8   15|-- Q5
+///     D6 --|9   14|-- Q6
+///     D7 --|10  13|-- Q7
+///    GND --|11  12|-- UNUSED
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Register8 {
+    clock: bool,
+    value: u8,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub oe: Pin,
+    pub load: Pin,
+    pub clk: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+    pub q0: Pin,
+    pub q1: Pin,
+    pub q2: Pin,
+    pub q3: Pin,
+    pub q4: Pin,
+    pub q5: Pin,
+    pub q6: Pin,
+    pub q7: Pin,
+}
+
+impl Register8 {
+    pub const OE: usize = 1;
+    pub const LOAD: usize = 2;
+    pub const D0: usize = 3;
+    pub const D1: usize = 4;
+    pub const D2: usize = 5;
+    pub const D3: usize = 6;
+    pub const D4: usize = 7;
+    pub const D5: usize = 8;
+    pub const D6: usize = 9;
+    pub const D7: usize = 10;
+    pub const GND: usize = 11;
+    pub const Q7: usize = 13;
+    pub const Q6: usize = 14;
+    pub const Q5: usize = 15;
+    pub const Q4: usize = 16;
+    pub const Q3: usize = 17;
+    pub const Q2: usize = 18;
+    pub const Q1: usize = 19;
+    pub const Q0: usize = 20;
+    pub const CLK: usize = 21;
+    pub const VCC: usize = 22;
+
+    /// Get the value currently held by the register
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    fn set_output_type(&mut self, pin_type: PinType) {
+        self.q0.pin_type = pin_type;
+        self.q1.pin_type = pin_type;
+        self.q2.pin_type = pin_type;
+        self.q3.pin_type = pin_type;
+        self.q4.pin_type = pin_type;
+        self.q5.pin_type = pin_type;
+        self.q6.pin_type = pin_type;
+        self.q7.pin_type = pin_type;
+    }
+}
+
+generate_chip!(
+    Register8 => Register8Pin,
+    vcc: Register8::VCC,
+    gnd: Register8::GND,
+    oe: Register8::OE,
+    load: Register8::LOAD,
+    clk: Register8::CLK,
+    d0: Register8::D0,
+    d1: Register8::D1,
+    d2: Register8::D2,
+    d3: Register8::D3,
+    d4: Register8::D4,
+    d5: Register8::D5,
+    d6: Register8::D6,
+    d7: Register8::D7,
+    q0: Register8::Q0,
+    q1: Register8::Q1,
+    q2: Register8::Q2,
+    q3: Register8::Q3,
+    q4: Register8::Q4,
+    q5: Register8::Q5,
+    q6: Register8::Q6,
+    q7: Register8::Q7
+);
+
+impl ChipBuilder<Register8> for Register8 {
+    fn build() -> Register8 {
+        Register8 {
+            clock: false,
+            value: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            oe: Pin::from(PinType::Input),
+            load: Pin::from(PinType::Input),
+            clk: Pin::from(PinType::Input),
+            d0: Pin::from(PinType::Input),
+            d1: Pin::from(PinType::Input),
+            d2: Pin::from(PinType::Input),
+            d3: Pin::from(PinType::Input),
+            d4: Pin::from(PinType::Input),
+            d5: Pin::from(PinType::Input),
+            d6: Pin::from(PinType::Input),
+            d7: Pin::from(PinType::Input),
+            q0: Pin::from(PinType::Floating),
+            q1: Pin::from(PinType::Floating),
+            q2: Pin::from(PinType::Floating),
+            q3: Pin::from(PinType::Floating),
+            q4: Pin::from(PinType::Floating),
+            q5: Pin::from(PinType::Floating),
+            q6: Pin::from(PinType::Floating),
+            q7: Pin::from(PinType::Floating),
+        }
+    }
+}
+
+impl From<Register8> for ChipSet {
+    fn from(value: Register8) -> Self {
+        ChipSet::Register8(value)
+    }
+}
+
+impl ChipRunner for Register8 {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let clock: bool = self.clk.state.as_logic(threshold).into();
+            if clock && !self.clock && self.load.state.as_logic(threshold) == State::High {
+                self.value = Pin::read_threshold(
+                    &[
+                        &self.d0, &self.d1, &self.d2, &self.d3, &self.d4, &self.d5, &self.d6,
+                        &self.d7,
+                    ],
+                    threshold,
+                ) as u8;
+            }
+            self.clock = clock;
+
+            // check Output Enable (active low)
+            if self.oe.state.as_logic(threshold) == State::Low {
+                self.set_output_type(PinType::Output);
+                Pin::write(
+                    &mut [
+                        &mut self.q0,
+                        &mut self.q1,
+                        &mut self.q2,
+                        &mut self.q3,
+                        &mut self.q4,
+                        &mut self.q5,
+                        &mut self.q6,
+                        &mut self.q7,
+                    ],
+                    self.value as usize,
+                );
+            } else {
+                self.set_output_type(PinType::Floating);
+            }
+        } else {
+            self.set_output_type(PinType::Floating);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.clock = false;
+        self.value = 0;
+    }
+}
+
+/// # An address/data demultiplexer
+///
+/// Splits a multiplexed AD0-7 bus into a latched low address byte and a data byte.
+/// The address is latched on the falling edge of ALE and is always output on A0-7.
+/// The latch is transparent while ALE is high.
+/// During the data phase (ALE low), AD0-7 is passed through to D0-7, otherwise D0-7 are floating.
+///
+/// # Diagram
+/// ALE: Address Latch Enable (active high)
+/// ```txt
+///         ---__---
+///    ALE --|1   28|-- VCC
+///    AD0 --|2   27|-- A0
+///    AD1 --|3   26|-- A1
+///    AD2 --|4   25|-- A2
+///    AD3 --|5   24|-- A3
+///    AD4 --|6   23|-- A4
+///    AD5 --|7   22|-- A5
+///    AD6 --|8   21|-- A6
+///    AD7 --|9   20|-- A7
+///     D0 --|10  19|-- D7
+///     D1 --|11  18|-- D6
+///     D2 --|12  17|-- D5
+///     D3 --|13  16|-- D4
+///    GND --|14  15|-- UNUSED
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DemuxAddressLatch {
+    address: u8,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub ale: Pin,
+    pub ad0: Pin,
+    pub ad1: Pin,
+    pub ad2: Pin,
+    pub ad3: Pin,
+    pub ad4: Pin,
+    pub ad5: Pin,
+    pub ad6: Pin,
+    pub ad7: Pin,
+    pub a0: Pin,
+    pub a1: Pin,
+    pub a2: Pin,
+    pub a3: Pin,
+    pub a4: Pin,
+    pub a5: Pin,
+    pub a6: Pin,
+    pub a7: Pin,
+    pub d0: Pin,
+    pub d1: Pin,
+    pub d2: Pin,
+    pub d3: Pin,
+    pub d4: Pin,
+    pub d5: Pin,
+    pub d6: Pin,
+    pub d7: Pin,
+}
+
+impl DemuxAddressLatch {
+    pub const ALE: usize = 1;
+    pub const AD0: usize = 2;
+    pub const AD1: usize = 3;
+    pub const AD2: usize = 4;
+    pub const AD3: usize = 5;
+    pub const AD4: usize = 6;
+    pub const AD5: usize = 7;
+    pub const AD6: usize = 8;
+    pub const AD7: usize = 9;
+    pub const D0: usize = 10;
+    pub const D1: usize = 11;
+    pub const D2: usize = 12;
+    pub const D3: usize = 13;
+    pub const GND: usize = 14;
+    pub const D4: usize = 16;
+    pub const D5: usize = 17;
+    pub const D6: usize = 18;
+    pub const D7: usize = 19;
+    pub const A7: usize = 20;
+    pub const A6: usize = 21;
+    pub const A5: usize = 22;
+    pub const A4: usize = 23;
+    pub const A3: usize = 24;
+    pub const A2: usize = 25;
+    pub const A1: usize = 26;
+    pub const A0: usize = 27;
+    pub const VCC: usize = 28;
+
+    /// Get the currently latched address byte
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    fn set_data_type(&mut self, pin_type: PinType) {
+        self.d0.pin_type = pin_type;
+        self.d1.pin_type = pin_type;
+        self.d2.pin_type = pin_type;
+        self.d3.pin_type = pin_type;
+        self.d4.pin_type = pin_type;
+        self.d5.pin_type = pin_type;
+        self.d6.pin_type = pin_type;
+        self.d7.pin_type = pin_type;
+    }
+}
+
+generate_chip!(
+    DemuxAddressLatch => DemuxAddressLatchPin,
+    vcc: DemuxAddressLatch::VCC,
+    gnd: DemuxAddressLatch::GND,
+    ale: DemuxAddressLatch::ALE,
+    ad0: DemuxAddressLatch::AD0,
+    ad1: DemuxAddressLatch::AD1,
+    ad2: DemuxAddressLatch::AD2,
+    ad3: DemuxAddressLatch::AD3,
+    ad4: DemuxAddressLatch::AD4,
+    ad5: DemuxAddressLatch::AD5,
+    ad6: DemuxAddressLatch::AD6,
+    ad7: DemuxAddressLatch::AD7,
+    a0: DemuxAddressLatch::A0,
+    a1: DemuxAddressLatch::A1,
+    a2: DemuxAddressLatch::A2,
+    a3: DemuxAddressLatch::A3,
+    a4: DemuxAddressLatch::A4,
+    a5: DemuxAddressLatch::A5,
+    a6: DemuxAddressLatch::A6,
+    a7: DemuxAddressLatch::A7,
+    d0: DemuxAddressLatch::D0,
+    d1: DemuxAddressLatch::D1,
+    d2: DemuxAddressLatch::D2,
+    d3: DemuxAddressLatch::D3,
+    d4: DemuxAddressLatch::D4,
+    d5: DemuxAddressLatch::D5,
+    d6: DemuxAddressLatch::D6,
+    d7: DemuxAddressLatch::D7
+);
+
+impl ChipBuilder<DemuxAddressLatch> for DemuxAddressLatch {
+    fn build() -> DemuxAddressLatch {
+        DemuxAddressLatch {
+            address: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            ale: Pin::from(PinType::Input),
+            ad0: Pin::from(PinType::Input),
+            ad1: Pin::from(PinType::Input),
+            ad2: Pin::from(PinType::Input),
+            ad3: Pin::from(PinType::Input),
+            ad4: Pin::from(PinType::Input),
+            ad5: Pin::from(PinType::Input),
+            ad6: Pin::from(PinType::Input),
+            ad7: Pin::from(PinType::Input),
+            a0: Pin::from(PinType::Output),
+            a1: Pin::from(PinType::Output),
+            a2: Pin::from(PinType::Output),
+            a3: Pin::from(PinType::Output),
+            a4: Pin::from(PinType::Output),
+            a5: Pin::from(PinType::Output),
+            a6: Pin::from(PinType::Output),
+            a7: Pin::from(PinType::Output),
+            d0: Pin::from(PinType::Floating),
+            d1: Pin::from(PinType::Floating),
+            d2: Pin::from(PinType::Floating),
+            d3: Pin::from(PinType::Floating),
+            d4: Pin::from(PinType::Floating),
+            d5: Pin::from(PinType::Floating),
+            d6: Pin::from(PinType::Floating),
+            d7: Pin::from(PinType::Floating),
+        }
+    }
+}
+
+impl From<DemuxAddressLatch> for ChipSet {
+    fn from(value: DemuxAddressLatch) -> Self {
+        ChipSet::DemuxAddressLatch(value)
+    }
+}
+
+impl ChipRunner for DemuxAddressLatch {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            let bus = Pin::read_threshold(
+                &[
+                    &self.ad0, &self.ad1, &self.ad2, &self.ad3, &self.ad4, &self.ad5, &self.ad6,
+                    &self.ad7,
+                ],
+                threshold,
+            );
+            if self.ale.state.as_logic(threshold) == State::High {
+                // address phase: the latch is transparent until ALE falls
+                self.address = bus as u8;
+                self.set_data_type(PinType::Floating);
+            } else {
+                // data phase: the address stays latched since ALE went low
+                self.set_data_type(PinType::Output);
+                Pin::write(
+                    &mut [
+                        &mut self.d0,
+                        &mut self.d1,
+                        &mut self.d2,
+                        &mut self.d3,
+                        &mut self.d4,
+                        &mut self.d5,
+                        &mut self.d6,
+                        &mut self.d7,
+                    ],
+                    bus,
+                );
+            }
+
+            Pin::write(
+                &mut [
+                    &mut self.a0,
+                    &mut self.a1,
+                    &mut self.a2,
+                    &mut self.a3,
+                    &mut self.a4,
+                    &mut self.a5,
+                    &mut self.a6,
+                    &mut self.a7,
+                ],
+                self.address as usize,
+            );
+        } else {
+            self.set_data_type(PinType::Floating);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.address = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        chip::{ChipBuilder, ChipRunner, Pin, PinType},
+        State,
+    };
+
+    use super::{DemuxAddressLatch, Register8};
+
+    fn set_inputs(register: &mut Register8, value: u8) {
+        Pin::write(
+            &mut [
+                &mut register.d0,
+                &mut register.d1,
+                &mut register.d2,
+                &mut register.d3,
+                &mut register.d4,
+                &mut register.d5,
+                &mut register.d6,
+                &mut register.d7,
+            ],
+            value as usize,
+        );
+    }
+
+    fn outputs(register: &Register8) -> Option<u8> {
+        let pins = [
+            &register.q0,
+            &register.q1,
+            &register.q2,
+            &register.q3,
+            &register.q4,
+            &register.q5,
+            &register.q6,
+            &register.q7,
+        ];
+        pins.iter()
+            .all(|pin| matches!(pin.pin_type, PinType::Output))
+            .then(|| Pin::read(&pins) as u8)
+    }
+
+    fn clock(register: &mut Register8) {
+        for state in [State::Low, State::High] {
+            register.clk.state = state;
+            register.run(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn register_holds_the_loaded_byte() {
+        let mut register = Register8::build();
+        register.vcc.state = State::High;
+        register.oe.state = State::Low;
+        register.load.state = State::High;
+        set_inputs(&mut register, 0x5A);
+        clock(&mut register);
+        assert_eq!(outputs(&register), Some(0x5A));
+
+        // the inputs are ignored until the next edge with LOAD high
+        register.load.state = State::Low;
+        set_inputs(&mut register, 0xC3);
+        clock(&mut register);
+        assert_eq!(outputs(&register), Some(0x5A));
+
+        // the outputs float while OE is high, and the value is kept
+        register.oe.state = State::High;
+        register.run(Duration::from_millis(1));
+        assert_eq!(outputs(&register), None);
+        register.oe.state = State::Low;
+        register.run(Duration::from_millis(1));
+        assert_eq!(outputs(&register), Some(0x5A));
+    }
+
+    fn set_bus(latch: &mut DemuxAddressLatch, value: u8) {
+        Pin::write(
+            &mut [
+                &mut latch.ad0,
+                &mut latch.ad1,
+                &mut latch.ad2,
+                &mut latch.ad3,
+                &mut latch.ad4,
+                &mut latch.ad5,
+                &mut latch.ad6,
+                &mut latch.ad7,
+            ],
+            value as usize,
+        );
+        latch.run(Duration::from_millis(1));
+    }
+
+    #[test]
+    fn address_phase_then_data_phase() {
+        let mut latch = DemuxAddressLatch::build();
+        latch.vcc.state = State::High;
+
+        // address phase: the latch follows the bus and the data outputs float
+        latch.ale.state = State::High;
+        set_bus(&mut latch, 0x34);
+        assert_eq!(latch.address(), 0x34);
+        assert!(matches!(latch.d0.pin_type, PinType::Floating));
+
+        // data phase: the address stays latched and the bus goes through to D0-7
+        latch.ale.state = State::Low;
+        set_bus(&mut latch, 0x34);
+        set_bus(&mut latch, 0xE7);
+        let address = Pin::read(&[
+            &latch.a0, &latch.a1, &latch.a2, &latch.a3, &latch.a4, &latch.a5, &latch.a6, &latch.a7,
+        ]);
+        let data = Pin::read(&[
+            &latch.d0, &latch.d1, &latch.d2, &latch.d3, &latch.d4, &latch.d5, &latch.d6, &latch.d7,
+        ]);
+        assert_eq!(address, 0x34);
+        assert_eq!(data, 0xE7);
+        assert!(matches!(latch.d0.pin_type, PinType::Output));
+    }
+}
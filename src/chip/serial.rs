@@ -0,0 +1,7 @@
+pub mod i2c;
+pub mod spi;
+pub mod uart;
+
+pub use i2c::*;
+pub use spi::*;
+pub use uart::*;
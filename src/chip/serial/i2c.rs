@@ -0,0 +1,574 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{
+    chip::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType},
+    generate_chip, State,
+};
+
+/// Number of registers of an [`I2cSlave`]
+pub const I2C_REGISTERS: usize = 16;
+
+/// Get the level of an open-drain line, a line that nobody pulls low is considered high
+fn line_level(pin: &Pin, threshold: f32) -> bool {
+    pin.state == State::Undefined || pin.state.as_logic(threshold) == State::High
+}
+
+/// Pull an open-drain line low, or release it
+fn drive_line(pin: &mut Pin, released: bool) {
+    if released {
+        pin.pin_type = PinType::Input;
+    } else {
+        pin.pin_type = PinType::Output;
+        pin.state = State::Low;
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Transaction {
+    Write {
+        address: u8,
+        register: u8,
+        value: u8,
+    },
+    Read {
+        address: u8,
+        register: u8,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum Sample {
+    Ack,
+    Data,
+}
+
+/// Levels held on the bus for half a clock period, `true` meaning that the line is released
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct BusStep {
+    scl: bool,
+    sda: bool,
+    /// SDA is sampled at the end of the step
+    sample: Option<Sample>,
+}
+
+impl BusStep {
+    fn new(scl: bool, sda: bool) -> Self {
+        BusStep {
+            scl,
+            sda,
+            sample: None,
+        }
+    }
+
+    fn start(steps: &mut VecDeque<BusStep>) {
+        steps.extend([
+            BusStep::new(false, true),
+            BusStep::new(true, true),
+            BusStep::new(true, false),
+        ]);
+    }
+
+    fn stop(steps: &mut VecDeque<BusStep>) {
+        steps.extend([
+            BusStep::new(false, false),
+            BusStep::new(true, false),
+            BusStep::new(true, true),
+        ]);
+    }
+
+    fn write_byte(steps: &mut VecDeque<BusStep>, byte: u8) {
+        for bit in (0..8).rev() {
+            let sda = byte & (1 << bit) != 0;
+            steps.extend([BusStep::new(false, sda), BusStep::new(true, sda)]);
+        }
+        steps.extend([
+            BusStep::new(false, true),
+            BusStep {
+                scl: true,
+                sda: true,
+                sample: Some(Sample::Ack),
+            },
+        ]);
+    }
+
+    fn read_byte(steps: &mut VecDeque<BusStep>, ack: bool) {
+        for _ in 0..8 {
+            steps.extend([
+                BusStep::new(false, true),
+                BusStep {
+                    scl: true,
+                    sda: true,
+                    sample: Some(Sample::Data),
+                },
+            ]);
+        }
+        steps.extend([BusStep::new(false, !ack), BusStep::new(true, !ack)]);
+    }
+}
+
+/// # An I2C bus controller
+///
+/// Drives the SCL and SDA open-drain lines to access the registers of [`I2cSlave`] chips.
+/// Transactions are queued using `write_register` and `read_register`, and are run one after
+/// the other at the clock rate given to `with_clock_rate` (defaults to 100kHz).
+/// Values read from the slaves are retrieved using `read_result`.
+/// Transactions that aren't acknowledged by a slave are aborted.
+///
+/// Released lines read as high, as if they were pulled up, the board needs to run with a step
+/// shorter than half a clock period.
+///
+/// # Diagram
+/// ```txt
+///        ---__---
+///  SDA --|1    4|-- VCC
+///  SCL --|2    3|-- GND
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct I2cController {
+    clock_rate: u32,
+    transactions: VecDeque<Transaction>,
+    steps: VecDeque<BusStep>,
+    elapsed: Duration,
+    shift: u8,
+    bits: usize,
+    results: VecDeque<u8>,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub sda: Pin,
+    pub scl: Pin,
+}
+
+impl I2cController {
+    pub const SDA: usize = 1;
+    pub const SCL: usize = 2;
+    pub const GND: usize = 3;
+    pub const VCC: usize = 4;
+
+    /// Set the SCL frequency, in Hz
+    pub fn with_clock_rate(mut self, clock_rate: u32) -> Self {
+        self.clock_rate = clock_rate.max(1);
+        self
+    }
+
+    /// Queue a write of `value` into the `register` of the slave at the given 7-bit address
+    pub fn write_register(&mut self, address: u8, register: u8, value: u8) {
+        self.transactions.push_back(Transaction::Write {
+            address,
+            register,
+            value,
+        });
+    }
+
+    /// Queue a read of the `register` of the slave at the given 7-bit address,
+    /// the value will be available from `read_result` once the transaction is done
+    pub fn read_register(&mut self, address: u8, register: u8) {
+        self.transactions
+            .push_back(Transaction::Read { address, register });
+    }
+
+    /// Take the oldest value read from a slave, if any
+    pub fn read_result(&mut self) -> Option<u8> {
+        self.results.pop_front()
+    }
+
+    /// Get whether transactions are still running or waiting to be run
+    pub fn is_busy(&self) -> bool {
+        !self.steps.is_empty() || !self.transactions.is_empty()
+    }
+
+    fn step_duration(&self) -> Duration {
+        Duration::from_secs(1) / self.clock_rate / 2
+    }
+
+    fn prepare(&mut self, transaction: Transaction) {
+        let steps = &mut self.steps;
+        BusStep::start(steps);
+        match transaction {
+            Transaction::Write {
+                address,
+                register,
+                value,
+            } => {
+                BusStep::write_byte(steps, address << 1);
+                BusStep::write_byte(steps, register);
+                BusStep::write_byte(steps, value);
+            }
+            Transaction::Read { address, register } => {
+                BusStep::write_byte(steps, address << 1);
+                BusStep::write_byte(steps, register);
+                BusStep::start(steps);
+                BusStep::write_byte(steps, address << 1 | 1);
+                BusStep::read_byte(steps, false);
+            }
+        }
+        BusStep::stop(steps);
+    }
+
+    /// Sample SDA at the end of a step
+    fn sample(&mut self, sample: Sample, threshold: f32) {
+        let level = line_level(&self.sda, threshold);
+        match sample {
+            Sample::Ack if level => {
+                // not acknowledged, abort the transaction
+                self.steps.clear();
+                BusStep::stop(&mut self.steps);
+            }
+            Sample::Ack => {}
+            Sample::Data => {
+                self.shift = self.shift << 1 | level as u8;
+                self.bits += 1;
+                if self.bits == 8 {
+                    self.results.push_back(self.shift);
+                    self.bits = 0;
+                }
+            }
+        }
+    }
+}
+
+impl ChipBuilder<I2cController> for I2cController {
+    fn build() -> I2cController {
+        I2cController {
+            clock_rate: 100_000,
+            transactions: VecDeque::new(),
+            steps: VecDeque::new(),
+            elapsed: Duration::ZERO,
+            shift: 0,
+            bits: 0,
+            results: VecDeque::new(),
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            sda: Pin::from(PinType::Input),
+            scl: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<I2cController> for ChipSet {
+    fn from(value: I2cController) -> Self {
+        ChipSet::I2cController(value)
+    }
+}
+
+generate_chip!(
+    I2cController => I2cControllerPin,
+    vcc: I2cController::VCC,
+    gnd: I2cController::GND,
+    sda: I2cController::SDA,
+    scl: I2cController::SCL
+);
+
+impl ChipRunner for I2cController {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            if self.steps.is_empty() {
+                if let Some(transaction) = self.transactions.pop_front() {
+                    self.prepare(transaction);
+                    self.elapsed = Duration::ZERO;
+                    self.bits = 0;
+                }
+            } else {
+                self.elapsed += tick_duration;
+                if self.elapsed >= self.step_duration() {
+                    self.elapsed = Duration::ZERO;
+                    if let Some(sample) = self.steps.pop_front().and_then(|step| step.sample) {
+                        self.sample(sample, threshold);
+                    }
+                }
+            }
+
+            let step = self
+                .steps
+                .front()
+                .copied()
+                .unwrap_or(BusStep::new(true, true));
+            drive_line(&mut self.scl, step.scl);
+            drive_line(&mut self.sda, step.sda);
+        } else {
+            drive_line(&mut self.scl, true);
+            drive_line(&mut self.sda, true);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.transactions.clear();
+        self.steps.clear();
+        self.results.clear();
+        self.bits = 0;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum SlaveState {
+    Idle,
+    Address,
+    Register,
+    Write,
+    Read,
+}
+
+/// # An I2C slave with a small register file
+///
+/// Answers to the given 7-bit address (defaults to 0x20, see `with_address`) on the SCL and
+/// SDA open-drain lines.
+/// A write transaction sends the register number, followed by values written to consecutive
+/// registers. A read transaction sends values from consecutive registers, starting at the
+/// last register number written.
+///
+/// # Diagram
+/// ```txt
+///        ---__---
+///  SDA --|1    4|-- VCC
+///  SCL --|2    3|-- GND
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct I2cSlave {
+    address: u8,
+    registers: [u8; I2C_REGISTERS],
+    pointer: usize,
+    state: SlaveState,
+    shift: u8,
+    bits: usize,
+    acking: bool,
+    prev_scl: bool,
+    prev_sda: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub sda: Pin,
+    pub scl: Pin,
+}
+
+impl I2cSlave {
+    pub const SDA: usize = 1;
+    pub const SCL: usize = 2;
+    pub const GND: usize = 3;
+    pub const VCC: usize = 4;
+
+    /// Set the 7-bit address the slave answers to
+    pub fn with_address(mut self, address: u8) -> Self {
+        self.address = address & 0x7F;
+        self
+    }
+
+    /// Get the value of a register, out of range registers read as 0
+    pub fn register(&self, register: usize) -> u8 {
+        self.registers.get(register).copied().unwrap_or(0)
+    }
+
+    /// Set the value of a register, out of range registers are ignored
+    pub fn set_register(&mut self, register: usize, value: u8) {
+        if let Some(r) = self.registers.get_mut(register) {
+            *r = value;
+        }
+    }
+
+    fn on_rising_edge(&mut self, sda: bool) {
+        match self.state {
+            SlaveState::Idle => {}
+            // acknowledge from the controller
+            SlaveState::Read if self.bits == 9 => {
+                if sda {
+                    self.state = SlaveState::Idle;
+                } else {
+                    self.pointer = (self.pointer + 1) % I2C_REGISTERS;
+                    self.bits = 0;
+                }
+            }
+            SlaveState::Read => {}
+            _ if !self.acking => {
+                self.shift = self.shift << 1 | sda as u8;
+                self.bits += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn on_falling_edge(&mut self) {
+        if self.acking {
+            self.acking = false;
+            self.bits = 0;
+            self.shift = 0;
+        } else if self.bits == 8 && self.state != SlaveState::Read {
+            self.acking = self.receive(self.shift);
+            if !self.acking {
+                self.state = SlaveState::Idle;
+            }
+            return;
+        }
+
+        if self.state == SlaveState::Read {
+            if self.bits < 8 {
+                self.bits += 1;
+            } else {
+                // release SDA for the controller to acknowledge
+                self.bits = 9;
+            }
+        }
+    }
+
+    /// Handle a received byte, and tell whether it's acknowledged
+    fn receive(&mut self, byte: u8) -> bool {
+        match self.state {
+            SlaveState::Address => {
+                if byte >> 1 != self.address {
+                    return false;
+                }
+                self.state = if byte & 1 == 1 {
+                    SlaveState::Read
+                } else {
+                    SlaveState::Register
+                };
+            }
+            SlaveState::Register => {
+                self.pointer = byte as usize % I2C_REGISTERS;
+                self.state = SlaveState::Write;
+            }
+            SlaveState::Write => {
+                self.registers[self.pointer] = byte;
+                self.pointer = (self.pointer + 1) % I2C_REGISTERS;
+            }
+            SlaveState::Idle | SlaveState::Read => return false,
+        }
+        true
+    }
+
+    /// Get whether the slave currently releases SDA
+    fn sda_released(&self) -> bool {
+        match self.state {
+            _ if self.acking => false,
+            SlaveState::Read if (1..=8).contains(&self.bits) => {
+                self.registers[self.pointer] & (1 << (8 - self.bits)) != 0
+            }
+            _ => true,
+        }
+    }
+}
+
+impl ChipBuilder<I2cSlave> for I2cSlave {
+    fn build() -> I2cSlave {
+        I2cSlave {
+            address: 0x20,
+            registers: [0; I2C_REGISTERS],
+            pointer: 0,
+            state: SlaveState::Idle,
+            shift: 0,
+            bits: 0,
+            acking: false,
+            prev_scl: true,
+            prev_sda: true,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            sda: Pin::from(PinType::Input),
+            scl: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<I2cSlave> for ChipSet {
+    fn from(value: I2cSlave) -> Self {
+        ChipSet::I2cSlave(value)
+    }
+}
+
+generate_chip!(
+    I2cSlave => I2cSlavePin,
+    vcc: I2cSlave::VCC,
+    gnd: I2cSlave::GND,
+    sda: I2cSlave::SDA,
+    scl: I2cSlave::SCL
+);
+
+impl ChipRunner for I2cSlave {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let scl = line_level(&self.scl, threshold);
+            let sda = line_level(&self.sda, threshold);
+
+            if scl && self.prev_scl && sda != self.prev_sda {
+                // SDA changing while SCL is high is either a start or a stop condition
+                self.state = if sda {
+                    SlaveState::Idle
+                } else {
+                    SlaveState::Address
+                };
+                self.shift = 0;
+                self.bits = 0;
+                self.acking = false;
+            } else if scl && !self.prev_scl {
+                self.on_rising_edge(sda);
+            } else if !scl && self.prev_scl {
+                self.on_falling_edge();
+            }
+            self.prev_scl = scl;
+            self.prev_sda = sda;
+
+            let released = self.sda_released();
+            drive_line(&mut self.sda, released);
+        } else {
+            drive_line(&mut self.sda, true);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.registers = [0; I2C_REGISTERS];
+        self.pointer = 0;
+        self.state = SlaveState::Idle;
+        self.acking = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{I2cController, I2cSlave};
+    use crate::{
+        board::Board,
+        chip::{generators::Generator, ChipBuilder, ChipSet},
+    };
+
+    #[test]
+    fn write_then_read_back_a_register() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let mut controller = I2cController::build();
+        controller.write_register(0x20, 3, 0x7E);
+        controller.read_register(0x20, 3);
+        // nobody answers at this address
+        controller.read_register(0x21, 3);
+        let controller = board.register_chip(controller.into());
+        let slave = board.register_chip(I2cSlave::build().into());
+        board.power_rail(
+            vcc,
+            &[(controller, I2cController::VCC), (slave, I2cSlave::VCC)],
+        );
+        board.connect(controller, I2cController::SDA, slave, I2cSlave::SDA);
+        board.connect(controller, I2cController::SCL, slave, I2cSlave::SCL);
+
+        board.run_during(Duration::from_millis(2), Duration::from_micros(1));
+
+        match board.get_chip(&slave) {
+            Some(ChipSet::I2cSlave(slave)) => assert_eq!(slave.register(3), 0x7E),
+            _ => unreachable!(),
+        }
+        let Some(ChipSet::I2cController(controller)) = board.get_chip_mut(&controller) else {
+            unreachable!()
+        };
+        assert!(!controller.is_busy());
+        assert_eq!(controller.read_result(), Some(0x7E));
+        assert_eq!(controller.read_result(), None);
+    }
+}
@@ -0,0 +1,335 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{
+    chip::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType},
+    generate_chip, State,
+};
+
+/// Frequency divided by the clock divider of a [`SpiMaster`] to get the SCLK frequency
+pub const SPI_BASE_CLOCK: u32 = 1_000_000;
+
+/// # An SPI master
+///
+/// Each byte queued with `transfer` is shifted out on MOSI, most significant bit first, while the
+/// byte sent back by the slave is shifted in from MISO (SPI mode 0: data is sampled on the
+/// rising edge of SCLK and changes on the falling edge).
+/// CS is held low while there are bytes to transfer.
+/// The byte sent back by the slave is available from `received` once the master isn't busy
+/// anymore.
+///
+/// SCLK runs at 1MHz divided by the clock divider given to `with_clock_divider` (defaults to 8),
+/// the board needs to run with a step shorter than half a SCLK period.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///   SCLK --|1    6|-- VCC
+///   MOSI --|2    5|-- CS
+///   MISO --|3    4|-- GND
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpiMaster {
+    clock_divider: u32,
+    queue: VecDeque<u8>,
+    /// byte being transferred, byte being received and current half clock period
+    current: Option<(u8, u8, usize)>,
+    elapsed: Duration,
+    received: u8,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub sclk: Pin,
+    pub mosi: Pin,
+    pub miso: Pin,
+    pub cs: Pin,
+}
+
+impl SpiMaster {
+    pub const SCLK: usize = 1;
+    pub const MOSI: usize = 2;
+    pub const MISO: usize = 3;
+    pub const GND: usize = 4;
+    pub const CS: usize = 5;
+    pub const VCC: usize = 6;
+
+    /// Set the divider applied to the 1MHz base clock to get the SCLK frequency
+    pub fn with_clock_divider(mut self, clock_divider: u32) -> Self {
+        self.clock_divider = clock_divider.max(1);
+        self
+    }
+
+    /// Queue a byte to be sent to the slave, the byte sent back can be read with `received`
+    /// once `is_busy` returns false
+    pub fn transfer(&mut self, byte: u8) {
+        self.queue.push_back(byte);
+    }
+
+    /// Get the byte received by the last completed transfer
+    pub fn received(&self) -> u8 {
+        self.received
+    }
+
+    /// Get whether bytes are still being transferred or waiting to be
+    pub fn is_busy(&self) -> bool {
+        self.current.is_some() || !self.queue.is_empty()
+    }
+
+    fn half_period(&self) -> Duration {
+        Duration::from_secs(1) / (SPI_BASE_CLOCK / self.clock_divider).max(1) / 2
+    }
+}
+
+impl ChipBuilder<SpiMaster> for SpiMaster {
+    fn build() -> SpiMaster {
+        SpiMaster {
+            clock_divider: 8,
+            queue: VecDeque::new(),
+            current: None,
+            elapsed: Duration::ZERO,
+            received: 0,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            sclk: Pin::from(PinType::Output),
+            mosi: Pin::from(PinType::Output),
+            miso: Pin::from(PinType::Input),
+            cs: Pin::from(PinType::Output),
+        }
+    }
+}
+
+impl From<SpiMaster> for ChipSet {
+    fn from(value: SpiMaster) -> Self {
+        ChipSet::SpiMaster(value)
+    }
+}
+
+generate_chip!(
+    SpiMaster => SpiMasterPin,
+    vcc: SpiMaster::VCC,
+    gnd: SpiMaster::GND,
+    sclk: SpiMaster::SCLK,
+    mosi: SpiMaster::MOSI,
+    miso: SpiMaster::MISO,
+    cs: SpiMaster::CS
+);
+
+impl ChipRunner for SpiMaster {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+
+            if let Some((byte, mut shift, mut half)) = self.current {
+                self.elapsed += tick_duration;
+                if self.elapsed >= self.half_period() {
+                    self.elapsed = Duration::ZERO;
+                    if half % 2 == 1 {
+                        // end of the high half, sample MISO
+                        let miso = self.miso.state.as_logic(threshold) == State::High;
+                        shift = shift << 1 | miso as u8;
+                    }
+                    half += 1;
+                }
+                if half < 16 {
+                    self.current = Some((byte, shift, half));
+                } else {
+                    self.received = shift;
+                    self.current = None;
+                }
+            }
+            if self.current.is_none() {
+                if let Some(byte) = self.queue.pop_front() {
+                    self.current = Some((byte, 0, 0));
+                    self.elapsed = Duration::ZERO;
+                }
+            }
+
+            if let Some((byte, _, half)) = self.current {
+                self.cs.state = State::Low;
+                self.sclk.state = State::from(half % 2 == 1);
+                self.mosi.state = State::from_bit(byte, 7 - (half / 2) as u8);
+            } else {
+                self.cs.state = State::High;
+                self.sclk.state = State::Low;
+                self.mosi.state = State::Low;
+            }
+        } else {
+            self.cs.state = State::Undefined;
+            self.sclk.state = State::Undefined;
+            self.mosi.state = State::Undefined;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.queue.clear();
+        self.current = None;
+        self.received = 0;
+    }
+}
+
+/// # An SPI slave shift register
+///
+/// While CS is low, MOSI is shifted into the register on the rising edge of SCLK, and the most
+/// significant bit of the register is output on MISO on the falling edge (SPI mode 0).
+/// After a full transfer the register holds the byte sent by the master, while the master
+/// received the byte that was previously in the register.
+/// MISO is floating while CS is high.
+///
+/// # Diagram
+/// ```txt
+///         ---__---
+///   SCLK --|1    6|-- VCC
+///   MOSI --|2    5|-- CS
+///   MISO --|3    4|-- GND
+///         --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpiSlave {
+    data: u8,
+    miso_bit: bool,
+    selected: bool,
+    clock: bool,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub sclk: Pin,
+    pub mosi: Pin,
+    pub miso: Pin,
+    pub cs: Pin,
+}
+
+impl SpiSlave {
+    pub const SCLK: usize = 1;
+    pub const MOSI: usize = 2;
+    pub const MISO: usize = 3;
+    pub const GND: usize = 4;
+    pub const CS: usize = 5;
+    pub const VCC: usize = 6;
+
+    /// Get the content of the shift register
+    pub fn data(&self) -> u8 {
+        self.data
+    }
+
+    /// Set the content of the shift register, to be sent during the next transfer
+    pub fn set_data(&mut self, data: u8) {
+        self.data = data;
+        self.miso_bit = data & 0x80 != 0;
+    }
+}
+
+impl ChipBuilder<SpiSlave> for SpiSlave {
+    fn build() -> SpiSlave {
+        SpiSlave {
+            data: 0,
+            miso_bit: false,
+            selected: false,
+            clock: false,
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            sclk: Pin::from(PinType::Input),
+            mosi: Pin::from(PinType::Input),
+            miso: Pin::from(PinType::Floating),
+            cs: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<SpiSlave> for ChipSet {
+    fn from(value: SpiSlave) -> Self {
+        ChipSet::SpiSlave(value)
+    }
+}
+
+generate_chip!(
+    SpiSlave => SpiSlavePin,
+    vcc: SpiSlave::VCC,
+    gnd: SpiSlave::GND,
+    sclk: SpiSlave::SCLK,
+    mosi: SpiSlave::MOSI,
+    miso: SpiSlave::MISO,
+    cs: SpiSlave::CS
+);
+
+impl ChipRunner for SpiSlave {
+    fn run(&mut self, _: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            let selected = self.cs.state.as_logic(threshold) == State::Low;
+            let clock = self.sclk.state.as_logic(threshold) == State::High;
+
+            if selected {
+                if !self.selected {
+                    self.miso_bit = self.data & 0x80 != 0;
+                } else if clock && !self.clock {
+                    let mosi = self.mosi.state.as_logic(threshold) == State::High;
+                    self.data = self.data << 1 | mosi as u8;
+                } else if !clock && self.clock {
+                    self.miso_bit = self.data & 0x80 != 0;
+                }
+                self.miso.pin_type = PinType::Output;
+                self.miso.state = State::from(self.miso_bit);
+            } else {
+                self.miso.pin_type = PinType::Floating;
+            }
+            self.selected = selected;
+            self.clock = clock;
+        } else {
+            self.miso.pin_type = PinType::Floating;
+            self.selected = false;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.data = 0;
+        self.miso_bit = false;
+        self.selected = false;
+        self.clock = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{SpiMaster, SpiSlave};
+    use crate::{
+        board::Board,
+        chip::{generators::Generator, ChipBuilder, ChipSet},
+    };
+
+    #[test]
+    fn full_duplex_transfer() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let master = board.register_chip(SpiMaster::build().into());
+        let mut slave = SpiSlave::build();
+        slave.set_data(0x3C);
+        let slave = board.register_chip(slave.into());
+        board.power_rail(vcc, &[(master, SpiMaster::VCC), (slave, SpiSlave::VCC)]);
+        board.connect(master, SpiMaster::SCLK, slave, SpiSlave::SCLK);
+        board.connect(master, SpiMaster::MOSI, slave, SpiSlave::MOSI);
+        board.connect(master, SpiMaster::MISO, slave, SpiSlave::MISO);
+        board.connect(master, SpiMaster::CS, slave, SpiSlave::CS);
+
+        if let Some(ChipSet::SpiMaster(master)) = board.get_chip_mut(&master) {
+            master.transfer(0xA5);
+        }
+        // 8 bits at 125kHz, with a step of a quarter of a SCLK period
+        board.run_during(Duration::from_micros(80), Duration::from_nanos(500));
+
+        match board.get_chip(&master) {
+            Some(ChipSet::SpiMaster(master)) => {
+                assert!(!master.is_busy());
+                assert_eq!(master.received(), 0x3C);
+            }
+            _ => unreachable!(),
+        }
+        match board.get_chip(&slave) {
+            Some(ChipSet::SpiSlave(slave)) => assert_eq!(slave.data(), 0xA5),
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{
+    chip::{ChipBuilder, ChipRunner, ChipSet, Pin, PinType},
+    generate_chip, State,
+};
+
+/// Number of bits in a frame: a start bit, 8 data bits and a stop bit
+const FRAME_BITS: usize = 10;
+
+/// # A serial UART
+///
+/// Bytes queued with `send_byte` are shifted out on TX, least significant bit first,
+/// framed by a start bit (low) and a stop bit (high). TX stays high while idle.
+/// Frames received on RX are sampled in the middle of each bit and made available to `recv_byte`,
+/// frames with an invalid stop bit are discarded.
+///
+/// The baud rate is set using `with_baud_rate` and defaults to 9600, the board needs to run with
+/// a step shorter than half a bit period for the frames to be properly received.
+///
+/// # Diagram
+/// ```txt
+///        ---__---
+///   TX --|1    4|-- VCC
+///   RX --|2    3|-- GND
+///        --------
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Uart {
+    baud_rate: u32,
+    tx_queue: VecDeque<u8>,
+    /// frame being sent and index of the bit currently on TX
+    tx_frame: Option<(u16, usize)>,
+    tx_elapsed: Duration,
+    /// frame being received and number of bits sampled so far
+    rx_frame: Option<(u16, usize)>,
+    rx_elapsed: Duration,
+    rx_buffer: VecDeque<u8>,
+    pub vcc: Pin,
+    pub gnd: Pin,
+    pub tx: Pin,
+    pub rx: Pin,
+}
+
+impl Uart {
+    pub const TX: usize = 1;
+    pub const RX: usize = 2;
+    pub const GND: usize = 3;
+    pub const VCC: usize = 4;
+
+    /// Set the baud rate, in bits per second
+    pub fn with_baud_rate(mut self, baud_rate: u32) -> Self {
+        self.baud_rate = baud_rate.max(1);
+        self
+    }
+
+    /// Queue a byte to be sent on TX
+    pub fn send_byte(&mut self, byte: u8) {
+        self.tx_queue.push_back(byte);
+    }
+
+    /// Take the oldest byte received on RX, if any
+    pub fn recv_byte(&mut self) -> Option<u8> {
+        self.rx_buffer.pop_front()
+    }
+
+    fn bit_period(&self) -> Duration {
+        Duration::from_secs(1) / self.baud_rate
+    }
+
+    fn transmit(&mut self, tick_duration: Duration) {
+        if self.tx_frame.is_none() {
+            if let Some(byte) = self.tx_queue.pop_front() {
+                self.tx_frame = Some(((byte as u16) << 1 | 1 << 9, 0));
+                self.tx_elapsed = Duration::ZERO;
+            }
+        }
+        let Some((frame, bit)) = self.tx_frame else {
+            self.tx.state = State::High;
+            return;
+        };
+        self.tx.state = State::from(frame & (1 << bit) != 0);
+
+        let period = self.bit_period();
+        let mut bit = bit;
+        self.tx_elapsed += tick_duration;
+        while self.tx_elapsed >= period && bit < FRAME_BITS {
+            self.tx_elapsed -= period;
+            bit += 1;
+        }
+        self.tx_frame = (bit < FRAME_BITS).then_some((frame, bit));
+    }
+
+    fn receive(&mut self, tick_duration: Duration, threshold: f32) {
+        let level = self.rx.state.as_logic(threshold) == State::High;
+        let Some((mut frame, mut bit)) = self.rx_frame else {
+            // wait for a start bit, a floating line is considered idle
+            if self.rx.state != State::Undefined && !level {
+                self.rx_frame = Some((0, 0));
+                self.rx_elapsed = Duration::ZERO;
+            }
+            return;
+        };
+
+        let period = self.bit_period();
+        self.rx_elapsed += tick_duration;
+        while bit < FRAME_BITS && self.rx_elapsed >= period * bit as u32 + period / 2 {
+            if level {
+                frame |= 1 << bit;
+            }
+            bit += 1;
+        }
+        if bit < FRAME_BITS {
+            self.rx_frame = Some((frame, bit));
+        } else {
+            if frame & 1 == 0 && frame & (1 << 9) != 0 {
+                self.rx_buffer.push_back((frame >> 1) as u8);
+            }
+            self.rx_frame = None;
+        }
+    }
+}
+
+impl ChipBuilder<Uart> for Uart {
+    fn build() -> Uart {
+        Uart {
+            baud_rate: 9600,
+            tx_queue: VecDeque::new(),
+            tx_frame: None,
+            tx_elapsed: Duration::ZERO,
+            rx_frame: None,
+            rx_elapsed: Duration::ZERO,
+            rx_buffer: VecDeque::new(),
+            vcc: Pin::from(PinType::Input),
+            gnd: Pin::from(PinType::Output),
+            tx: Pin::from(PinType::Output),
+            rx: Pin::from(PinType::Input),
+        }
+    }
+}
+
+impl From<Uart> for ChipSet {
+    fn from(value: Uart) -> Self {
+        ChipSet::Uart(value)
+    }
+}
+
+generate_chip!(
+    Uart => UartPin,
+    vcc: Uart::VCC,
+    gnd: Uart::GND,
+    tx: Uart::TX,
+    rx: Uart::RX
+);
+
+impl ChipRunner for Uart {
+    fn run(&mut self, tick_duration: Duration) {
+        if self.vcc.state.as_logic(1.0) == State::High {
+            self.gnd.state = State::Low;
+            let threshold = self.vcc.state.rail_threshold(3.3);
+            self.transmit(tick_duration);
+            self.receive(tick_duration, threshold);
+        } else {
+            self.tx.state = State::Undefined;
+        }
+    }
+
+    fn reset(&mut self) {
+        self.tx_queue.clear();
+        self.tx_frame = None;
+        self.rx_frame = None;
+        self.rx_buffer.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Uart;
+    use crate::{
+        board::Board,
+        chip::{generators::Generator, ChipBuilder, ChipSet},
+    };
+
+    #[test]
+    fn loopback_receives_the_sent_bytes() {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().into());
+        let mut uart = Uart::build().with_baud_rate(9600);
+        uart.send_byte(0x5A);
+        uart.send_byte(0x81);
+        let uart = board.register_chip(uart.into());
+        board.power_rail(vcc, &[(uart, Uart::VCC)]);
+        board.connect(uart, Uart::TX, uart, Uart::RX);
+
+        // two frames of 10 bits at 9600 bauds take about 2.1ms
+        board.run_during(Duration::from_millis(3), Duration::from_micros(10));
+
+        let Some(ChipSet::Uart(uart)) = board.get_chip_mut(&uart) else {
+            unreachable!()
+        };
+        assert_eq!(uart.recv_byte(), Some(0x5A));
+        assert_eq!(uart.recv_byte(), Some(0x81));
+        assert_eq!(uart.recv_byte(), None);
+    }
+}
@@ -0,0 +1,208 @@
+use std::time::Duration;
+
+use crate::{board::Board, utilities::Id, State};
+
+use super::{Chip, ChipBuilder, ChipRunner, ChipSet, Pin, PinId, PinType};
+
+/// # A subcircuit
+///
+/// Wraps a whole board into a single chip, so that a module built from several chips can be
+/// placed on a bigger board as one part.
+///
+/// Each pin of the subcircuit is mapped to one or more pins of the inner board with
+/// `with_pin`, the pins being numbered from 1 in the order they are added:
+/// - it's an output when one of the inner pins is an output, and takes the state of the inner
+///   outputs
+/// - it's an input otherwise, and forces its state onto all the inner pins
+///
+/// The inner board runs one step each time the subcircuit runs, so the signals take as many
+/// steps to go through the subcircuit as they would if its chips were placed on the outer board.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Subcircuit<C: Chip> {
+    board: Board<C>,
+    mapping: Vec<Vec<(Id<C>, PinId)>>,
+    pins: Vec<Pin>,
+}
+
+impl<C: Chip> Subcircuit<C> {
+    /// Wrap the given board, without any pin until they get mapped with `with_pin`
+    pub fn new(board: Board<C>) -> Self {
+        Subcircuit {
+            board,
+            mapping: Vec::new(),
+            pins: Vec::new(),
+        }
+    }
+
+    /// Add a pin to the subcircuit, mapped to the given pins of the inner board.
+    /// Its id is the number of pins added before it plus one.
+    pub fn with_pin(mut self, inner_pins: &[(Id<C>, PinId)]) -> Self {
+        self.mapping.push(inner_pins.to_vec());
+        self.pins.push(Pin::from(PinType::Floating));
+        self.update_pin(self.pins.len());
+        self
+    }
+
+    pub fn board(&self) -> &Board<C> {
+        &self.board
+    }
+
+    pub fn board_mut(&mut self) -> &mut Board<C> {
+        &mut self.board
+    }
+
+    /// Refresh the type and the state of a pin from the inner pins it's mapped to
+    fn update_pin(&mut self, pin: PinId) {
+        let (Some(inner_pins), Some(outer)) = (
+            self.mapping.get(pin.wrapping_sub(1)),
+            self.pins.get_mut(pin.wrapping_sub(1)),
+        ) else {
+            return;
+        };
+        let mut pin_type = PinType::Floating;
+        let mut state = State::Undefined;
+        for inner in inner_pins
+            .iter()
+            .filter_map(|(chip, pin)| self.board.get_chip(chip)?.get_pin(*pin))
+        {
+            match inner.pin_type {
+                PinType::Output => {
+                    pin_type = PinType::Output;
+                    state = state.feed_state(inner.state);
+                }
+                PinType::Input if !matches!(pin_type, PinType::Output) => {
+                    pin_type = PinType::Input;
+                }
+                _ => {}
+            }
+        }
+        outer.pin_type = pin_type;
+        if matches!(pin_type, PinType::Output) {
+            outer.state = state;
+        }
+    }
+}
+
+impl<C: Chip> ChipBuilder<Subcircuit<C>> for Subcircuit<C> {
+    fn build() -> Subcircuit<C> {
+        Subcircuit::new(Board::new())
+    }
+}
+
+impl From<Subcircuit<ChipSet>> for ChipSet {
+    fn from(value: Subcircuit<ChipSet>) -> Self {
+        ChipSet::Subcircuit(Box::new(value))
+    }
+}
+
+impl<C: Chip> Chip for Subcircuit<C> {
+    fn list_pins(&self) -> Vec<(PinId, &Pin)> {
+        self.pins
+            .iter()
+            .enumerate()
+            .map(|(index, pin)| (index + 1, pin))
+            .collect()
+    }
+
+    fn get_pin(&self, pin: PinId) -> Option<&Pin> {
+        self.pins.get(pin.wrapping_sub(1))
+    }
+
+    fn get_pin_mut(&mut self, pin: PinId) -> Option<&mut Pin> {
+        self.pins.get_mut(pin.wrapping_sub(1))
+    }
+
+    fn pin_count(&self) -> usize {
+        self.pins.len()
+    }
+}
+
+impl<C: Chip> ChipRunner for Subcircuit<C> {
+    fn run(&mut self, tick_duration: Duration) {
+        for (inner_pins, pin) in self.mapping.iter().zip(self.pins.iter()) {
+            if !matches!(pin.pin_type, PinType::Input) {
+                continue;
+            }
+            for (chip, inner_pin) in inner_pins {
+                self.board.force_pin(*chip, *inner_pin, pin.state);
+            }
+        }
+        self.board.run(tick_duration);
+        for pin in 1..=self.pins.len() {
+            self.update_pin(pin);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.board.reset_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        board::Board,
+        chip::{arithmetic::HalfAdder, gates::OrGate, generators::Generator, ChipBuilder, ChipSet},
+        State,
+    };
+
+    use super::Subcircuit;
+
+    /// A full adder made of two half adders and an OR gate, with pins A, B, CIN, SUM and COUT
+    fn full_adder() -> Subcircuit<ChipSet> {
+        let mut board: Board<ChipSet> = Board::new();
+        let vcc = board.register_chip(Generator::build().with_state(State::High).into());
+        let first = board.register_chip(HalfAdder::build().into());
+        let second = board.register_chip(HalfAdder::build().into());
+        let or = board.register_chip(OrGate::build().into());
+        board.connect_many(&[
+            (vcc, Generator::OUT),
+            (first, HalfAdder::VCC),
+            (second, HalfAdder::VCC),
+            (or, OrGate::VCC),
+        ]);
+        board.connect(first, HalfAdder::SUM, second, HalfAdder::A);
+        board.connect(first, HalfAdder::CARRY, or, OrGate::A);
+        board.connect(second, HalfAdder::CARRY, or, OrGate::B);
+        Subcircuit::new(board)
+            .with_pin(&[(first, HalfAdder::A)])
+            .with_pin(&[(first, HalfAdder::B)])
+            .with_pin(&[(second, HalfAdder::B)])
+            .with_pin(&[(second, HalfAdder::SUM)])
+            .with_pin(&[(or, OrGate::AB)])
+    }
+
+    #[test]
+    fn full_adder_subcircuit_adds_on_a_bigger_board() {
+        for inputs in 0..8u8 {
+            let mut board: Board<ChipSet> = Board::new();
+            let adder = board.register_chip(full_adder().into());
+            for bit in 0..3usize {
+                let state = State::from(inputs & (1 << bit) != 0);
+                let source = board.register_chip(Generator::build().with_state(state).into());
+                board.connect(source, Generator::OUT, adder, bit + 1);
+            }
+            let sum = board.connect_many(&[(adder, 4)]);
+            let carry = board.connect_many(&[(adder, 5)]);
+
+            // let the signals go through both half adders and the OR gate
+            for _ in 0..5 {
+                board.run(Duration::from_millis(1));
+            }
+            let total = inputs.count_ones();
+            assert_eq!(
+                board.get_trace_state(&sum),
+                Some(State::from(total & 1 != 0)),
+                "sum of {inputs:03b}"
+            );
+            assert_eq!(
+                board.get_trace_state(&carry),
+                Some(State::from(total >= 2)),
+                "carry of {inputs:03b}"
+            );
+        }
+    }
+}
@@ -2,6 +2,19 @@ pub mod board;
 pub mod chip;
 pub mod utilities;
 
+use std::ops::RangeInclusive;
+
+/// Supply rails that analog states are clamped to when they get fed to a trace,
+/// unless the board is given other rails with `Board::set_rails`
+pub const DEFAULT_RAILS: RangeInclusive<f32> = 0.0..=5.0;
+
+/// Tolerance used to compare analog states internally, see `State::approx_eq`
+pub const ANALOG_EPSILON: f32 = 1e-6;
+
+/// State of a pin.
+///
+/// Comparing analog states with `==` is exact, so two voltages that went through different
+/// calculations may not be equal, prefer `approx_eq` to compare them.
 #[derive(Debug, Default, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum State {
@@ -14,6 +27,17 @@ pub enum State {
 
 impl State {
     pub fn feed_state(&mut self, state: State) -> Self {
+        self.feed_state_within(state, &DEFAULT_RAILS)
+    }
+
+    /// Same as `feed_state`, with analog states saturating at the given supply rails.
+    /// A net can't go beyond the rails of the circuit powering it, and letting it do so would
+    /// make the chips reading it compare unphysical voltages to their thresholds.
+    pub fn feed_state_within(&mut self, state: State, rails: &RangeInclusive<f32>) -> Self {
+        let state = match state {
+            State::Analog(v) => State::Analog(v.clamp(*rails.start(), *rails.end())),
+            _ => state,
+        };
         match state {
             State::Low if matches!(self, State::Undefined) => State::Low,
             State::High => State::High,
@@ -33,6 +57,14 @@ impl State {
         }
     }
 
+    /// Compare two states, analog states being equal when they are at most `epsilon` volts apart
+    pub fn approx_eq(&self, other: &State, epsilon: f32) -> bool {
+        match (self, other) {
+            (State::Analog(a), State::Analog(b)) => (a - b).abs() <= epsilon,
+            _ => self == other,
+        }
+    }
+
     pub fn as_analog(&self, conversion_target: f32) -> Self {
         match self {
             State::Undefined | State::Low => State::Analog(0.0),
@@ -41,6 +73,25 @@ impl State {
         }
     }
 
+    /// Same as converting the state into a bool
+    pub fn as_bool(&self) -> bool {
+        bool::from(*self)
+    }
+
+    /// Get the state of a single bit of a byte, bits over 7 are Low
+    pub fn from_bit(byte: u8, bit: u8) -> Self {
+        State::from(byte.checked_shr(bit as u32).unwrap_or(0) & 1 == 1)
+    }
+
+    /// Get the logic threshold of a chip powered by this state: half of the rail voltage when
+    /// it is analog, or the given default otherwise
+    pub fn rail_threshold(&self, default: f32) -> f32 {
+        match self {
+            State::Analog(v) if *v > 0.0 => v / 2.0,
+            _ => default,
+        }
+    }
+
     pub fn as_logic(&self, threshold: f32) -> Self {
         match self {
             State::Undefined => State::Low,
@@ -75,3 +126,50 @@ impl From<bool> for State {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::State;
+
+    #[test]
+    fn from_bit_reads_each_bit_of_a_byte() {
+        let byte = 0b1010_0110;
+        let bits: Vec<_> = (0..8).map(|bit| State::from_bit(byte, bit)).collect();
+        assert_eq!(
+            bits,
+            [
+                State::Low,
+                State::High,
+                State::High,
+                State::Low,
+                State::Low,
+                State::High,
+                State::Low,
+                State::High
+            ]
+        );
+        assert_eq!(State::from_bit(0xFF, 8), State::Low);
+    }
+
+    #[test]
+    fn as_bool_of_undefined_and_analog_states() {
+        assert!(!State::Undefined.as_bool());
+        assert!(!State::Low.as_bool());
+        assert!(State::High.as_bool());
+        assert!(!State::Analog(0.0).as_bool());
+        assert!(State::Analog(1.2).as_bool());
+    }
+
+    #[test]
+    fn approx_eq_tolerates_tiny_differences() {
+        let computed = State::Analog(3.3 + 1e-5);
+        let expected = State::Analog(3.3);
+        assert_ne!(computed, expected);
+        assert!(computed.approx_eq(&expected, 1e-4));
+        assert!(!computed.approx_eq(&expected, 1e-6));
+
+        // other states still need to match exactly
+        assert!(State::High.approx_eq(&State::High, 1e-4));
+        assert!(!State::High.approx_eq(&State::Analog(3.3), 1e-4));
+    }
+}
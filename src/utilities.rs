@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, marker::PhantomData};
+use std::{collections::BTreeMap, fmt::Display, marker::PhantomData};
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -34,9 +34,10 @@ where
     }
 
     pub fn add(&mut self, value: T) -> Id<T> {
-        self.storage.insert(self.next_id, value);
+        let id = self.next_id;
+        self.storage.insert(id, value);
         self.next_id += 1;
-        Id(self.storage.len() - 1, PhantomData::default())
+        Id(id, PhantomData)
     }
 
     // this needs invalidating every Id instance
@@ -55,7 +56,7 @@ where
     pub fn as_vec(&self) -> Vec<(Id<T>, &T)> {
         let mut vec = vec![];
         for (id, value) in self.storage.iter() {
-            vec.push((Id(*id, PhantomData::default()), value));
+            vec.push((Id(*id, PhantomData), value));
         }
         vec
     }
@@ -63,7 +64,7 @@ where
     pub fn as_mut_vec(&mut self) -> Vec<(Id<T>, &mut T)> {
         let mut vec = vec![];
         for (id, value) in self.storage.iter_mut() {
-            vec.push((Id(*id, PhantomData::default()), value));
+            vec.push((Id(*id, PhantomData), value));
         }
         vec
     }
@@ -79,3 +80,12 @@ where
 }
 
 impl<T> Copy for Id<T> where T: Clone {}
+
+impl<T> Display for Id<T>
+where
+    T: Clone,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}